@@ -0,0 +1,8 @@
+-- Runtime allowed-host overrides per plugin (applied on top of the plugin TOML)
+CREATE TABLE IF NOT EXISTS plugin_host_overrides (
+  plugin      TEXT NOT NULL,
+  host        TEXT NOT NULL,
+  allowed     INTEGER NOT NULL,            -- 1 = added, 0 = removed
+  updated_at  DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY(plugin, host)
+);
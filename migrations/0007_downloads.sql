@@ -0,0 +1,13 @@
+-- Files written by downloads (per chapter/episode) with integrity data
+CREATE TABLE IF NOT EXISTS downloads (
+  id          INTEGER PRIMARY KEY AUTOINCREMENT,
+  unit_id     TEXT NOT NULL,               -- canonical chapter or episode id
+  kind        TEXT NOT NULL CHECK (kind IN ('chapter','episode')),
+  path        TEXT NOT NULL,
+  size_bytes  INTEGER NOT NULL,
+  sha256      TEXT NOT NULL,               -- lowercase hex digest
+  created_at  DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  UNIQUE(unit_id, path)
+);
+
+CREATE INDEX IF NOT EXISTS idx_downloads_unit ON downloads(unit_id);
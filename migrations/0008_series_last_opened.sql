@@ -0,0 +1,4 @@
+-- Track when the user last opened a series, separate from content freshness (updated_at)
+ALTER TABLE series ADD COLUMN last_opened_at INTEGER;
+
+CREATE INDEX IF NOT EXISTS idx_series_last_opened ON series(last_opened_at);
@@ -0,0 +1,13 @@
+-- Reading sessions: consecutive progress updates on a chapter with no long idle gap
+CREATE TABLE IF NOT EXISTS reading_sessions (
+  id             INTEGER PRIMARY KEY AUTOINCREMENT,
+  chapter_id     TEXT NOT NULL,
+  series_id      TEXT NOT NULL,
+  started_at     INTEGER NOT NULL,
+  last_update_at INTEGER NOT NULL,
+  FOREIGN KEY(chapter_id) REFERENCES chapters(id) ON DELETE CASCADE,
+  FOREIGN KEY(series_id) REFERENCES series(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_reading_sessions_chapter ON reading_sessions(chapter_id, last_update_at);
+CREATE INDEX IF NOT EXISTS idx_reading_sessions_series ON reading_sessions(series_id);
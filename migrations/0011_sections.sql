@@ -0,0 +1,27 @@
+-- Sections/arcs grouping chapters or episodes (UnitKind::Section)
+CREATE TABLE IF NOT EXISTS sections (
+  id           TEXT PRIMARY KEY,
+  series_id    TEXT NOT NULL,
+  source_id    TEXT NOT NULL,
+  external_id  TEXT NOT NULL,
+  number_text  TEXT,
+  number_num   REAL,
+  title        TEXT,
+  lang         TEXT,
+  position     INTEGER NOT NULL DEFAULT 0,   -- order as reported by the source
+  updated_at   DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  UNIQUE(series_id, source_id, external_id),
+  FOREIGN KEY(series_id) REFERENCES series(id) ON DELETE CASCADE
+);
+
+-- Membership of chapters/episodes in a section (unit_id is a canonical chapter or episode id)
+CREATE TABLE IF NOT EXISTS section_units (
+  section_id  TEXT NOT NULL,
+  unit_id     TEXT NOT NULL,
+  position    INTEGER NOT NULL DEFAULT 0,
+  PRIMARY KEY(section_id, unit_id),
+  FOREIGN KEY(section_id) REFERENCES sections(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_sections_series ON sections(series_id, position);
+CREATE INDEX IF NOT EXISTS idx_section_units_unit ON section_units(unit_id);
@@ -0,0 +1,4 @@
+-- LRU bookkeeping for size-bounded cache pruning (epoch seconds; 0 = never read since upgrade)
+ALTER TABLE search_cache ADD COLUMN last_accessed_at INTEGER NOT NULL DEFAULT 0;
+
+CREATE INDEX IF NOT EXISTS idx_search_cache_accessed ON search_cache(last_accessed_at);
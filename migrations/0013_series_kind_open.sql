@@ -0,0 +1,28 @@
+-- Allow any non-empty series kind (e.g. "novel" from MediaType::Other), not just manga/anime.
+-- SQLite can't drop a CHECK in place, so rebuild the table. Runs with foreign keys off
+-- (see Database::run_migrations) so dropping the old table doesn't cascade to children.
+CREATE TABLE series_new (
+  id             TEXT PRIMARY KEY,
+  kind           TEXT NOT NULL CHECK (kind <> ''),
+  title          TEXT NOT NULL,
+  alt_titles     TEXT,
+  description    TEXT,
+  cover_url      TEXT,
+  tags           TEXT,
+  status         TEXT,
+  created_at     DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  updated_at     DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  last_opened_at INTEGER
+);
+
+INSERT INTO series_new (id, kind, title, alt_titles, description, cover_url, tags, status,
+                        created_at, updated_at, last_opened_at)
+SELECT id, kind, title, alt_titles, description, cover_url, tags, status,
+       created_at, updated_at, last_opened_at
+FROM series;
+
+DROP TABLE series;
+ALTER TABLE series_new RENAME TO series;
+
+CREATE INDEX IF NOT EXISTS idx_series_kind_title ON series(kind, title);
+CREATE INDEX IF NOT EXISTS idx_series_last_opened ON series(last_opened_at);
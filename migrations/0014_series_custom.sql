@@ -0,0 +1,11 @@
+-- User-defined key/value metadata per series (personal rating, shelf, notes)
+CREATE TABLE IF NOT EXISTS series_custom (
+  series_id   TEXT NOT NULL,
+  key         TEXT NOT NULL,
+  value       TEXT NOT NULL,
+  updated_at  DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY(series_id, key),
+  FOREIGN KEY(series_id) REFERENCES series(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_series_custom_key_value ON series_custom(key, value);
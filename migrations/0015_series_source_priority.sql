@@ -0,0 +1,8 @@
+-- Per-series order in which sources are tried for chapter pages, ahead of the global order
+CREATE TABLE IF NOT EXISTS series_source_priority (
+  series_id   TEXT NOT NULL,
+  source_id   TEXT NOT NULL,
+  position    INTEGER NOT NULL,
+  PRIMARY KEY(series_id, source_id),
+  FOREIGN KEY(series_id) REFERENCES series(id) ON DELETE CASCADE
+);
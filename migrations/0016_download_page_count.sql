@@ -0,0 +1,2 @@
+-- Pages in a recorded archive (known for imported CBZs); NULL when not counted
+ALTER TABLE downloads ADD COLUMN page_count INTEGER;
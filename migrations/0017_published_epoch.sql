@@ -0,0 +1,11 @@
+-- `published_at` parsed to unix seconds on write (RFC 3339, plain dates, relative strings)
+ALTER TABLE chapters ADD COLUMN published_epoch INTEGER;
+ALTER TABLE episodes ADD COLUMN published_epoch INTEGER;
+
+-- Backfill absolute dates; relative strings ("2 days ago") are filled on the next refetch
+UPDATE chapters SET published_epoch = CAST(strftime('%s', published_at) AS INTEGER)
+WHERE published_at LIKE '____-__-__%' AND strftime('%s', published_at) IS NOT NULL;
+UPDATE episodes SET published_epoch = CAST(strftime('%s', published_at) AS INTEGER)
+WHERE published_at LIKE '____-__-__%' AND strftime('%s', published_at) IS NOT NULL;
+
+CREATE INDEX IF NOT EXISTS idx_chapters_series_published ON chapters(series_id, published_epoch);
@@ -1,14 +1,52 @@
 use anyhow::Result;
+use futures::stream::{self, StreamExt};
+use sqlx::AnyPool;
 use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
 
 use crate::dao;
 use crate::db::Database;
-use crate::mapping::{chapter_insert_from_unit, series_insert_from_media, series_source_from};
+use crate::error::TouringError;
+use crate::events::{EventBus, LibraryEvent};
+use crate::mapping::{
+    chapter_insert_from_unit, episode_insert_from_unit, group_units_by_section,
+    section_insert_from_unit, series_insert_from_media, series_source_from,
+};
 use crate::plugins::{
-    Asset, Media, MediaType, PluginManager, ProviderCapabilities, Unit, UnitKind,
+    Asset, Media, MediaType, PluginConfig, PluginManager, PluginStatus, ProviderCapabilities, Unit,
+    UnitKind,
 };
 use crate::storage::Storage;
-use crate::types::{media_from_cache, media_to_cache, MediaCache, SearchEntry}; // trait for get_cache/put_cache
+use crate::types::{
+    decode_cache, media_from_cache, media_to_cache, CacheCodec, MediaCache, SearchEntry,
+}; // trait for get_cache/put_cache
+
+/// Sources searched at once by the cached search path.
+const SEARCH_SOURCE_CONCURRENCY: usize = 8;
+
+// Outcome of one source's part of a search
+enum SourceSearch {
+    Results(Vec<Media>),
+    // Offline with nothing cached
+    Skipped,
+    Failed,
+}
+
+// Last assembled per-plugin answer (capabilities, allowed hosts) and when it was taken
+type PluginInfoCache<T> = std::sync::Mutex<Option<(Instant, Vec<(String, T)>)>>;
+
+/// Streams returned by `get_episode_streams_for_series`.
+#[derive(Debug, Clone)]
+pub struct EpisodeStreams {
+    pub streams: Vec<Asset>,
+    /// Plugin that served the streams.
+    pub source_id: Option<String>,
+    /// Canonical episode the streams were saved under; `None` means nothing was persisted.
+    pub episode_id: Option<String>,
+}
 
 /// Aggregator owns database + plugins and provides higher-level cached & persisted operations.
 pub struct Aggregator {
@@ -17,6 +55,17 @@ pub struct Aggregator {
     // TTLs (seconds)
     search_ttl_secs: i64,
     pages_ttl_secs: i64,
+    cache_codec: std::sync::RwLock<CacheCodec>,
+    // Serve cache/database only; never call plugins
+    offline: AtomicBool,
+    events: EventBus,
+    // Upsert search results on a spawned task instead of before returning them
+    background_persist: AtomicBool,
+    pending_persistence: Arc<std::sync::Mutex<Vec<JoinHandle<()>>>>,
+    // How long `get_capabilities`/`get_allowed_hosts` results are reused; zero disables
+    plugin_info_ttl: std::sync::RwLock<Duration>,
+    capabilities_cache: PluginInfoCache<ProviderCapabilities>,
+    allowed_hosts_cache: PluginInfoCache<Vec<String>>,
 }
 
 impl Aggregator {
@@ -26,11 +75,24 @@ impl Aggregator {
     pub fn plugin_manager(&self) -> &PluginManager {
         &self.pm
     }
+
+    pub async fn reconnect_database(&mut self) -> Result<()> {
+        self.db.reconnect().await
+    }
     pub async fn new(database_url: Option<&str>, run_migrations: bool) -> Result<Self> {
         let db = Database::connect(database_url).await?;
         if run_migrations {
             db.run_migrations().await?;
         }
+        Self::with_database(db)
+    }
+
+    /// Aggregator over `Database::connect_read_only`; migrations never run.
+    pub async fn open_read_only(database_url: &str) -> Result<Self> {
+        Self::with_database(Database::connect_read_only(database_url).await?)
+    }
+
+    fn with_database(db: Database) -> Result<Self> {
         let pm = PluginManager::new()?;
         let search_ttl_secs = std::env::var("TOURING_SEARCH_TTL_SECS")
             .ok()
@@ -40,26 +102,195 @@ impl Aggregator {
             .ok()
             .and_then(|s| s.parse().ok())
             .unwrap_or(24 * 3600);
+        let plugin_info_ttl = std::env::var("TOURING_PLUGIN_INFO_TTL_MS")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .map(Duration::from_millis)
+            .unwrap_or(Duration::from_secs(10));
         Ok(Self {
             db,
             pm,
             search_ttl_secs,
             pages_ttl_secs,
+            cache_codec: std::sync::RwLock::new(CacheCodec::from_env()),
+            offline: AtomicBool::new(offline_from_env()),
+            events: EventBus::new(),
+            background_persist: AtomicBool::new(env_flag("TOURING_BACKGROUND_PERSIST")),
+            pending_persistence: Arc::default(),
+            plugin_info_ttl: std::sync::RwLock::new(plugin_info_ttl),
+            capabilities_cache: Default::default(),
+            allowed_hosts_cache: Default::default(),
         })
     }
 
+    pub fn events(&self) -> &EventBus {
+        &self.events
+    }
+
+    pub fn cache_codec(&self) -> CacheCodec {
+        *self.cache_codec.read().unwrap()
+    }
+
+    pub fn set_prefer_precompiled(&self, prefer: bool) {
+        self.pm.set_prefer_precompiled(prefer);
+    }
+
+    pub fn set_max_cache_payload_bytes(&self, limit: usize) {
+        self.db.set_max_cache_payload_bytes(limit);
+    }
+
+    /// Codec for newly written cache rows; existing rows stay readable either way.
+    pub fn set_cache_codec(&self, codec: CacheCodec) {
+        *self.cache_codec.write().unwrap() = codec;
+    }
+
+    pub fn is_offline(&self) -> bool {
+        self.offline.load(Ordering::Relaxed)
+    }
+
+    /// Persist search results on a background task so searches return before the series
+    /// upserts finish. `flush_pending_persistence` waits for them.
+    pub fn set_background_persist(&self, enabled: bool) {
+        self.background_persist.store(enabled, Ordering::Relaxed);
+    }
+
+    pub fn background_persist(&self) -> bool {
+        self.background_persist.load(Ordering::Relaxed)
+    }
+
+    /// Reuse the plugin-wide `get_capabilities`/`get_allowed_hosts` results for `ttl`
+    /// (`Duration::ZERO` queries every plugin on each call).
+    pub fn set_plugin_info_ttl(&self, ttl: Duration) {
+        *self.plugin_info_ttl.write().unwrap() = ttl;
+        self.invalidate_plugin_info();
+    }
+
+    // Drop cached capabilities and allowed hosts (plugins (re)loaded or changed)
+    fn invalidate_plugin_info(&self) {
+        *self.capabilities_cache.lock().unwrap() = None;
+        *self.allowed_hosts_cache.lock().unwrap() = None;
+    }
+
+    fn cached_plugin_info<T: Clone>(&self, cache: &PluginInfoCache<T>) -> Option<Vec<(String, T)>> {
+        let ttl = *self.plugin_info_ttl.read().unwrap();
+        cache
+            .lock()
+            .unwrap()
+            .as_ref()
+            .filter(|(at, _)| at.elapsed() < ttl)
+            .map(|(_, value)| value.clone())
+    }
+
+    fn store_plugin_info<T: Clone>(&self, cache: &PluginInfoCache<T>, value: &[(String, T)]) {
+        if !self.plugin_info_ttl.read().unwrap().is_zero() {
+            *cache.lock().unwrap() = Some((Instant::now(), value.to_vec()));
+        }
+    }
+
+    /// Wait for every background search persistence task spawned so far.
+    pub async fn flush_pending_persistence(&self) {
+        loop {
+            let handles = std::mem::take(&mut *self.pending_persistence.lock().unwrap());
+            if handles.is_empty() {
+                return;
+            }
+            for handle in handles {
+                if let Err(e) = handle.await {
+                    tracing::warn!(error=%e, "search persistence task failed");
+                }
+            }
+        }
+    }
+
+    /// In offline mode searches and page lists come from the cache only (expired entries
+    /// included) and anything else needing a plugin fails with `TouringError::Offline`.
+    pub fn set_offline(&self, offline: bool) {
+        self.offline.store(offline, Ordering::Relaxed);
+    }
+
+    /// `Err(TouringError::Offline)` when offline mode is on.
+    pub fn ensure_online(&self, operation: &str) -> Result<()> {
+        if self.is_offline() {
+            return Err(TouringError::Offline {
+                operation: operation.to_string(),
+            }
+            .into());
+        }
+        Ok(())
+    }
+
+    #[cfg(test)]
+    pub(crate) fn register_mock_source(
+        &mut self,
+        name: &str,
+        source: crate::plugins::mock::MockSource,
+    ) {
+        self.pm.register_mock_source(name, source);
+        self.invalidate_plugin_info();
+    }
+
     pub async fn load_plugins_from_directory(&mut self, dir: &Path) -> Result<()> {
-        self.pm.load_plugins_from_directory(dir).await
+        self.invalidate_plugin_info();
+        self.pm.load_plugins_from_directory(dir).await?;
+        self.apply_host_overrides().await
     }
     pub async fn reload_plugins_from_directory(&mut self, dir: &Path) -> Result<()> {
         let mut pm = PluginManager::new()?;
+        pm.set_host_rate_limiter(self.pm.host_rate_limiter());
+        pm.set_prefer_precompiled(self.pm.prefer_precompiled());
+        pm.set_fail_when_all_sources_error(self.pm.fail_when_all_sources_error());
+        pm.adopt_embedded_plugins(&self.pm);
         pm.load_plugins_from_directory(dir).await?;
         self.pm = pm;
+        self.invalidate_plugin_info();
+        self.apply_host_overrides().await
+    }
+
+    pub async fn load_plugin_bytes(
+        &mut self,
+        name: &str,
+        wasm: &[u8],
+        config: PluginConfig,
+    ) -> Result<()> {
+        self.pm.load_plugin_bytes(name, wasm, config)?;
+        self.invalidate_plugin_info();
+        self.apply_host_overrides().await
+    }
+
+    /// Allow or disallow a host for a plugin at runtime; persisted so it survives reloads.
+    /// Removing a plugin's last allowed host is rejected and nothing is persisted.
+    pub async fn set_host_allowed(&self, plugin: &str, host: &str, allowed: bool) -> Result<()> {
+        let host = host.trim().to_ascii_lowercase();
+        if host.is_empty() {
+            return Err(anyhow::anyhow!("host must not be empty"));
+        }
+        self.pm.set_host_allowed(plugin, &host, allowed).await?;
+        *self.allowed_hosts_cache.lock().unwrap() = None;
+        let pool = self.db.pool().clone();
+        dao::set_plugin_host_override(&pool, plugin, &host, allowed).await
+    }
+
+    // Re-apply persisted host overrides to freshly registered plugin slots
+    async fn apply_host_overrides(&self) -> Result<()> {
+        let pool = self.db.pool().clone();
+        let overrides = match dao::list_plugin_host_overrides(&pool).await {
+            Ok(v) => v,
+            Err(e) => {
+                tracing::warn!(error=%e, "failed to read plugin host overrides");
+                return Ok(());
+            }
+        };
+        for (plugin, host, allowed) in overrides {
+            self.pm.restore_host_override(&plugin, &host, allowed);
+        }
         Ok(())
     }
     pub fn list_plugins(&self) -> Vec<String> {
         self.pm.list_plugins()
     }
+    pub async fn get_plugin_status(&self) -> Vec<PluginStatus> {
+        self.pm.get_plugin_status().await
+    }
 
     /// Create or get canonical series ID for a media item from a source
     pub async fn get_or_create_series_id(
@@ -68,57 +299,11 @@ impl Aggregator {
         external_id: &str,
         media: &Media,
     ) -> Result<String> {
-        let pool = self.db.pool().clone();
-
-        println!("[DEBUG] get_or_create_series_id called: source={}, external_id={}, media.title={}, media.cover_url={:?}", 
-                 source_id, external_id, media.title, media.cover_url);
-
-        // Check if series already exists
-        if let Some(existing) =
-            dao::find_series_id_by_source_external(&pool, source_id, external_id).await?
-        {
-            println!("[DEBUG] Series exists with id={}", existing);
-            // Series exists - only update metadata if the incoming media has a non-empty title
-            // (to avoid overwriting good data with stub/empty media objects)
-            if !media.title.is_empty() {
-                println!("[DEBUG] Updating metadata (media has non-empty title)");
-                let s = series_insert_from_media(existing.clone(), media);
-                println!(
-                    "[DEBUG] Created SeriesInsert: id={}, title={}, cover_url={:?}",
-                    s.id, s.title, s.cover_url
-                );
-                dao::upsert_series(&pool, &s).await?;
-                println!("[DEBUG] upsert_series completed successfully");
-            } else {
-                println!(
-                    "[DEBUG] Skipping metadata update (media has empty title - likely a stub)"
-                );
-            }
-            return Ok(existing);
-        }
-
-        println!("[DEBUG] Series does not exist, creating new");
-        // Ensure the source exists before creating the series_source link
-        self.upsert_source(source_id, "unknown").await?;
-
-        let new_id = uuid::Uuid::new_v4().to_string();
-        let s = series_insert_from_media(new_id.clone(), media);
-        println!(
-            "[DEBUG] Created SeriesInsert for new series: id={}, title={}, cover_url={:?}",
-            s.id, s.title, s.cover_url
-        );
-        dao::upsert_series(&pool, &s).await?;
-        let link = series_source_from(
-            new_id.clone(),
-            source_id.to_string(),
-            external_id.to_string(),
-        );
-        dao::upsert_series_source(&pool, &link).await?;
-        println!("[DEBUG] New series created successfully");
-        Ok(new_id)
+        create_or_get_series(self.db.pool(), &self.events, source_id, external_id, media).await
     }
 
     pub async fn search_manga(&self, query: &str) -> Result<Vec<Media>> {
+        self.ensure_online("searching")?;
         Ok(self
             .pm
             .search_manga_with_sources(query)
@@ -128,6 +313,7 @@ impl Aggregator {
             .collect())
     }
     pub async fn search_anime(&self, query: &str) -> Result<Vec<Media>> {
+        self.ensure_online("searching")?;
         Ok(self
             .pm
             .search_anime_with_sources(query)
@@ -157,6 +343,19 @@ impl Aggregator {
             .await
     }
 
+    /// Search any media type, e.g. `MediaType::Other("novel")`, with per-source caching.
+    /// `persist` upserts series (kind from `series_kind`) and source mappings.
+    pub async fn search_kind(
+        &self,
+        kind: MediaType,
+        query: &str,
+        refresh: bool,
+        persist: bool,
+    ) -> Result<Vec<(String, Media)>> {
+        self.search_with_sources(kind, query, refresh, persist)
+            .await
+    }
+
     /// Search without auto-creating series entries (for UI display only)
     pub async fn search_manga_no_persist(
         &self,
@@ -167,6 +366,16 @@ impl Aggregator {
             .await
     }
 
+    /// Anime counterpart of `search_manga_no_persist`
+    pub async fn search_anime_no_persist(
+        &self,
+        query: &str,
+        refresh: bool,
+    ) -> Result<Vec<(String, Media)>> {
+        self.search_with_sources(MediaType::Anime, query, refresh, false)
+            .await
+    }
+
     async fn search_with_sources(
         &self,
         kind: MediaType,
@@ -174,56 +383,118 @@ impl Aggregator {
         refresh: bool,
         auto_persist: bool,
     ) -> Result<Vec<(String, Media)>> {
-        let norm = norm_query(query);
-        let now = current_epoch();
+        if refresh {
+            self.ensure_online("refreshing search results")?;
+        }
         let sources = self.pm.list_plugins();
+        // `buffered` keeps results in source order while sources run concurrently
+        let outcomes: Vec<Result<SourceSearch>> = stream::iter(&sources)
+            .map(|source| self.search_source(&kind, source, query, refresh, auto_persist))
+            .buffered(SEARCH_SOURCE_CONCURRENCY)
+            .collect()
+            .await;
+        let mut failed = 0usize;
         let mut out = Vec::new();
-        for source in sources {
-            let key = format!("{}|search|{:?}|{}", source, kind, norm);
-            let mut hit: Option<Vec<Media>> = None;
-            if !refresh {
-                if let Some(payload) = self.db.get_cache(&key, now).await.ok().flatten() {
-                    hit = try_deserialize_media_cache(&payload, &kind);
+        for (source, outcome) in sources.iter().zip(outcomes) {
+            match outcome? {
+                SourceSearch::Results(list) => {
+                    out.extend(list.into_iter().map(|m| (source.clone(), m)));
                 }
+                SourceSearch::Skipped => {}
+                SourceSearch::Failed => failed += 1,
             }
-            let list = if let Some(m) = hit {
-                m
-            } else {
-                let mut list = match kind {
-                    MediaType::Manga => self.pm.search_manga_for(&source, query).await?,
-                    MediaType::Anime => self.pm.search_anime_for(&source, query).await?,
-                    _ => Vec::new(),
-                };
-                if matches!(kind, MediaType::Anime) {
-                    for v in &mut list {
-                        v.mediatype = MediaType::Anime;
-                    }
+        }
+        if failed > 0 && failed == sources.len() && self.pm.fail_when_all_sources_error() {
+            return Err(anyhow::anyhow!(
+                "search failed: all {} sources errored",
+                failed
+            ));
+        }
+        Ok(out)
+    }
+
+    // One source's share of `search_with_sources`: cache lookup, plugin call, cache write and
+    // persistence. Only cancellation and cache encoding errors are returned as `Err`.
+    async fn search_source(
+        &self,
+        kind: &MediaType,
+        source: &str,
+        query: &str,
+        refresh: bool,
+        auto_persist: bool,
+    ) -> Result<SourceSearch> {
+        let now = current_epoch();
+        // Offline, stale entries beat no results at all
+        let cache_now = if self.is_offline() { i64::MIN } else { now };
+        let key = format!("{}|search|{:?}|{}", source, kind, norm_query(query));
+        let mut hit: Option<Vec<Media>> = None;
+        if !refresh {
+            if let Some(payload) = self.db.get_cache(&key, cache_now).await.ok().flatten() {
+                hit = try_deserialize_media_cache(&payload, kind);
+            }
+        }
+        let list = if let Some(m) = hit {
+            m
+        } else if self.is_offline() {
+            return Ok(SourceSearch::Skipped);
+        } else {
+            let fetched = match kind {
+                MediaType::Manga => self.pm.search_manga_for(source, query).await,
+                MediaType::Anime => self.pm.search_anime_for(source, query).await,
+                MediaType::Other(_) => self.pm.search_kind_for(kind.clone(), source, query).await,
+            };
+            // A failing source is skipped so the others still contribute results
+            let mut list = match fetched {
+                Ok(list) => list,
+                Err(e)
+                    if matches!(
+                        TouringError::classify(&e),
+                        Some(TouringError::Cancelled { .. })
+                    ) =>
+                {
+                    return Err(e)
+                }
+                Err(e) => {
+                    tracing::warn!(plugin=%source, error=%e, kind=?kind, "search failed");
+                    return Ok(SourceSearch::Failed);
                 }
-                let payload =
-                    serde_json::to_string(&list.iter().map(media_to_cache).collect::<Vec<_>>())?;
-                let _ = self
-                    .db
-                    .put_cache(&key, &payload, now + self.search_ttl_secs)
-                    .await;
-                list
             };
-
-            // Only persist to database if auto_persist is enabled (e.g., for CLI, not UI search)
-            if auto_persist {
-                for m in &list {
-                    let _ = self.upsert_source(&source, "unknown").await; // ignore errors here
-                    let _ = self.get_or_create_series_id(&source, &m.id, m).await;
+            if !matches!(kind, MediaType::Manga) {
+                for v in &mut list {
+                    v.mediatype = kind.clone();
                 }
             }
+            let payload = self
+                .cache_codec()
+                .encode(&list.iter().map(media_to_cache).collect::<Vec<_>>())?;
+            let _ = self
+                .db
+                .put_cache(&key, &payload, now + self.search_ttl_secs)
+                .await;
+            list
+        };
 
-            for m in list {
-                out.push((source.clone(), m));
+        // Only persist to database if auto_persist is enabled (e.g., for CLI, not UI search)
+        if auto_persist {
+            let task = persist_search_results(
+                self.db.pool().clone(),
+                self.events.clone(),
+                source.to_string(),
+                list.clone(),
+            );
+            if self.background_persist() {
+                let mut pending = self.pending_persistence.lock().unwrap();
+                pending.retain(|handle| !handle.is_finished());
+                pending.push(tokio::spawn(task));
+            } else {
+                task.await;
             }
         }
-        Ok(out)
+        Ok(SourceSearch::Results(list))
     }
 
     pub async fn get_manga_chapters(&self, external_manga_id: &str) -> Result<Vec<Unit>> {
+        self.ensure_online("fetching chapters")?;
         let (source_opt, units) = self
             .pm
             .get_manga_chapters_with_source(external_manga_id)
@@ -240,26 +511,88 @@ impl Aggregator {
             let series_id = self
                 .get_or_create_series_id(&source_id, external_manga_id, &media_stub)
                 .await?;
+            // Fresh ids only take effect for new chapters; stored ones keep theirs
+            let rows: Vec<_> = units
+                .iter()
+                .filter(|u| matches!(u.kind, UnitKind::Chapter))
+                .map(|u| {
+                    let cid = uuid::Uuid::new_v4().to_string();
+                    chapter_insert_from_unit(cid, series_id.clone(), source_id.clone(), u)
+                })
+                .collect();
+            if let Err(e) = dao::upsert_chapters(self.db.pool(), &rows).await {
+                tracing::warn!(series=%series_id, source=%source_id, error=%e, "failed to persist chapters");
+            }
+        }
+        Ok(units)
+    }
+
+    /// Fetch sections (arcs) for a media id. When the series is already known locally, sections
+    /// are persisted along with links to their stored chapters/episodes. Returns only the
+    /// `Section` units, in source order.
+    pub async fn get_sections(&self, external_media_id: &str) -> Result<Vec<Unit>> {
+        self.ensure_online("fetching sections")?;
+        let (source_opt, units) = self.pm.get_sections_with_source(external_media_id).await?;
+        if let Some(source_id) = source_opt {
             let pool = self.db.pool().clone();
-            for u in units.iter().filter(|u| matches!(u.kind, UnitKind::Chapter)) {
-                if let Some(existing) =
-                    dao::find_chapter_id_by_mapping(&pool, &series_id, &source_id, &u.id).await?
+            if let Some(series_id) =
+                dao::find_series_id_by_source_external(&pool, &source_id, external_media_id).await?
+            {
+                for (position, (section, children)) in
+                    group_units_by_section(&units).into_iter().enumerate()
                 {
-                    let ch =
-                        chapter_insert_from_unit(existing, series_id.clone(), source_id.clone(), u);
-                    let _ = dao::upsert_chapter(&pool, &ch).await;
-                } else {
-                    let cid = uuid::Uuid::new_v4().to_string();
-                    let ch = chapter_insert_from_unit(cid, series_id.clone(), source_id.clone(), u);
-                    let _ = dao::upsert_chapter(&pool, &ch).await;
+                    let id = match dao::find_section_id_by_mapping(
+                        &pool,
+                        &series_id,
+                        &source_id,
+                        &section.id,
+                    )
+                    .await?
+                    {
+                        Some(existing) => existing,
+                        None => uuid::Uuid::new_v4().to_string(),
+                    };
+                    let row = section_insert_from_unit(
+                        id.clone(),
+                        series_id.clone(),
+                        source_id.clone(),
+                        position as i64,
+                        section,
+                    );
+                    dao::upsert_section(&pool, &row).await?;
+
+                    // Only children that have already been stored can be linked
+                    let mut unit_ids = Vec::new();
+                    for child in children {
+                        let found = match child.kind {
+                            UnitKind::Episode => {
+                                dao::find_episode_id_by_source_external(
+                                    &pool, &source_id, &child.id,
+                                )
+                                .await?
+                            }
+                            _ => {
+                                dao::find_chapter_id_by_mapping(
+                                    &pool, &series_id, &source_id, &child.id,
+                                )
+                                .await?
+                            }
+                        };
+                        unit_ids.extend(found);
+                    }
+                    dao::replace_section_units(&pool, &id, &unit_ids).await?;
                 }
             }
         }
-        Ok(units)
+        Ok(units
+            .into_iter()
+            .filter(|u| matches!(u.kind, UnitKind::Section))
+            .collect())
     }
 
     /// Fetch chapters without mutating the database (used for previews in the UI)
     pub async fn preview_manga_chapters(&self, external_manga_id: &str) -> Result<Vec<Unit>> {
+        self.ensure_online("fetching chapters")?;
         let (_source_opt, units) = self
             .pm
             .get_manga_chapters_with_source(external_manga_id)
@@ -268,6 +601,7 @@ impl Aggregator {
     }
 
     pub async fn get_anime_episodes(&self, external_anime_id: &str) -> Result<Vec<Unit>> {
+        self.ensure_online("fetching episodes")?;
         let (source_opt, units) = self
             .pm
             .get_anime_episodes_with_source(external_anime_id)
@@ -323,8 +657,47 @@ impl Aggregator {
         Ok(units)
     }
 
+    /// Re-fetch episodes from every source mapped to `series_id` and persist only the ones
+    /// not yet stored (matched by source + external id). Existing rows are left untouched.
+    /// Returns the canonical ids of the newly inserted episodes.
+    pub async fn check_for_new_episodes(&self, series_id: &str) -> Result<Vec<String>> {
+        self.ensure_online("checking for new episodes")?;
+        let pool = self.db.pool().clone();
+        let mut new_ids = Vec::new();
+        for (source_id, external_id) in dao::list_series_sources(&pool, series_id).await? {
+            let units = match self.pm.get_units_for(&source_id, &external_id).await {
+                Ok(units) => units,
+                Err(e) => {
+                    tracing::warn!(series=%series_id, source=%source_id, error=%e, "episode check failed");
+                    continue;
+                }
+            };
+            for u in units.iter().filter(|u| matches!(u.kind, UnitKind::Episode)) {
+                if dao::find_episode_id_by_mapping(&pool, series_id, &source_id, &u.id)
+                    .await?
+                    .is_some()
+                {
+                    continue;
+                }
+                let id = uuid::Uuid::new_v4().to_string();
+                let ep = episode_insert_from_unit(
+                    id.clone(),
+                    series_id.to_string(),
+                    source_id.clone(),
+                    u,
+                );
+                dao::upsert_episode(&pool, &ep).await?;
+                new_ids.push(id);
+            }
+            let ss = series_source_from(series_id.to_string(), source_id, external_id);
+            dao::upsert_series_source(&pool, &ss).await?;
+        }
+        Ok(new_ids)
+    }
+
     /// Fetch episodes without mutating the database (used for previews in the UI)
     pub async fn preview_anime_episodes(&self, external_anime_id: &str) -> Result<Vec<Unit>> {
+        self.ensure_online("fetching episodes")?;
         let (_source_opt, units) = self
             .pm
             .get_anime_episodes_with_source(external_anime_id)
@@ -333,29 +706,83 @@ impl Aggregator {
     }
 
     pub async fn get_episode_streams(&self, external_episode_id: &str) -> Result<Vec<Asset>> {
-        let (src_opt, vids) = self
+        let fetched = self
+            .get_episode_streams_for_series(external_episode_id, None)
+            .await?;
+        Ok(fetched.streams)
+    }
+
+    /// Fetch streams and persist them on the episode stored for the serving source. An
+    /// episode that isn't stored yet is created under `series_id` (when given and mapped to
+    /// that source); otherwise the result reports `episode_id: None`.
+    pub async fn get_episode_streams_for_series(
+        &self,
+        external_episode_id: &str,
+        series_id: Option<&str>,
+    ) -> Result<EpisodeStreams> {
+        self.ensure_online("fetching streams")?;
+        let (source_id, streams) = self
             .pm
             .get_episode_streams_with_source(external_episode_id)
             .await?;
-        if let Some(source_id) = src_opt {
-            let pool = self.db.pool().clone();
-            if let Some(canonical_eid) =
-                dao::find_episode_id_by_source_external(&pool, &source_id, external_episode_id)
+        let mut fetched = EpisodeStreams {
+            streams,
+            source_id,
+            episode_id: None,
+        };
+        let Some(source_id) = fetched.source_id.clone() else {
+            return Ok(fetched);
+        };
+        let pool = self.db.pool().clone();
+        let mut episode_id =
+            dao::find_episode_id_by_source_external(&pool, &source_id, external_episode_id).await?;
+        if episode_id.is_none() {
+            if let Some(series_id) = series_id {
+                let mapped = dao::list_series_sources(&pool, series_id)
                     .await?
-            {
-                let streams: Vec<crate::dao::StreamInsert> = vids
                     .iter()
-                    .map(|a| crate::dao::StreamInsert {
-                        episode_id: canonical_eid.clone(),
-                        url: a.url.clone(),
-                        quality: None,
-                        mime: a.mime.clone(),
-                    })
-                    .collect();
-                let _ = dao::upsert_streams(&pool, &canonical_eid, &streams).await;
+                    .any(|(source, _)| source == &source_id);
+                if mapped {
+                    let id = uuid::Uuid::new_v4().to_string();
+                    dao::upsert_episode(
+                        &pool,
+                        &crate::dao::EpisodeInsert {
+                            id: id.clone(),
+                            series_id: series_id.to_string(),
+                            source_id: source_id.clone(),
+                            external_id: external_episode_id.to_string(),
+                            number_text: None,
+                            number_num: None,
+                            title: None,
+                            lang: None,
+                            season: None,
+                            published_at: None,
+                        },
+                    )
+                    .await?;
+                    episode_id = Some(id);
+                }
             }
         }
-        Ok(vids)
+        let Some(canonical_eid) = episode_id else {
+            tracing::debug!(episode=%external_episode_id, source=%source_id, "episode not stored; streams not persisted");
+            return Ok(fetched);
+        };
+        let rows: Vec<crate::dao::StreamInsert> = fetched
+            .streams
+            .iter()
+            .map(|a| crate::dao::StreamInsert {
+                episode_id: canonical_eid.clone(),
+                url: a.url.clone(),
+                quality: crate::mapping::stream_quality(a),
+                mime: a.mime.clone(),
+            })
+            .collect();
+        match dao::upsert_streams(&pool, &canonical_eid, &rows).await {
+            Ok(_) => fetched.episode_id = Some(canonical_eid),
+            Err(e) => tracing::warn!(episode=%canonical_eid, error=%e, "failed to persist streams"),
+        }
+        Ok(fetched)
     }
 
     pub async fn get_chapter_images_with_refresh(
@@ -363,6 +790,19 @@ impl Aggregator {
         chapter_id: &str,
         refresh: bool,
     ) -> Result<Vec<String>> {
+        let (_source, urls) = self
+            .get_chapter_images_with_source_name(chapter_id, refresh)
+            .await?;
+        Ok(urls)
+    }
+
+    /// Like `get_chapter_images_with_refresh`, also returning the plugin that served the
+    /// pages. Cached hits report the source recorded with the cache entry (if any).
+    pub async fn get_chapter_images_with_source_name(
+        &self,
+        chapter_id: &str,
+        refresh: bool,
+    ) -> Result<(Option<String>, Vec<String>)> {
         let pool = self.db.pool().clone();
         let fetch_info = dao::find_chapter_fetch_info(&pool, chapter_id).await?;
         let (cache_id, fetch_id) = match fetch_info {
@@ -371,22 +811,56 @@ impl Aggregator {
         };
 
         let key = format!("all|pages|{}", cache_id);
+        // Stored next to the page list so `clear_cache_prefix("all|pages")` drops both
+        let source_key = format!("all|pages|source|{}", cache_id);
         let now = current_epoch();
+        if refresh {
+            self.ensure_online("refreshing chapter pages")?;
+        }
+        let cache_now = if self.is_offline() { i64::MIN } else { now };
         if !refresh {
-            if let Some(payload) = self.db.get_cache(&key, now).await.ok().flatten() {
-                if let Ok(urls) = serde_json::from_str::<Vec<String>>(&payload) {
-                    return Ok(urls);
+            if let Some(payload) = self.db.get_cache(&key, cache_now).await.ok().flatten() {
+                if let Some(urls) = decode_cache::<Vec<String>>(&payload) {
+                    let source = self
+                        .db
+                        .get_cache(&source_key, cache_now)
+                        .await
+                        .ok()
+                        .flatten()
+                        .filter(|s| !s.is_empty());
+                    return Ok((source, urls));
                 }
             }
         }
-        let (_src_opt, urls) = self.pm.get_chapter_images_with_source(&fetch_id).await?;
-        let payload = serde_json::to_string(&urls)?;
-        let _ = self
+        self.ensure_online("fetching chapter pages (not cached)")?;
+        let preferred = match dao::find_chapter_identity(&pool, &cache_id).await? {
+            Some((_, series_id)) => dao::get_series_source_priority(&pool, &series_id).await?,
+            None => Vec::new(),
+        };
+        let (src_opt, urls) = self
+            .pm
+            .get_chapter_images_with_source_in_order(&fetch_id, &preferred)
+            .await?;
+        let payload = self.cache_codec().encode(&urls)?;
+        let expires_at = now + self.pages_ttl_secs;
+        let _ = self.db.put_cache(&key, &payload, expires_at).await;
+        if let Some(src) = &src_opt {
+            let _ = self.db.put_cache(&source_key, src, expires_at).await;
+        }
+        Ok((src_opt, urls))
+    }
+    /// Page count from the cached page list (expired entries included), without fetching.
+    /// `chapter_id` must be canonical.
+    pub async fn cached_page_count(&self, chapter_id: &str) -> Result<Option<usize>> {
+        let key = format!("all|pages|{}", chapter_id);
+        Ok(self
             .db
-            .put_cache(&key, &payload, now + self.pages_ttl_secs)
-            .await;
-        Ok(urls)
+            .get_cache(&key, i64::MIN)
+            .await?
+            .and_then(|payload| decode_cache::<Vec<String>>(&payload))
+            .map(|urls| urls.len()))
     }
+
     pub async fn get_chapter_images(&self, chapter_id: &str) -> Result<Vec<String>> {
         self.get_chapter_images_with_refresh(chapter_id, false)
             .await
@@ -396,10 +870,34 @@ impl Aggregator {
         &self,
         refresh: bool,
     ) -> Result<Vec<(String, ProviderCapabilities)>> {
-        self.pm.get_capabilities(refresh).await
+        if !refresh {
+            if let Some(caps) = self.cached_plugin_info(&self.capabilities_cache) {
+                return Ok(caps);
+            }
+        }
+        let caps = self.pm.get_capabilities(refresh).await?;
+        self.store_plugin_info(&self.capabilities_cache, &caps);
+        Ok(caps)
+    }
+
+    /// Forget capabilities cached here and inside the plugins (see
+    /// `PluginManager::reset_capabilities_cache`).
+    pub async fn reset_capabilities_cache(&self, name: Option<&str>) -> Result<()> {
+        *self.capabilities_cache.lock().unwrap() = None;
+        self.pm.reset_capabilities_cache(name).await
     }
+
     pub async fn get_allowed_hosts(&self) -> Result<Vec<(String, Vec<String>)>> {
-        self.pm.get_allowed_hosts().await
+        if let Some(hosts) = self.cached_plugin_info(&self.allowed_hosts_cache) {
+            return Ok(hosts);
+        }
+        let hosts = self.pm.get_allowed_hosts().await?;
+        self.store_plugin_info(&self.allowed_hosts_cache, &hosts);
+        Ok(hosts)
+    }
+
+    pub async fn get_plugin_allowed_hosts(&self, name: &str) -> Result<Option<Vec<String>>> {
+        self.pm.get_plugin_allowed_hosts(name).await
     }
 
     pub async fn upsert_source(&self, id: &str, version: &str) -> Result<()> {
@@ -412,21 +910,65 @@ impl Aggregator {
             },
         )
         .await
-        .map_err(Into::into)
     }
     pub async fn clear_cache_prefix(&self, prefix: Option<&str>) -> Result<u64> {
-        self.db.clear_cache_prefix(prefix).await.map_err(Into::into)
+        self.db.clear_cache_prefix(prefix).await
+    }
+    /// Drop a series' cached page lists (and their source markers) plus search results for
+    /// its title or external ids on its sources. Returns rows deleted.
+    pub async fn clear_series_cache(&self, series_id: &str) -> Result<u64> {
+        let pool = self.db.pool().clone();
+        let mut keys = Vec::new();
+        for (chapter_id, _, _) in dao::list_chapters_for_series(&pool, series_id).await? {
+            keys.push(format!("all|pages|{}", chapter_id));
+            keys.push(format!("all|pages|source|{}", chapter_id));
+        }
+
+        let title: Option<String> = sqlx::query_scalar("SELECT title FROM series WHERE id = ?")
+            .bind(series_id)
+            .fetch_optional(&pool)
+            .await?;
+        for (source_id, external_id) in dao::list_series_sources(&pool, series_id).await? {
+            let suffixes: Vec<String> = title
+                .iter()
+                .chain(std::iter::once(&external_id))
+                .map(|q| format!("|{}", norm_query(q)))
+                .collect();
+            let prefix = format!("{}|search|", source_id);
+            for (key, _) in self.db.list_cache_keys(Some(&prefix), usize::MAX).await? {
+                if suffixes.iter().any(|s| key.ends_with(s.as_str())) {
+                    keys.push(key);
+                }
+            }
+        }
+        self.db.delete_cache_keys(&keys).await
+    }
+    pub async fn list_cache_keys(
+        &self,
+        prefix: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<(String, i64)>> {
+        self.db.list_cache_keys(prefix, limit).await
+    }
+    pub async fn prune_cache_to_size(&self, max_bytes: u64) -> Result<u64> {
+        self.db
+            .prune_cache_to_size(max_bytes, current_epoch())
+            .await
     }
     pub async fn vacuum_db(&self) -> Result<()> {
-        self.db.vacuum().await.map_err(Into::into)
+        self.db.vacuum().await
+    }
+
+    pub async fn backup_to(&self, path: &Path) -> Result<()> {
+        self.db.backup_to(path).await
     }
 }
 
 fn try_deserialize_media_cache(payload: &str, _kind: &MediaType) -> Option<Vec<Media>> {
-    if let Ok(items) = serde_json::from_str::<Vec<MediaCache>>(payload) {
+    if let Some(items) = decode_cache::<Vec<MediaCache>>(payload) {
         return Some(items.into_iter().map(media_from_cache).collect());
     }
-    if let Ok(entries) = serde_json::from_str::<Vec<SearchEntry>>(payload) {
+    if let Some(entries) = decode_cache::<Vec<SearchEntry>>(payload) {
         return Some(
             entries
                 .into_iter()
@@ -455,9 +997,506 @@ fn norm_query(q: &str) -> String {
     o
 }
 
+// Body of `Aggregator::get_or_create_series_id`, free of `&self` so background search
+// persistence can run it on a spawned task.
+async fn create_or_get_series(
+    pool: &AnyPool,
+    events: &EventBus,
+    source_id: &str,
+    external_id: &str,
+    media: &Media,
+) -> Result<String> {
+    if let Some(existing) =
+        dao::find_series_id_by_source_external(pool, source_id, external_id).await?
+    {
+        // Only update metadata from media with a title; stubs would overwrite good data
+        if !media.title.is_empty() {
+            dao::upsert_series(pool, &series_insert_from_media(existing.clone(), media)).await?;
+        } else {
+            tracing::debug!(series=%existing, source=%source_id, external_id, "stub media; metadata left as is");
+        }
+        return Ok(existing);
+    }
+
+    // Ensure the source exists before creating the series_source link
+    dao::upsert_source(
+        pool,
+        &dao::SourceInsert {
+            id: source_id.to_string(),
+            version: "unknown".to_string(),
+        },
+    )
+    .await?;
+
+    let new_id = uuid::Uuid::new_v4().to_string();
+    let s = series_insert_from_media(new_id.clone(), media);
+    dao::upsert_series(pool, &s).await?;
+    let link = series_source_from(
+        new_id.clone(),
+        source_id.to_string(),
+        external_id.to_string(),
+    );
+    dao::upsert_series_source(pool, &link).await?;
+    tracing::debug!(series=%new_id, source=%source_id, external_id, title=%s.title, "created series");
+    events.publish(LibraryEvent::SeriesAdded {
+        series_id: new_id.clone(),
+        title: s.title,
+    });
+    Ok(new_id)
+}
+
+// Series upserts for one source's search results; failures are logged and skipped.
+async fn persist_search_results(pool: AnyPool, events: EventBus, source: String, list: Vec<Media>) {
+    let source_row = dao::SourceInsert {
+        id: source.clone(),
+        version: "unknown".to_string(),
+    };
+    let _ = dao::upsert_source(&pool, &source_row).await;
+    for m in &list {
+        if let Err(e) = create_or_get_series(&pool, &events, &source, &m.id, m).await {
+            tracing::debug!(source=%source, media=%m.id, error=%e, "failed to persist search result");
+        }
+    }
+}
+
+// `TOURING_OFFLINE=1|true|yes` starts in offline mode
+fn offline_from_env() -> bool {
+    env_flag("TOURING_OFFLINE")
+}
+
+fn env_flag(name: &str) -> bool {
+    matches!(
+        std::env::var(name)
+            .map(|v| v.trim().to_ascii_lowercase())
+            .as_deref(),
+        Ok("1") | Ok("true") | Ok("yes")
+    )
+}
+
 fn current_epoch() -> i64 {
     std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
         .unwrap_or_default()
         .as_secs() as i64
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::mock::{media, page, unit, MockSource};
+
+    async fn aggregator(dir: &tempfile::TempDir) -> Aggregator {
+        let url = format!("sqlite://{}?mode=rwc", dir.path().join("t.db").display());
+        Aggregator::new(Some(&url), true).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn search_is_cached_persisted_and_skips_failing_sources() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut agg = aggregator(&dir).await;
+        let good = MockSource::new().with_media(media("m1", "Yotsuba&!", MediaType::Manga));
+        let down = MockSource::new().failing();
+        agg.register_mock_source("good", good.clone());
+        agg.register_mock_source("down", down.clone());
+
+        let hits = agg
+            .search_manga_cached_with_sources("yotsuba", false)
+            .await
+            .unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].0, "good");
+        let pool = agg.database().pool().clone();
+        assert!(dao::find_series_id_by_source_external(&pool, "good", "m1")
+            .await
+            .unwrap()
+            .is_some());
+
+        agg.search_manga_cached_with_sources("Yotsuba", false)
+            .await
+            .unwrap();
+        assert_eq!(good.calls(), 1);
+        assert_eq!(down.calls(), 2); // failures are not cached
+
+        agg.register_mock_source("good", MockSource::new().failing());
+        assert!(agg
+            .search_manga_cached_with_sources("other", false)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn search_emits_series_added_only_for_new_series() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut agg = aggregator(&dir).await;
+        let good = MockSource::new().with_media(media("m1", "Yotsuba&!", MediaType::Manga));
+        agg.register_mock_source("good", good);
+        let mut events = agg.events().subscribe();
+
+        agg.search_manga_cached_with_sources("yotsuba", false)
+            .await
+            .unwrap();
+        assert!(matches!(
+            events.try_recv(),
+            Ok(LibraryEvent::SeriesAdded { title, .. }) if title == "Yotsuba&!"
+        ));
+        agg.search_manga_cached_with_sources("yotsuba", true)
+            .await
+            .unwrap();
+        assert!(events.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn cache_keys_list_normalized_search_queries() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut agg = aggregator(&dir).await;
+        let good = MockSource::new().with_media(media("m1", "Yotsuba&!", MediaType::Manga));
+        agg.register_mock_source("good", good);
+
+        agg.search_manga_cached_with_sources("Yotsuba", false)
+            .await
+            .unwrap();
+        let keys = agg.list_cache_keys(Some("good|search|"), 10).await.unwrap();
+        assert_eq!(keys.len(), 1);
+        assert!(keys[0].0.ends_with("|yotsuba"));
+    }
+
+    #[tokio::test]
+    async fn plugin_status_records_last_success_and_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut agg = aggregator(&dir).await;
+        let good = MockSource::new().with_media(media("m1", "Yotsuba&!", MediaType::Manga));
+        agg.register_mock_source("good", good);
+        agg.register_mock_source("down", MockSource::new().failing());
+
+        agg.search_manga_cached_with_sources("yotsuba", false)
+            .await
+            .unwrap();
+        let status = agg.get_plugin_status().await; // sorted by name
+        assert_eq!(status[0].name, "down");
+        assert!(status[0].last_success.is_none());
+        assert_eq!(status[0].last_error.as_deref(), Some("mock source failure"));
+        assert!(status[1].loaded && status[1].last_success.is_some());
+    }
+
+    #[tokio::test]
+    async fn background_persistence_is_flushed_on_demand() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut agg = aggregator(&dir).await;
+        let source = MockSource::new().with_media(media("m1", "Mushishi", MediaType::Manga));
+        agg.register_mock_source("good", source);
+        agg.set_background_persist(true);
+
+        let hits = agg
+            .search_manga_cached_with_sources("mushi", false)
+            .await
+            .unwrap();
+        assert_eq!(hits.len(), 1);
+        agg.flush_pending_persistence().await;
+        let pool = agg.database().pool().clone();
+        assert!(dao::find_series_id_by_source_external(&pool, "good", "m1")
+            .await
+            .unwrap()
+            .is_some());
+    }
+
+    #[tokio::test]
+    async fn chapters_persist_and_pages_are_cached() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut agg = aggregator(&dir).await;
+        let src = MockSource::new()
+            .with_units(
+                "m1",
+                vec![
+                    unit("c1", 1.0, UnitKind::Chapter),
+                    unit("c2", 2.0, UnitKind::Chapter),
+                ],
+            )
+            .with_assets("c1", vec![page("mock://1"), page("mock://2")]);
+        agg.register_mock_source("src", src.clone());
+
+        assert_eq!(agg.get_manga_chapters("m1").await.unwrap().len(), 2);
+        let pool = agg.database().pool().clone();
+        let series_id = dao::find_series_id_by_source_external(&pool, "src", "m1")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            dao::list_chapters_for_series(&pool, &series_id)
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+
+        for _ in 0..2 {
+            let (source, urls) = agg
+                .get_chapter_images_with_source_name("c1", false)
+                .await
+                .unwrap();
+            assert_eq!(source.as_deref(), Some("src"));
+            assert_eq!(urls, vec!["mock://1", "mock://2"]);
+        }
+        assert_eq!(src.calls(), 2); // units once, pages once
+    }
+
+    #[tokio::test]
+    async fn cached_page_count_reads_expired_page_lists_without_fetching() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut agg = aggregator(&dir).await;
+        let src = MockSource::new()
+            .with_units(
+                "m1",
+                vec![
+                    unit("c1", 1.0, UnitKind::Chapter),
+                    unit("c2", 2.0, UnitKind::Chapter),
+                ],
+            )
+            .with_assets("c1", vec![page("mock://1"), page("mock://2")]);
+        agg.register_mock_source("src", src.clone());
+        agg.get_manga_chapters("m1").await.unwrap();
+        agg.get_chapter_images_with_source_name("c1", false)
+            .await
+            .unwrap();
+        let pool = agg.database().pool().clone();
+        let (c1, _) = dao::find_chapter_identity(&pool, "c1")
+            .await
+            .unwrap()
+            .unwrap();
+        let (c2, _) = dao::find_chapter_identity(&pool, "c2")
+            .await
+            .unwrap()
+            .unwrap();
+        sqlx::query("UPDATE search_cache SET expires_at = 0")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let calls = src.calls();
+        assert_eq!(agg.cached_page_count(&c1).await.unwrap(), Some(2));
+        assert_eq!(agg.cached_page_count(&c2).await.unwrap(), None);
+        assert_eq!(src.calls(), calls);
+    }
+
+    #[tokio::test]
+    async fn clear_series_cache_drops_only_that_series() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut agg = aggregator(&dir).await;
+        let src = MockSource::new()
+            .with_media(media("m1", "Yotsuba&!", MediaType::Manga))
+            .with_media(media("m2", "Mushishi", MediaType::Manga))
+            .with_units("m1", vec![unit("c1", 1.0, UnitKind::Chapter)])
+            .with_units("m2", vec![unit("d1", 1.0, UnitKind::Chapter)])
+            .with_assets("c1", vec![page("mock://1"), page("mock://2")])
+            .with_assets("d1", vec![page("mock://3")]);
+        agg.register_mock_source("src", src.clone());
+        for (query, media_id, chapter) in [("Yotsuba&!", "m1", "c1"), ("Mushishi", "m2", "d1")] {
+            agg.search_manga_cached_with_sources(query, false)
+                .await
+                .unwrap();
+            agg.get_manga_chapters(media_id).await.unwrap();
+            agg.get_chapter_images_with_source_name(chapter, false)
+                .await
+                .unwrap();
+        }
+        let pool = agg.database().pool().clone();
+        let series_id = dao::find_series_id_by_source_external(&pool, "src", "m1")
+            .await
+            .unwrap()
+            .unwrap();
+        let (d1, _) = dao::find_chapter_identity(&pool, "d1")
+            .await
+            .unwrap()
+            .unwrap();
+
+        // Page list, its source marker and the search for the series title
+        assert_eq!(agg.clear_series_cache(&series_id).await.unwrap(), 3);
+        assert_eq!(agg.cached_page_count(&d1).await.unwrap(), Some(1));
+        let calls = src.calls();
+        agg.search_manga_cached_with_sources("Mushishi", false)
+            .await
+            .unwrap();
+        agg.get_chapter_images_with_source_name("d1", false)
+            .await
+            .unwrap();
+        assert_eq!(src.calls(), calls);
+        agg.search_manga_cached_with_sources("Yotsuba&!", false)
+            .await
+            .unwrap();
+        agg.get_chapter_images_with_source_name("c1", false)
+            .await
+            .unwrap();
+        assert_eq!(src.calls(), calls + 2);
+    }
+
+    #[tokio::test]
+    async fn refetched_chapters_update_in_place_and_keep_canonical_ids() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut agg = aggregator(&dir).await;
+        let first = MockSource::new().with_units("m1", vec![unit("c1", 1.0, UnitKind::Chapter)]);
+        agg.register_mock_source("src", first);
+        agg.get_manga_chapters("m1").await.unwrap();
+        let pool = agg.database().pool().clone();
+        let (c1, _) = dao::find_chapter_identity(&pool, "c1")
+            .await
+            .unwrap()
+            .unwrap();
+
+        let renamed = Unit {
+            title: "Renamed".to_string(),
+            ..unit("c1", 1.0, UnitKind::Chapter)
+        };
+        let second =
+            MockSource::new().with_units("m1", vec![renamed, unit("c2", 2.0, UnitKind::Chapter)]);
+        agg.register_mock_source("src", second);
+        agg.get_manga_chapters("m1").await.unwrap();
+
+        let series_id = dao::find_series_id_by_source_external(&pool, "src", "m1")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            dao::list_chapters_for_series(&pool, &series_id)
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+        let title: String = sqlx::query_scalar("SELECT title FROM chapters WHERE id = ?")
+            .bind(&c1)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(title, "Renamed");
+    }
+
+    #[tokio::test]
+    async fn series_source_priority_orders_page_fetches() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut agg = aggregator(&dir).await;
+        let a = MockSource::new()
+            .with_units("m1", vec![unit("c1", 1.0, UnitKind::Chapter)])
+            .with_assets("c1", vec![page("mock://a")]);
+        let b = MockSource::new().with_assets("c1", vec![page("mock://b")]);
+        agg.register_mock_source("a", a);
+        agg.register_mock_source("b", b);
+        agg.get_manga_chapters("m1").await.unwrap();
+        let pool = agg.database().pool().clone();
+        let series_id = dao::find_series_id_by_source_external(&pool, "a", "m1")
+            .await
+            .unwrap()
+            .unwrap();
+
+        let (source, _) = agg
+            .get_chapter_images_with_source_name("c1", true)
+            .await
+            .unwrap();
+        assert_eq!(source.as_deref(), Some("a"));
+        dao::set_series_source_priority(&pool, &series_id, &["b".to_string()])
+            .await
+            .unwrap();
+        let (source, urls) = agg
+            .get_chapter_images_with_source_name("c1", true)
+            .await
+            .unwrap();
+        assert_eq!(source.as_deref(), Some("b"));
+        assert_eq!(urls, vec!["mock://b"]);
+    }
+
+    #[tokio::test]
+    async fn streams_for_unknown_episode_persist_only_with_a_series() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut agg = aggregator(&dir).await;
+        let video = Asset {
+            kind: crate::plugins::AssetKind::Video,
+            ..page("mock://e2.mp4")
+        };
+        let src = MockSource::new()
+            .with_units("a1", vec![unit("e1", 1.0, UnitKind::Episode)])
+            .with_assets("e2", vec![video]);
+        agg.register_mock_source("src", src);
+        agg.get_anime_episodes("a1").await.unwrap();
+        let pool = agg.database().pool().clone();
+        let series_id = dao::find_series_id_by_source_external(&pool, "src", "a1")
+            .await
+            .unwrap()
+            .unwrap();
+
+        let fetched = agg
+            .get_episode_streams_for_series("e2", None)
+            .await
+            .unwrap();
+        assert_eq!(fetched.streams.len(), 1);
+        assert_eq!(fetched.episode_id, None);
+
+        let fetched = agg
+            .get_episode_streams_for_series("e2", Some(&series_id))
+            .await
+            .unwrap();
+        let episode_id = fetched.episode_id.unwrap();
+        assert_eq!(
+            dao::find_episode_id_by_source_external(&pool, "src", "e2")
+                .await
+                .unwrap(),
+            Some(episode_id)
+        );
+    }
+
+    #[tokio::test]
+    async fn refetched_streams_update_quality_without_erasing_it() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut agg = aggregator(&dir).await;
+        let src = MockSource::new().with_units("a1", vec![unit("e1", 1.0, UnitKind::Episode)]);
+        agg.register_mock_source("src", src);
+        agg.get_anime_episodes("a1").await.unwrap();
+        let pool = agg.database().pool().clone();
+        let episode_id = dao::find_episode_id_by_source_external(&pool, "src", "e1")
+            .await
+            .unwrap()
+            .unwrap();
+        let stream = |quality: Option<&str>, mime: Option<&str>| dao::StreamInsert {
+            episode_id: episode_id.clone(),
+            url: "mock://e1.m3u8".to_string(),
+            quality: quality.map(str::to_string),
+            mime: mime.map(str::to_string),
+        };
+
+        dao::upsert_streams(&pool, &episode_id, &[stream(None, None)])
+            .await
+            .unwrap();
+        dao::upsert_streams(&pool, &episode_id, &[stream(Some("720p"), None)])
+            .await
+            .unwrap();
+        dao::upsert_streams(&pool, &episode_id, &[stream(None, Some("video/mp2t"))])
+            .await
+            .unwrap();
+        let stored = dao::list_streams(&pool, &episode_id).await.unwrap();
+        assert_eq!(stored.len(), 1);
+        assert_eq!(stored[0].quality.as_deref(), Some("720p"));
+        assert_eq!(stored[0].mime.as_deref(), Some("video/mp2t"));
+    }
+
+    #[tokio::test]
+    async fn host_overrides_keep_one_host_and_are_reapplied_after_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut agg = aggregator(&dir).await;
+        let src = || MockSource::new().with_allowed_hosts(&["a.com"]);
+        agg.register_mock_source("src", src());
+
+        agg.set_host_allowed("src", "B.com", true).await.unwrap();
+        agg.set_host_allowed("src", "a.com", false).await.unwrap();
+        assert!(agg.set_host_allowed("src", "b.com", false).await.is_err());
+        let hosts = agg.get_plugin_allowed_hosts("src").await.unwrap();
+        assert_eq!(hosts, Some(vec!["b.com".to_string()]));
+        let pool = agg.database().pool().clone();
+        assert_eq!(
+            dao::list_plugin_host_overrides(&pool).await.unwrap().len(),
+            2
+        );
+
+        let mut restarted = aggregator(&dir).await;
+        restarted.register_mock_source("src", src());
+        restarted.apply_host_overrides().await.unwrap();
+        let hosts = restarted.get_plugin_allowed_hosts("src").await.unwrap();
+        assert_eq!(hosts, Some(vec!["b.com".to_string()]));
+    }
+}
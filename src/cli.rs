@@ -35,6 +35,9 @@ pub enum Commands {
         /// Refresh capabilities by calling each plugin
         #[arg(long)]
         refresh: bool,
+        /// Output JSON (per-plugin plus the union across plugins)
+        #[arg(long)]
+        json: bool,
     },
     /// Show allowed hosts per plugin
     AllowedHosts,
@@ -116,7 +119,7 @@ pub enum DownloadCmd {
     Chapter {
         /// Chapter ID
         chapter_id: String,
-        /// Output directory (created if missing). If --cbz is used, this is the .cbz path. If omitted, use the series download_path and auto-name.
+        /// Output directory (created if missing). If --cbz is used, this is the .cbz path. If omitted, use the series download directory (stored path or download root) and auto-name.
         #[arg(long)]
         out: Option<String>,
         /// Create a .cbz instead of files on disk
@@ -133,7 +136,7 @@ pub enum DownloadCmd {
     Episode {
         /// Episode ID
         episode_id: String,
-        /// Output file. If omitted, use the series download_path and auto-name.
+        /// Output file. If omitted, use the series download directory (stored path or download root) and auto-name.
         #[arg(long)]
         out: Option<String>,
         /// Select stream by index (default 0)
@@ -144,7 +147,7 @@ pub enum DownloadCmd {
     Series {
         /// Series ID (canonical)
         series_id: String,
-        /// Output directory; if omitted, use the stored series download_path or a folder named from the title
+        /// Output directory; if omitted, use the stored series download_path or a folder named from the title under the download root (TOURING_DOWNLOAD_ROOT)
         #[arg(long)]
         out: Option<String>,
         /// For manga, create .cbz files instead of folders with images
@@ -0,0 +1,91 @@
+//! `ComicInfo.xml` (ComicRack schema v2) for CBZ archives, read by Komga, Kavita and most
+//! comic readers.
+
+/// Metadata for one chapter archive; `None` fields are omitted.
+#[derive(Debug, Clone, Default)]
+pub struct ComicInfo {
+    pub series: String,
+    pub title: Option<String>,
+    pub number: Option<String>,
+    /// Free-form volume label ("Vol. 2", "2"); only its first integer is written.
+    pub volume: Option<String>,
+    pub language: Option<String>,
+    pub page_count: usize,
+}
+
+impl ComicInfo {
+    /// Serialize with elements in schema order.
+    pub fn to_xml(&self) -> String {
+        let mut xml = String::from(
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<ComicInfo xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\" xmlns:xsd=\"http://www.w3.org/2001/XMLSchema\">\n",
+        );
+        push_element(&mut xml, "Title", self.title.as_deref());
+        push_element(&mut xml, "Series", Some(&self.series));
+        push_element(&mut xml, "Number", self.number.as_deref());
+        let volume = self.volume.as_deref().and_then(first_integer);
+        push_element(&mut xml, "Volume", volume.as_deref());
+        push_element(&mut xml, "PageCount", Some(&self.page_count.to_string()));
+        push_element(&mut xml, "LanguageISO", self.language.as_deref());
+        xml.push_str("</ComicInfo>\n");
+        xml
+    }
+}
+
+fn push_element(xml: &mut String, name: &str, value: Option<&str>) {
+    let Some(value) = value.map(str::trim).filter(|v| !v.is_empty()) else {
+        return;
+    };
+    xml.push_str(&format!("  <{name}>{}</{name}>\n", escape(value)));
+}
+
+fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            // Control characters are not allowed in XML 1.0
+            c if c.is_control() && !matches!(c, '\t' | '\n' | '\r') => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+fn first_integer(s: &str) -> Option<String> {
+    let start = s.find(|c: char| c.is_ascii_digit())?;
+    let digits: String = s[start..]
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .collect();
+    let trimmed = digits.trim_start_matches('0');
+    Some(if trimmed.is_empty() { "0" } else { trimmed }.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_escaped_fields_and_skips_missing_ones() {
+        let xml = ComicInfo {
+            series: "Tom & Jerry <Classic>".into(),
+            title: Some("The \"Chase\"".into()),
+            number: Some("12.5".into()),
+            volume: Some("Vol. 03".into()),
+            language: None,
+            page_count: 24,
+        }
+        .to_xml();
+        assert!(xml.contains("<Series>Tom &amp; Jerry &lt;Classic&gt;</Series>"));
+        assert!(xml.contains("<Title>The &quot;Chase&quot;</Title>"));
+        assert!(xml.contains("<Number>12.5</Number>"));
+        assert!(xml.contains("<Volume>3</Volume>"));
+        assert!(xml.contains("<PageCount>24</PageCount>"));
+        assert!(!xml.contains("LanguageISO"));
+        assert!(xml.find("<Title>") < xml.find("<Series>"));
+    }
+}
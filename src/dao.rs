@@ -2,7 +2,7 @@ use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use sqlx::AnyPool;
 
-use crate::ChapterProgress;
+use crate::{ChapterProgress, IdKind};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SourceInsert {
@@ -67,6 +67,19 @@ pub struct EpisodeInsert {
     pub published_at: Option<String>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SectionInsert {
+    pub id: String,
+    pub series_id: String,
+    pub source_id: String,
+    pub external_id: String,
+    pub number_text: Option<String>,
+    pub number_num: Option<f64>,
+    pub title: Option<String>,
+    pub lang: Option<String>,
+    pub position: i64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StreamInsert {
     pub episode_id: String,
@@ -75,6 +88,16 @@ pub struct StreamInsert {
     pub mime: Option<String>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DownloadInsert {
+    pub unit_id: String,
+    pub kind: String, // "chapter" | "episode"
+    pub path: String,
+    pub size_bytes: i64,
+    pub sha256: String,
+    pub page_count: Option<i64>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SeriesPref {
     pub series_id: String,
@@ -92,6 +115,16 @@ pub async fn upsert_source(pool: &AnyPool, src: &SourceInsert) -> Result<()> {
     Ok(())
 }
 
+/// (id, version, created_at, updated_at) for every row in `sources`.
+pub async fn list_sources(pool: &AnyPool) -> Result<Vec<(String, String, String, String)>> {
+    let rows = sqlx::query_as::<_, (String, String, String, String)>(
+        "SELECT id, version, CAST(created_at AS TEXT), CAST(updated_at AS TEXT) FROM sources ORDER BY id",
+    )
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
+
 pub async fn upsert_series(pool: &AnyPool, s: &SeriesInsert) -> Result<()> {
     sqlx::query(
         "INSERT INTO series(id, kind, title, alt_titles, description, cover_url, tags, status)\n         VALUES(?, ?, ?, ?, ?, ?, ?, ?)\n         ON CONFLICT(id) DO UPDATE SET\n           kind=excluded.kind, title=excluded.title, alt_titles=excluded.alt_titles,\n           description=excluded.description, cover_url=excluded.cover_url,\n           tags=excluded.tags, status=excluded.status, updated_at=CURRENT_TIMESTAMP",
@@ -109,6 +142,59 @@ pub async fn upsert_series(pool: &AnyPool, s: &SeriesInsert) -> Result<()> {
     Ok(())
 }
 
+/// Swap a series' external id on one source in a single transaction. Returns false (and
+/// changes nothing) if the old mapping doesn't exist; errors if another series already
+/// maps `new_external` on that source.
+pub async fn replace_series_source(
+    pool: &AnyPool,
+    series_id: &str,
+    source_id: &str,
+    old_external: &str,
+    new_external: &str,
+) -> Result<bool> {
+    let mut tx = pool.begin().await?;
+    let other: Option<String> = sqlx::query_scalar(
+        "SELECT series_id FROM series_sources
+         WHERE source_id = ? AND external_id = ? AND series_id <> ? LIMIT 1",
+    )
+    .bind(source_id)
+    .bind(new_external)
+    .bind(series_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+    if let Some(other) = other {
+        return Err(anyhow::anyhow!(
+            "{}:{} is already mapped to series {}",
+            source_id,
+            new_external,
+            other
+        ));
+    }
+    let removed = sqlx::query(
+        "DELETE FROM series_sources WHERE series_id = ? AND source_id = ? AND external_id = ?",
+    )
+    .bind(series_id)
+    .bind(source_id)
+    .bind(old_external)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+    if removed == 0 {
+        return Ok(false);
+    }
+    sqlx::query(
+        "INSERT INTO series_sources(series_id, source_id, external_id) VALUES(?, ?, ?)
+         ON CONFLICT(series_id, source_id, external_id) DO UPDATE SET last_synced_at=CURRENT_TIMESTAMP",
+    )
+    .bind(series_id)
+    .bind(source_id)
+    .bind(new_external)
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+    Ok(true)
+}
+
 pub async fn upsert_series_source(pool: &AnyPool, ss: &SeriesSourceInsert) -> Result<()> {
     sqlx::query(
         "INSERT INTO series_sources(series_id, source_id, external_id) VALUES(?, ?, ?)\n         ON CONFLICT(series_id, source_id, external_id) DO UPDATE SET last_synced_at=CURRENT_TIMESTAMP",
@@ -121,25 +207,69 @@ pub async fn upsert_series_source(pool: &AnyPool, ss: &SeriesSourceInsert) -> Re
     Ok(())
 }
 
+// Relative dates resolve against the time of the write
+fn published_epoch(published_at: Option<&str>) -> Option<i64> {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+    published_at.and_then(|p| crate::mapping::parse_published_epoch(p, now))
+}
+
 pub async fn upsert_chapter(pool: &AnyPool, c: &ChapterInsert) -> Result<()> {
     sqlx::query(
-        "INSERT INTO chapters(\n            id, series_id, source_id, external_id, number_text, number_num, title, lang, volume, published_at\n         ) VALUES(?, ?, ?, ?, ?, ?, ?, ?, ?, ?)\n         ON CONFLICT(series_id, source_id, external_id) DO UPDATE SET\n           id=excluded.id, number_text=excluded.number_text, number_num=excluded.number_num,\n           title=excluded.title, lang=excluded.lang, volume=excluded.volume,\n           published_at=excluded.published_at, updated_at=CURRENT_TIMESTAMP",
+        "INSERT INTO chapters(\n            id, series_id, source_id, external_id, number_text, number_num, title, lang, volume, published_at, published_epoch\n         ) VALUES(?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)\n         ON CONFLICT(series_id, source_id, external_id) DO UPDATE SET\n           id=excluded.id, number_text=excluded.number_text, number_num=excluded.number_num,\n           title=excluded.title, lang=excluded.lang, volume=excluded.volume,\n           published_at=excluded.published_at,\n           published_epoch=excluded.published_epoch, updated_at=CURRENT_TIMESTAMP",
     )
     .bind(&c.id)
     .bind(&c.series_id)
     .bind(&c.source_id)
     .bind(&c.external_id)
     .bind(&c.number_text)
-    .bind(&c.number_num)
+    .bind(c.number_num)
     .bind(&c.title)
     .bind(&c.lang)
     .bind(&c.volume)
     .bind(&c.published_at)
+    .bind(published_epoch(c.published_at.as_deref()))
     .execute(pool)
     .await?;
     Ok(())
 }
 
+/// Upsert a source's chapter list in one transaction. A row already stored under the same
+/// (series, source, external id) keeps its canonical id, so callers can pass fresh ids for
+/// every row. A row that fails (e.g. a constraint) is logged and skipped. Returns rows written.
+pub async fn upsert_chapters(pool: &AnyPool, chapters: &[ChapterInsert]) -> Result<usize> {
+    let mut tx = pool.begin().await?;
+    let mut written = 0;
+    for c in chapters {
+        let result = sqlx::query(
+            "INSERT INTO chapters(\n            id, series_id, source_id, external_id, number_text, number_num, title, lang, volume, published_at, published_epoch\n         ) VALUES(?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)\n         ON CONFLICT(series_id, source_id, external_id) DO UPDATE SET\n           number_text=excluded.number_text, number_num=excluded.number_num,\n           title=excluded.title, lang=excluded.lang, volume=excluded.volume,\n           published_at=excluded.published_at,\n           published_epoch=excluded.published_epoch, updated_at=CURRENT_TIMESTAMP",
+        )
+        .bind(&c.id)
+        .bind(&c.series_id)
+        .bind(&c.source_id)
+        .bind(&c.external_id)
+        .bind(&c.number_text)
+        .bind(c.number_num)
+        .bind(&c.title)
+        .bind(&c.lang)
+        .bind(&c.volume)
+        .bind(&c.published_at)
+        .bind(published_epoch(c.published_at.as_deref()))
+        .execute(&mut *tx)
+        .await;
+        match result {
+            Ok(_) => written += 1,
+            Err(e) => {
+                tracing::warn!(chapter = %c.external_id, error = %e, "failed to store chapter")
+            }
+        }
+    }
+    tx.commit().await?;
+    Ok(written)
+}
+
 pub async fn upsert_chapter_images(pool: &AnyPool, images: &[ChapterImageInsert]) -> Result<()> {
     let mut tx = pool.begin().await?;
     for img in images {
@@ -150,8 +280,8 @@ pub async fn upsert_chapter_images(pool: &AnyPool, images: &[ChapterImageInsert]
         .bind(img.idx)
         .bind(&img.url)
         .bind(&img.mime)
-        .bind(&img.width)
-        .bind(&img.height)
+        .bind(img.width)
+        .bind(img.height)
         .execute(&mut *tx)
         .await?;
     }
@@ -161,23 +291,27 @@ pub async fn upsert_chapter_images(pool: &AnyPool, images: &[ChapterImageInsert]
 
 pub async fn upsert_episode(pool: &AnyPool, e: &EpisodeInsert) -> Result<()> {
     sqlx::query(
-        "INSERT INTO episodes(\n            id, series_id, source_id, external_id, number_text, number_num, title, lang, season, published_at\n         ) VALUES(?, ?, ?, ?, ?, ?, ?, ?, ?, ?)\n         ON CONFLICT(series_id, source_id, external_id) DO UPDATE SET\n           id=excluded.id, number_text=excluded.number_text, number_num=excluded.number_num,\n           title=excluded.title, lang=excluded.lang, season=excluded.season,\n           published_at=excluded.published_at, updated_at=CURRENT_TIMESTAMP",
+        "INSERT INTO episodes(\n            id, series_id, source_id, external_id, number_text, number_num, title, lang, season, published_at, published_epoch\n         ) VALUES(?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)\n         ON CONFLICT(series_id, source_id, external_id) DO UPDATE SET\n           id=excluded.id, number_text=excluded.number_text, number_num=excluded.number_num,\n           title=excluded.title, lang=excluded.lang, season=excluded.season,\n           published_at=excluded.published_at,\n           published_epoch=excluded.published_epoch, updated_at=CURRENT_TIMESTAMP",
     )
     .bind(&e.id)
     .bind(&e.series_id)
     .bind(&e.source_id)
     .bind(&e.external_id)
     .bind(&e.number_text)
-    .bind(&e.number_num)
+    .bind(e.number_num)
     .bind(&e.title)
     .bind(&e.lang)
     .bind(&e.season)
     .bind(&e.published_at)
+    .bind(published_epoch(e.published_at.as_deref()))
     .execute(pool)
     .await?;
     Ok(())
 }
 
+/// Store an episode's streams, one row per (episode_id, url) (unique since migration 0004).
+/// A URL seen again takes the newer quality/mime, but a missing value never erases one
+/// learned from an earlier fetch.
 pub async fn upsert_streams(
     pool: &AnyPool,
     episode_id: &str,
@@ -186,7 +320,7 @@ pub async fn upsert_streams(
     let mut tx = pool.begin().await?;
     for s in streams {
         sqlx::query(
-            "INSERT INTO streams(episode_id, url, quality, mime) VALUES(?, ?, ?, ?)\n             ON CONFLICT DO NOTHING",
+            "INSERT INTO streams(episode_id, url, quality, mime) VALUES(?, ?, ?, ?)\n             ON CONFLICT(episode_id, url) DO UPDATE SET\n               quality=COALESCE(excluded.quality, streams.quality),\n               mime=COALESCE(excluded.mime, streams.mime)",
         )
         .bind(episode_id)
         .bind(&s.url)
@@ -199,6 +333,25 @@ pub async fn upsert_streams(
     Ok(())
 }
 
+/// Stored streams for an episode in insertion order.
+pub async fn list_streams(pool: &AnyPool, episode_id: &str) -> Result<Vec<StreamInsert>> {
+    let rows = sqlx::query_as::<_, (String, String, String)>(
+        "SELECT url, COALESCE(quality, ''), COALESCE(mime, '') FROM streams WHERE episode_id = ? ORDER BY id",
+    )
+    .bind(episode_id)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows
+        .into_iter()
+        .map(|(url, quality, mime)| StreamInsert {
+            episode_id: episode_id.to_string(),
+            url,
+            quality: non_empty(quality),
+            mime: non_empty(mime),
+        })
+        .collect())
+}
+
 // New helpers for canonical identity
 pub async fn find_series_id_by_source_external(
     pool: &AnyPool,
@@ -232,6 +385,59 @@ pub async fn find_chapter_id_by_mapping(
     Ok(id)
 }
 
+/// Chapter stored for `external_id` on `source_id`, whatever series it belongs to. The same
+/// pair only repeats if one source listing is mapped to two series; the oldest row wins.
+pub async fn find_chapter_id_by_source_external(
+    pool: &AnyPool,
+    source_id: &str,
+    external_id: &str,
+) -> Result<Option<String>> {
+    let id = sqlx::query_scalar::<_, String>(
+        "SELECT id FROM chapters WHERE source_id = ? AND external_id = ? ORDER BY created_at, id LIMIT 1",
+    )
+    .bind(source_id)
+    .bind(external_id)
+    .fetch_optional(pool)
+    .await?;
+    Ok(id)
+}
+
+/// (source_id, external_id) mappings for a series.
+pub async fn list_series_sources(pool: &AnyPool, series_id: &str) -> Result<Vec<(String, String)>> {
+    let rows = sqlx::query_as::<_, (String, String)>(
+        "SELECT source_id, external_id FROM series_sources WHERE series_id = ?",
+    )
+    .bind(series_id)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
+
+/// Distinct chapter languages per source for a series, sorted by source then language.
+/// Sources whose chapters carry no language are listed with an empty Vec.
+pub async fn series_languages_by_source(
+    pool: &AnyPool,
+    series_id: &str,
+) -> Result<Vec<(String, Vec<String>)>> {
+    let rows = sqlx::query_as::<_, (String, String)>(
+        "SELECT DISTINCT source_id, COALESCE(TRIM(lang), '') FROM chapters WHERE series_id = ?
+         ORDER BY source_id, 2",
+    )
+    .bind(series_id)
+    .fetch_all(pool)
+    .await?;
+    let mut out: Vec<(String, Vec<String>)> = Vec::new();
+    for (source, lang) in rows {
+        if out.last().map(|(s, _)| s) != Some(&source) {
+            out.push((source, Vec::new()));
+        }
+        if !lang.is_empty() {
+            out.last_mut().unwrap().1.push(lang);
+        }
+    }
+    Ok(out)
+}
+
 pub async fn find_episode_id_by_mapping(
     pool: &AnyPool,
     series_id: &str,
@@ -265,6 +471,42 @@ pub async fn find_episode_id_by_source_external(
     Ok(id)
 }
 
+// Resolve a chapter by series + number. Numbers come from f32 plugin data, so compare with a
+// small tolerance. `lang` filters strictly; `prefer_source` only orders the candidates.
+pub async fn find_chapter_id_by_number(
+    pool: &AnyPool,
+    series_id: &str,
+    number: f64,
+    lang: Option<&str>,
+    prefer_source: Option<&str>,
+) -> Result<Option<String>> {
+    let id = sqlx::query_scalar::<_, String>(
+        "SELECT id FROM chapters
+         WHERE series_id = ? AND number_num IS NOT NULL AND ABS(number_num - ?) < 0.001
+           AND (? IS NULL OR lang = ?)
+         ORDER BY CASE WHEN source_id = ? THEN 0 ELSE 1 END, created_at
+         LIMIT 1",
+    )
+    .bind(series_id)
+    .bind(number)
+    .bind(lang)
+    .bind(lang)
+    .bind(prefer_source.unwrap_or(""))
+    .fetch_optional(pool)
+    .await?;
+    Ok(id)
+}
+
+/// Stored `chapter_images` rows for a canonical chapter.
+pub async fn count_chapter_images(pool: &AnyPool, chapter_id: &str) -> Result<i64> {
+    let n =
+        sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM chapter_images WHERE chapter_id = ?")
+            .bind(chapter_id)
+            .fetch_one(pool)
+            .await?;
+    Ok(n)
+}
+
 pub async fn find_chapter_identity(
     pool: &AnyPool,
     chapter_id_or_external: &str,
@@ -336,6 +578,58 @@ pub async fn upsert_chapter_progress(
     Ok(())
 }
 
+// Reading sessions: extend the chapter's latest session if it was updated within
+// `idle_gap_secs`, otherwise start a new one.
+pub async fn record_reading_activity(
+    pool: &AnyPool,
+    chapter_id: &str,
+    series_id: &str,
+    now: i64,
+    idle_gap_secs: i64,
+) -> Result<()> {
+    let res = sqlx::query(
+        "UPDATE reading_sessions SET last_update_at = ?
+         WHERE id = (SELECT id FROM reading_sessions WHERE chapter_id = ? ORDER BY last_update_at DESC LIMIT 1)
+           AND last_update_at >= ?",
+    )
+    .bind(now)
+    .bind(chapter_id)
+    .bind(now - idle_gap_secs)
+    .execute(pool)
+    .await?;
+    if res.rows_affected() == 0 {
+        sqlx::query(
+            "INSERT INTO reading_sessions(chapter_id, series_id, started_at, last_update_at) VALUES(?, ?, ?, ?)",
+        )
+        .bind(chapter_id)
+        .bind(series_id)
+        .bind(now)
+        .bind(now)
+        .execute(pool)
+        .await?;
+    }
+    Ok(())
+}
+
+/// Sum of session spans in seconds, for one series or the whole library.
+pub async fn reading_time_secs(pool: &AnyPool, series_id: Option<&str>) -> Result<i64> {
+    let secs: i64 = if let Some(sid) = series_id {
+        sqlx::query_scalar(
+            "SELECT COALESCE(SUM(last_update_at - started_at), 0) FROM reading_sessions WHERE series_id = ?",
+        )
+        .bind(sid)
+        .fetch_one(pool)
+        .await?
+    } else {
+        sqlx::query_scalar(
+            "SELECT COALESCE(SUM(last_update_at - started_at), 0) FROM reading_sessions",
+        )
+        .fetch_one(pool)
+        .await?
+    };
+    Ok(secs)
+}
+
 pub async fn clear_chapter_progress(pool: &AnyPool, chapter_id: &str) -> Result<u64> {
     let res = sqlx::query("DELETE FROM chapter_progress WHERE chapter_id = ?")
         .bind(chapter_id)
@@ -344,6 +638,90 @@ pub async fn clear_chapter_progress(pool: &AnyPool, chapter_id: &str) -> Result<
     Ok(res.rows_affected())
 }
 
+/// Progress rows for one source's chapters of a series:
+/// (chapter_id, number_num, lang, page_index, total_pages, updated_at).
+pub async fn list_progress_for_source(
+    pool: &AnyPool,
+    series_id: &str,
+    source_id: &str,
+) -> Result<Vec<(String, Option<f64>, Option<String>, i64, Option<i64>, i64)>> {
+    let rows = sqlx::query_as::<_, (String, f64, i64, String, i64, i64, i64)>(
+        "SELECT c.id, COALESCE(c.number_num, 0.0), c.number_num IS NOT NULL, COALESCE(c.lang, ''),
+                p.page_index, COALESCE(p.total_pages, -1), p.updated_at
+         FROM chapter_progress p JOIN chapters c ON c.id = p.chapter_id
+         WHERE c.series_id = ? AND c.source_id = ?",
+    )
+    .bind(series_id)
+    .bind(source_id)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows
+        .into_iter()
+        .map(|(id, num, has_num, lang, page, total, updated)| {
+            (
+                id,
+                (has_num != 0).then_some(num),
+                non_empty(lang),
+                page,
+                (total >= 0).then_some(total),
+                updated,
+            )
+        })
+        .collect())
+}
+
+/// The chapter of `source_id` in a series with the given number, preferring `lang`.
+pub async fn find_source_chapter_by_number(
+    pool: &AnyPool,
+    series_id: &str,
+    source_id: &str,
+    number: f64,
+    lang: Option<&str>,
+) -> Result<Option<String>> {
+    let id = sqlx::query_scalar::<_, String>(
+        "SELECT id FROM chapters
+         WHERE series_id = ? AND source_id = ? AND number_num IS NOT NULL AND ABS(number_num - ?) < 0.001
+         ORDER BY CASE WHEN lang = ? THEN 0 ELSE 1 END, id
+         LIMIT 1",
+    )
+    .bind(series_id)
+    .bind(source_id)
+    .bind(number)
+    .bind(lang)
+    .fetch_optional(pool)
+    .await?;
+    Ok(id)
+}
+
+/// Write progress carried over from another chapter, keeping its timestamp. Existing progress
+/// on the destination is only replaced when the incoming one is newer.
+pub async fn merge_chapter_progress(
+    pool: &AnyPool,
+    chapter_id: &str,
+    series_id: &str,
+    page_index: i64,
+    total_pages: Option<i64>,
+    updated_at: i64,
+) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO chapter_progress(chapter_id, series_id, page_index, total_pages, updated_at)
+         VALUES(?, ?, ?, ?, ?)
+         ON CONFLICT(chapter_id) DO UPDATE SET
+           page_index=excluded.page_index,
+           total_pages=excluded.total_pages,
+           updated_at=excluded.updated_at
+         WHERE excluded.updated_at > chapter_progress.updated_at",
+    )
+    .bind(chapter_id)
+    .bind(series_id)
+    .bind(page_index)
+    .bind(total_pages)
+    .bind(updated_at)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
 pub async fn get_chapter_progress(
     pool: &AnyPool,
     chapter_id: &str,
@@ -393,78 +771,487 @@ pub async fn get_chapter_progress_for_series(
         .collect())
 }
 
-// New: preferences
-pub async fn get_series_pref(pool: &AnyPool, series_id: &str) -> Result<Option<SeriesPref>> {
-    // Use COALESCE to avoid decoding NULL directly into Option<String> with the Any driver
-    let opt: Option<String> = sqlx::query_scalar::<_, String>(
-        "SELECT COALESCE(download_path, '') FROM series_prefs WHERE series_id = ?",
+/// Newest progress row of each series, most recently read series first (ties by chapter id).
+pub async fn latest_progress_per_series(
+    pool: &AnyPool,
+    limit: i64,
+) -> Result<Vec<ChapterProgress>> {
+    let rows = sqlx::query_as::<_, (String, String, i64, i64, i64)>(
+        "SELECT p.chapter_id, p.series_id, p.page_index, COALESCE(p.total_pages, -1), p.updated_at
+         FROM chapter_progress p JOIN series s ON s.id = p.series_id
+         WHERE p.chapter_id = (
+             SELECT p2.chapter_id FROM chapter_progress p2 WHERE p2.series_id = p.series_id
+             ORDER BY p2.updated_at DESC, p2.chapter_id DESC LIMIT 1
+         )
+         ORDER BY p.updated_at DESC, p.series_id
+         LIMIT ?",
     )
-    .bind(series_id)
-    .fetch_optional(pool)
+    .bind(limit)
+    .fetch_all(pool)
     .await?;
+    Ok(rows
+        .into_iter()
+        .map(
+            |(chapter_id, series_id, page_index, total, updated_at)| ChapterProgress {
+                chapter_id,
+                series_id,
+                page_index,
+                total_pages: (total >= 0).then_some(total),
+                updated_at,
+            },
+        )
+        .collect())
+}
 
-    match opt {
-        None => Ok(None),
-        Some(s) if s.is_empty() => Ok(Some(SeriesPref {
-            series_id: series_id.to_string(),
-            download_path: None,
-        })),
-        Some(s) => Ok(Some(SeriesPref {
-            series_id: series_id.to_string(),
-            download_path: Some(s),
-        })),
-    }
+/// Aggregate counts for one series (see `Touring::get_series_statistics`).
+#[derive(Debug, Clone, Default)]
+pub struct SeriesStatsRow {
+    pub chapters: i64,
+    pub episodes: i64,
+    pub read_chapters: i64,
+    pub downloaded_units: i64,
+    pub total_pages: i64,
+    pub languages: Vec<String>,
+    pub sources: i64,
 }
 
-pub async fn set_series_download_path(
-    pool: &AnyPool,
-    series_id: &str,
-    path: Option<&str>,
-) -> Result<()> {
-    // Ensure the series exists to avoid FK violations and provide a clearer error
-    let exists: Option<i64> = sqlx::query_scalar("SELECT 1 FROM series WHERE id = ?")
+/// 0..=100 from `page_index`/`total_pages`; `None` without progress or a known page count.
+pub async fn chapter_read_percentage(pool: &AnyPool, chapter_id: &str) -> Result<Option<f64>> {
+    Ok(sqlx::query_scalar::<_, f64>(
+        "SELECT MIN(100.0, (page_index + 1) * 100.0 / total_pages) FROM chapter_progress
+         WHERE chapter_id = ? AND total_pages > 0",
+    )
+    .bind(chapter_id)
+    .fetch_optional(pool)
+    .await?)
+}
+
+/// Mean chapter completion (0..=100) over all chapters of a series; chapters without
+/// progress or a known page count count as unread.
+pub async fn series_read_percentage(pool: &AnyPool, series_id: &str) -> Result<f64> {
+    Ok(sqlx::query_scalar::<_, f64>(
+        "SELECT COALESCE(AVG(CASE WHEN p.total_pages > 0
+                THEN MIN(1.0, (p.page_index + 1) * 1.0 / p.total_pages) ELSE 0.0 END), 0.0) * 100.0
+         FROM chapters c LEFT JOIN chapter_progress p ON p.chapter_id = c.id
+         WHERE c.series_id = ?",
+    )
+    .bind(series_id)
+    .fetch_one(pool)
+    .await?)
+}
+
+/// Chapters in the whole library not yet read to their last page (see `series_statistics`).
+pub async fn count_unread_chapters(pool: &AnyPool) -> Result<i64> {
+    Ok(sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) - COALESCE(SUM(CASE WHEN p.total_pages IS NOT NULL
+                AND p.page_index + 1 >= p.total_pages THEN 1 ELSE 0 END), 0)
+         FROM chapters c LEFT JOIN chapter_progress p ON p.chapter_id = c.id",
+    )
+    .fetch_one(pool)
+    .await?)
+}
+
+// A chapter counts as read once its last page has been reached (page_index is 0-based);
+// page totals prefer stored image rows over the reader-reported total.
+pub async fn series_statistics(pool: &AnyPool, series_id: &str) -> Result<SeriesStatsRow> {
+    let (chapters, read_chapters, downloaded_chapters, total_pages) =
+        sqlx::query_as::<_, (i64, i64, i64, i64)>(
+            "SELECT COUNT(*),
+                COALESCE(SUM(CASE WHEN p.total_pages IS NOT NULL AND p.page_index + 1 >= p.total_pages
+                    THEN 1 ELSE 0 END), 0),
+                COALESCE(SUM(EXISTS(SELECT 1 FROM downloads d WHERE d.unit_id = c.id)), 0),
+                COALESCE(SUM(MAX(
+                    (SELECT COUNT(*) FROM chapter_images ci WHERE ci.chapter_id = c.id),
+                    COALESCE(p.total_pages, 0))), 0)
+             FROM chapters c LEFT JOIN chapter_progress p ON p.chapter_id = c.id
+             WHERE c.series_id = ?",
+        )
         .bind(series_id)
-        .fetch_optional(pool)
+        .fetch_one(pool)
         .await?;
-    if exists.is_none() {
-        return Err(anyhow::anyhow!("Series not found: {}", series_id));
-    }
 
-    sqlx::query(
-        "INSERT INTO series_prefs(series_id, download_path) VALUES(?, ?)\n         ON CONFLICT(series_id) DO UPDATE SET download_path=excluded.download_path, updated_at=CURRENT_TIMESTAMP",
+    let (episodes, downloaded_episodes, sources) = sqlx::query_as::<_, (i64, i64, i64)>(
+        "SELECT (SELECT COUNT(*) FROM episodes WHERE series_id = ?),
+            (SELECT COUNT(*) FROM episodes e WHERE e.series_id = ?
+                AND EXISTS(SELECT 1 FROM downloads d WHERE d.unit_id = e.id)),
+            (SELECT COUNT(DISTINCT source_id) FROM series_sources WHERE series_id = ?)",
     )
     .bind(series_id)
-    .bind(path)
-    .execute(pool)
+    .bind(series_id)
+    .bind(series_id)
+    .fetch_one(pool)
     .await?;
-    Ok(())
+
+    let languages = sqlx::query_scalar::<_, String>(
+        "SELECT lang FROM chapters WHERE series_id = ? AND lang IS NOT NULL AND lang <> ''
+         UNION
+         SELECT lang FROM episodes WHERE series_id = ? AND lang IS NOT NULL AND lang <> ''
+         ORDER BY 1",
+    )
+    .bind(series_id)
+    .bind(series_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(SeriesStatsRow {
+        chapters,
+        episodes,
+        read_chapters,
+        downloaded_units: downloaded_chapters + downloaded_episodes,
+        total_pages,
+        languages,
+        sources,
+    })
 }
 
-// Deletion helpers (cascade removes children where FK declared)
-pub async fn delete_series(pool: &AnyPool, series_id: &str) -> Result<u64> {
-    let res = sqlx::query("DELETE FROM series WHERE id = ?")
+// Cheap existence probes (canonical ids only)
+pub async fn series_exists(pool: &AnyPool, series_id: &str) -> Result<bool> {
+    let exists: Option<i64> = sqlx::query_scalar("SELECT 1 FROM series WHERE id = ? LIMIT 1")
         .bind(series_id)
-        .execute(pool)
+        .fetch_optional(pool)
         .await?;
-    Ok(res.rows_affected())
+    Ok(exists.is_some())
 }
 
-pub async fn delete_chapter(pool: &AnyPool, chapter_id: &str) -> Result<u64> {
-    let res = sqlx::query("DELETE FROM chapters WHERE id = ?")
+pub async fn chapter_exists(pool: &AnyPool, chapter_id: &str) -> Result<bool> {
+    let exists: Option<i64> = sqlx::query_scalar("SELECT 1 FROM chapters WHERE id = ? LIMIT 1")
         .bind(chapter_id)
-        .execute(pool)
+        .fetch_optional(pool)
         .await?;
-    Ok(res.rows_affected())
+    Ok(exists.is_some())
 }
 
-pub async fn delete_episode(pool: &AnyPool, episode_id: &str) -> Result<u64> {
-    let res = sqlx::query("DELETE FROM episodes WHERE id = ?")
-        .bind(episode_id)
-        .execute(pool)
-        .await?;
+/// What `id` refers to; see `IdKind` for the resolution order.
+pub async fn describe_id(pool: &AnyPool, id: &str) -> Result<IdKind> {
+    for (table, kind) in [
+        ("series", IdKind::CanonicalSeries),
+        ("chapters", IdKind::CanonicalChapter),
+        ("episodes", IdKind::CanonicalEpisode),
+    ] {
+        let sql = format!("SELECT 1 FROM {table} WHERE id = ? LIMIT 1");
+        if sqlx::query_scalar::<_, i64>(&sql)
+            .bind(id)
+            .fetch_optional(pool)
+            .await?
+            .is_some()
+        {
+            return Ok(kind);
+        }
+    }
+    // (table, canonical id column); lowest source_id wins when several sources share the id
+    for (table, column) in [
+        ("series_sources", "series_id"),
+        ("chapters", "id"),
+        ("episodes", "id"),
+    ] {
+        let sql = format!(
+            "SELECT source_id, {column} FROM {table} WHERE external_id = ? ORDER BY source_id LIMIT 1"
+        );
+        if let Some((source_id, canonical)) = sqlx::query_as::<_, (String, String)>(&sql)
+            .bind(id)
+            .fetch_optional(pool)
+            .await?
+        {
+            return Ok(match table {
+                "series_sources" => IdKind::ExternalSeries {
+                    source_id,
+                    series_id: canonical,
+                },
+                "chapters" => IdKind::ExternalChapter {
+                    source_id,
+                    chapter_id: canonical,
+                },
+                _ => IdKind::ExternalEpisode {
+                    source_id,
+                    episode_id: canonical,
+                },
+            });
+        }
+    }
+    Ok(IdKind::Unknown)
+}
+
+// Activity timestamps: updated_at tracks content freshness, last_opened_at user activity
+pub async fn touch_series(pool: &AnyPool, series_id: &str) -> Result<()> {
+    let res = sqlx::query("UPDATE series SET updated_at = CURRENT_TIMESTAMP WHERE id = ?")
+        .bind(series_id)
+        .execute(pool)
+        .await?;
+    if res.rows_affected() == 0 {
+        return Err(anyhow::anyhow!("Series not found: {}", series_id));
+    }
+    Ok(())
+}
+
+pub async fn mark_series_opened(pool: &AnyPool, series_id: &str) -> Result<()> {
+    let res = sqlx::query("UPDATE series SET last_opened_at = unixepoch() WHERE id = ?")
+        .bind(series_id)
+        .execute(pool)
+        .await?;
+    if res.rows_affected() == 0 {
+        return Err(anyhow::anyhow!("Series not found: {}", series_id));
+    }
+    Ok(())
+}
+
+/// Distinct series titles starting with `prefix` (case-insensitive for ASCII), most recently
+/// opened first, then by stored chapter + episode count. `%` and `_` in the prefix match
+/// literally.
+pub async fn series_title_suggestions(
+    pool: &AnyPool,
+    prefix: &str,
+    limit: usize,
+) -> Result<Vec<String>> {
+    let escaped = prefix
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    let rows = sqlx::query_scalar::<_, String>(
+        "SELECT s.title FROM series s WHERE s.title LIKE ? ESCAPE '\\'
+         GROUP BY s.title
+         ORDER BY MAX(COALESCE(s.last_opened_at, 0)) DESC,
+                  MAX((SELECT COUNT(*) FROM chapters c WHERE c.series_id = s.id)
+                      + (SELECT COUNT(*) FROM episodes e WHERE e.series_id = s.id)) DESC,
+                  s.title
+         LIMIT ?",
+    )
+    .bind(format!("{}%", escaped))
+    .bind(limit.min(i64::MAX as usize) as i64)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
+
+/// (id, title, last_opened_at) for series opened at least once, most recent first.
+pub async fn list_recently_opened(
+    pool: &AnyPool,
+    limit: i64,
+) -> Result<Vec<(String, String, i64)>> {
+    let rows = sqlx::query_as::<_, (String, String, i64)>(
+        "SELECT id, title, last_opened_at FROM series WHERE last_opened_at IS NOT NULL ORDER BY last_opened_at DESC, title LIMIT ?",
+    )
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
+
+/// Series row with its download path and chapter/episode counts, as used by `SeriesInfo`.
+#[derive(Debug, Clone)]
+pub struct SeriesSummaryRow {
+    pub id: String,
+    pub kind: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub cover_url: Option<String>,
+    pub status: Option<String>,
+    pub download_path: Option<String>,
+    pub chapters: i64,
+    pub episodes: i64,
+}
+
+// Ids per statement; each is bound three times, staying under SQLite's 999-variable limit
+const SERIES_BATCH_CHUNK: usize = 300;
+
+/// Summaries for many series with one statement per 300 ids (prefs joined, counts grouped).
+/// Unknown ids are skipped; order is unspecified.
+pub async fn series_summaries(pool: &AnyPool, ids: &[String]) -> Result<Vec<SeriesSummaryRow>> {
+    let mut out = Vec::with_capacity(ids.len());
+    for chunk in ids.chunks(SERIES_BATCH_CHUNK) {
+        let placeholders = vec!["?"; chunk.len()].join(", ");
+        let sql = format!(
+            "SELECT s.id, s.kind, s.title, COALESCE(s.description, ''), COALESCE(s.cover_url, ''),
+                COALESCE(s.status, ''), COALESCE(p.download_path, ''),
+                COALESCE(c.n, 0), COALESCE(e.n, 0)
+             FROM series s
+             LEFT JOIN series_prefs p ON p.series_id = s.id
+             LEFT JOIN (SELECT series_id, COUNT(*) AS n FROM chapters
+                        WHERE series_id IN ({placeholders}) GROUP BY series_id) c
+               ON c.series_id = s.id
+             LEFT JOIN (SELECT series_id, COUNT(*) AS n FROM episodes
+                        WHERE series_id IN ({placeholders}) GROUP BY series_id) e
+               ON e.series_id = s.id
+             WHERE s.id IN ({placeholders})"
+        );
+        let mut query = sqlx::query_as::<
+            _,
+            (
+                String,
+                String,
+                String,
+                String,
+                String,
+                String,
+                String,
+                i64,
+                i64,
+            ),
+        >(&sql);
+        for _ in 0..3 {
+            for id in chunk {
+                query = query.bind(id);
+            }
+        }
+        for (id, kind, title, description, cover_url, status, path, chapters, episodes) in
+            query.fetch_all(pool).await?
+        {
+            out.push(SeriesSummaryRow {
+                id,
+                kind,
+                title,
+                description: non_empty(description),
+                cover_url: non_empty(cover_url),
+                status: non_empty(status),
+                download_path: non_empty(path),
+                chapters,
+                episodes,
+            });
+        }
+    }
+    Ok(out)
+}
+
+// New: preferences
+pub async fn get_series_pref(pool: &AnyPool, series_id: &str) -> Result<Option<SeriesPref>> {
+    // Use COALESCE to avoid decoding NULL directly into Option<String> with the Any driver
+    let opt: Option<String> = sqlx::query_scalar::<_, String>(
+        "SELECT COALESCE(download_path, '') FROM series_prefs WHERE series_id = ?",
+    )
+    .bind(series_id)
+    .fetch_optional(pool)
+    .await?;
+
+    match opt {
+        None => Ok(None),
+        Some(s) if s.is_empty() => Ok(Some(SeriesPref {
+            series_id: series_id.to_string(),
+            download_path: None,
+        })),
+        Some(s) => Ok(Some(SeriesPref {
+            series_id: series_id.to_string(),
+            download_path: Some(s),
+        })),
+    }
+}
+
+/// (series_id, download_path) for every series with a stored download folder.
+pub async fn list_series_download_paths(pool: &AnyPool) -> Result<Vec<(String, String)>> {
+    let rows = sqlx::query_as::<_, (String, String)>(
+        "SELECT series_id, download_path FROM series_prefs\n         WHERE download_path IS NOT NULL AND download_path <> '' ORDER BY series_id",
+    )
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
+
+pub async fn set_series_download_path(
+    pool: &AnyPool,
+    series_id: &str,
+    path: Option<&str>,
+) -> Result<()> {
+    // Ensure the series exists to avoid FK violations and provide a clearer error
+    if !series_exists(pool, series_id).await? {
+        return Err(anyhow::anyhow!("Series not found: {}", series_id));
+    }
+
+    sqlx::query(
+        "INSERT INTO series_prefs(series_id, download_path) VALUES(?, ?)\n         ON CONFLICT(series_id) DO UPDATE SET download_path=excluded.download_path, updated_at=CURRENT_TIMESTAMP",
+    )
+    .bind(series_id)
+    .bind(path)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Set many download paths in one transaction. Unknown series are skipped rather than
+/// failing the batch; returns (updated ids, skipped ids).
+pub async fn bulk_set_series_download_paths(
+    pool: &AnyPool,
+    entries: &[(String, Option<String>)],
+) -> Result<(Vec<String>, Vec<String>)> {
+    let mut tx = pool.begin().await?;
+    let (mut updated, mut skipped) = (Vec::new(), Vec::new());
+    for (series_id, path) in entries {
+        let exists: Option<i64> = sqlx::query_scalar("SELECT 1 FROM series WHERE id = ? LIMIT 1")
+            .bind(series_id)
+            .fetch_optional(&mut *tx)
+            .await?;
+        if exists.is_none() {
+            skipped.push(series_id.clone());
+            continue;
+        }
+        sqlx::query(
+            "INSERT INTO series_prefs(series_id, download_path) VALUES(?, ?)
+             ON CONFLICT(series_id) DO UPDATE SET download_path=excluded.download_path, updated_at=CURRENT_TIMESTAMP",
+        )
+        .bind(series_id)
+        .bind(path.as_deref())
+        .execute(&mut *tx)
+        .await?;
+        updated.push(series_id.clone());
+    }
+    tx.commit().await?;
+    Ok((updated, skipped))
+}
+
+// Deletion helpers (cascade removes children where FK declared)
+pub async fn delete_series(pool: &AnyPool, series_id: &str) -> Result<u64> {
+    let res = sqlx::query("DELETE FROM series WHERE id = ?")
+        .bind(series_id)
+        .execute(pool)
+        .await?;
     Ok(res.rows_affected())
 }
 
+/// Delete a chapter and its dependent rows (progress, reading sessions, images, section
+/// links, download records) in one transaction, without relying on FK cascades. Returns rows removed
+/// across all tables.
+pub async fn delete_chapter(pool: &AnyPool, chapter_id: &str) -> Result<u64> {
+    delete_unit_with_dependents(
+        pool,
+        chapter_id,
+        &[
+            "DELETE FROM chapter_progress WHERE chapter_id = ?",
+            "DELETE FROM reading_sessions WHERE chapter_id = ?",
+            "DELETE FROM chapter_images WHERE chapter_id = ?",
+            "DELETE FROM section_units WHERE unit_id = ?",
+            "DELETE FROM downloads WHERE unit_id = ?",
+            "DELETE FROM chapters WHERE id = ?",
+        ],
+    )
+    .await
+}
+
+/// Delete an episode with its streams, section links and download records; see `delete_chapter`.
+pub async fn delete_episode(pool: &AnyPool, episode_id: &str) -> Result<u64> {
+    delete_unit_with_dependents(
+        pool,
+        episode_id,
+        &[
+            "DELETE FROM streams WHERE episode_id = ?",
+            "DELETE FROM section_units WHERE unit_id = ?",
+            "DELETE FROM downloads WHERE unit_id = ?",
+            "DELETE FROM episodes WHERE id = ?",
+        ],
+    )
+    .await
+}
+
+async fn delete_unit_with_dependents(pool: &AnyPool, id: &str, stmts: &[&str]) -> Result<u64> {
+    let mut tx = pool.begin().await?;
+    let mut total = 0;
+    for sql in stmts {
+        total += sqlx::query(sql)
+            .bind(id)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+    }
+    tx.commit().await?;
+    Ok(total)
+}
+
 // Lookups to drive downloads/selection
 pub async fn list_series(pool: &AnyPool, kind: Option<&str>) -> Result<Vec<(String, String)>> {
     let rows = if let Some(k) = kind {
@@ -486,24 +1273,741 @@ pub async fn list_chapters_for_series(
     pool: &AnyPool,
     series_id: &str,
 ) -> Result<Vec<(String, Option<f64>, Option<String>)>> {
-    let rows = sqlx::query_as::<_, (String, Option<f64>, Option<String>)>(
-        "SELECT id, number_num, number_text FROM chapters WHERE series_id = ? ORDER BY number_num NULLS LAST, number_text",
+    // The Any driver can't decode NULL into Option<_>, so flag NULLs explicitly
+    let rows = sqlx::query_as::<_, (String, f64, i64, String)>(
+        "SELECT id, COALESCE(number_num, 0.0), number_num IS NOT NULL, COALESCE(number_text, '')\n         FROM chapters WHERE series_id = ? ORDER BY number_num NULLS LAST, number_text",
     )
     .bind(series_id)
     .fetch_all(pool)
     .await?;
-    Ok(rows)
+    Ok(rows
+        .into_iter()
+        .map(|(id, num, has_num, text)| (id, (has_num != 0).then_some(num), non_empty(text)))
+        .collect())
+}
+
+/// Fill `number_num` from `number_text` for chapters of a series that lack it, in one
+/// transaction. `parse` returns `None` for text without a usable number. Returns rows updated.
+pub async fn backfill_chapter_numbers(
+    pool: &AnyPool,
+    series_id: &str,
+    parse: impl Fn(&str) -> Option<f64>,
+) -> Result<u64> {
+    let mut tx = pool.begin().await?;
+    let rows = sqlx::query_as::<_, (String, String)>(
+        "SELECT id, number_text FROM chapters
+         WHERE series_id = ? AND number_num IS NULL AND number_text IS NOT NULL",
+    )
+    .bind(series_id)
+    .fetch_all(&mut *tx)
+    .await?;
+    let mut updated = 0;
+    for (id, text) in rows {
+        let Some(number) = parse(&text) else {
+            continue;
+        };
+        updated += sqlx::query(
+            "UPDATE chapters SET number_num = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?",
+        )
+        .bind(number)
+        .bind(&id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+    }
+    tx.commit().await?;
+    Ok(updated)
+}
+
+/// A chapter joined with its read/download state (see `list_chapters_with_state`).
+#[derive(Debug, Clone)]
+pub struct ChapterStateRow {
+    pub id: String,
+    pub series_id: String,
+    pub external_id: String,
+    pub number_text: Option<String>,
+    pub number_num: Option<f64>,
+    pub title: Option<String>,
+    pub lang: Option<String>,
+    pub volume: Option<String>,
+    pub image_count: i64,
+    pub has_cached_pages: bool,
+    // (page_index, total_pages, updated_at) when progress exists
+    pub progress: Option<(i64, Option<i64>, i64)>,
+    pub downloaded: bool,
+}
+
+// NULL-free projection of ChapterStateRow (the Any driver can't decode NULL into Option<T>)
+type RawChapterStateRow = (
+    String,
+    String,
+    String,
+    String,
+    f64,
+    i64,
+    String,
+    String,
+    String,
+    i64,
+    i64,
+    i64,
+    i64,
+    i64,
+    i64,
+    i64,
+);
+
+fn non_empty(s: String) -> Option<String> {
+    (!s.is_empty()).then_some(s)
+}
+
+// Columns decoded by `chapter_state_row`; expects `chapters c LEFT JOIN chapter_progress p`
+const CHAPTER_STATE_COLUMNS: &str = "c.id, c.series_id, c.external_id, COALESCE(c.number_text, ''),
+                COALESCE(c.number_num, 0.0), c.number_num IS NOT NULL,
+                COALESCE(c.title, ''), COALESCE(c.lang, ''), COALESCE(c.volume, ''),
+                (SELECT COUNT(*) FROM chapter_images ci WHERE ci.chapter_id = c.id),
+                EXISTS(SELECT 1 FROM search_cache sc WHERE sc.key = 'all|pages|' || c.id AND sc.expires_at > unixepoch()),
+                p.chapter_id IS NOT NULL, COALESCE(p.page_index, 0), COALESCE(p.total_pages, -1),
+                COALESCE(p.updated_at, 0),
+                EXISTS(SELECT 1 FROM downloads d WHERE d.unit_id = c.id)";
+
+fn chapter_state_row(raw: RawChapterStateRow) -> ChapterStateRow {
+    let (
+        id,
+        series_id,
+        external_id,
+        number_text,
+        number_num,
+        has_number,
+        title,
+        lang,
+        volume,
+        image_count,
+        has_cached_pages,
+        has_progress,
+        page_index,
+        total_pages,
+        progress_updated_at,
+        downloaded,
+    ) = raw;
+    ChapterStateRow {
+        id,
+        series_id,
+        external_id,
+        number_text: non_empty(number_text),
+        number_num: (has_number != 0).then_some(number_num),
+        title: non_empty(title),
+        lang: non_empty(lang),
+        volume: non_empty(volume),
+        image_count,
+        has_cached_pages: has_cached_pages != 0,
+        progress: (has_progress != 0).then_some((
+            page_index,
+            (total_pages >= 0).then_some(total_pages),
+            progress_updated_at,
+        )),
+        downloaded: downloaded != 0,
+    }
+}
+
+/// One page of a series' chapters with progress and download state, plus the total
+/// number of chapters matching `lang`. Sorted by chapter number (`descending` flips it).
+pub async fn list_chapters_with_state(
+    pool: &AnyPool,
+    series_id: &str,
+    lang: Option<&str>,
+    descending: bool,
+    limit: i64,
+    offset: i64,
+) -> Result<(Vec<ChapterStateRow>, i64)> {
+    let total: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM chapters WHERE series_id = ? AND (? IS NULL OR lang = ?)",
+    )
+    .bind(series_id)
+    .bind(lang)
+    .bind(lang)
+    .fetch_one(pool)
+    .await?;
+
+    let order = if descending {
+        "c.number_num DESC NULLS LAST, c.number_text DESC"
+    } else {
+        "c.number_num ASC NULLS LAST, c.number_text ASC"
+    };
+    let sql = format!(
+        "SELECT {CHAPTER_STATE_COLUMNS}
+         FROM chapters c
+         LEFT JOIN chapter_progress p ON p.chapter_id = c.id
+         WHERE c.series_id = ? AND (? IS NULL OR c.lang = ?)
+         ORDER BY {order}
+         LIMIT ? OFFSET ?"
+    );
+    let rows = sqlx::query_as::<_, RawChapterStateRow>(&sql)
+        .bind(series_id)
+        .bind(lang)
+        .bind(lang)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await?;
+    Ok((rows.into_iter().map(chapter_state_row).collect(), total))
+}
+
+/// One chapter (canonical id) with progress and download state.
+pub async fn get_chapter_with_state(
+    pool: &AnyPool,
+    chapter_id: &str,
+) -> Result<Option<ChapterStateRow>> {
+    let sql = format!(
+        "SELECT {CHAPTER_STATE_COLUMNS}
+         FROM chapters c
+         LEFT JOIN chapter_progress p ON p.chapter_id = c.id
+         WHERE c.id = ?"
+    );
+    let row = sqlx::query_as::<_, RawChapterStateRow>(&sql)
+        .bind(chapter_id)
+        .fetch_optional(pool)
+        .await?;
+    Ok(row.map(chapter_state_row))
+}
+
+/// Chapters of a series published at or after `since` (unix seconds, from the parsed
+/// `published_epoch`), newest first. Chapters with an unparseable date are left out.
+pub async fn list_chapters_published_since(
+    pool: &AnyPool,
+    series_id: &str,
+    since: i64,
+) -> Result<Vec<ChapterStateRow>> {
+    let sql = format!(
+        "SELECT {CHAPTER_STATE_COLUMNS}
+         FROM chapters c
+         LEFT JOIN chapter_progress p ON p.chapter_id = c.id
+         WHERE c.series_id = ? AND c.published_epoch >= ?
+         ORDER BY c.published_epoch DESC, c.number_num DESC NULLS LAST"
+    );
+    let rows = sqlx::query_as::<_, RawChapterStateRow>(&sql)
+        .bind(series_id)
+        .bind(since)
+        .fetch_all(pool)
+        .await?;
+    Ok(rows.into_iter().map(chapter_state_row).collect())
+}
+
+/// Chapters whose title or number label contains `query` (`LIKE`, ASCII case-insensitive),
+/// optionally within one series. Ordered by series, then chapter number.
+pub async fn search_chapters(
+    pool: &AnyPool,
+    query: &str,
+    series_id: Option<&str>,
+    limit: i64,
+) -> Result<Vec<ChapterStateRow>> {
+    let like = format!("%{}%", query);
+    let sql = format!(
+        "SELECT {CHAPTER_STATE_COLUMNS}
+         FROM chapters c
+         LEFT JOIN chapter_progress p ON p.chapter_id = c.id
+         WHERE (c.title LIKE ? OR c.number_text LIKE ?)
+           AND (? IS NULL OR c.series_id = ?)
+         ORDER BY c.series_id, c.number_num NULLS LAST, c.number_text
+         LIMIT ?"
+    );
+    let rows = sqlx::query_as::<_, RawChapterStateRow>(&sql)
+        .bind(&like)
+        .bind(&like)
+        .bind(series_id)
+        .bind(series_id)
+        .bind(limit)
+        .fetch_all(pool)
+        .await?;
+    Ok(rows.into_iter().map(chapter_state_row).collect())
 }
 
 pub async fn list_episodes_for_series(
     pool: &AnyPool,
     series_id: &str,
 ) -> Result<Vec<(String, Option<f64>, Option<String>)>> {
-    let rows = sqlx::query_as::<_, (String, Option<f64>, Option<String>)>(
-        "SELECT id, number_num, number_text FROM episodes WHERE series_id = ? ORDER BY number_num NULLS LAST, number_text",
+    let rows = sqlx::query_as::<_, (String, f64, i64, String)>(
+        "SELECT id, COALESCE(number_num, 0.0), number_num IS NOT NULL, COALESCE(number_text, '')\n         FROM episodes WHERE series_id = ? ORDER BY number_num NULLS LAST, number_text",
+    )
+    .bind(series_id)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows
+        .into_iter()
+        .map(|(id, num, has_num, text)| (id, (has_num != 0).then_some(num), non_empty(text)))
+        .collect())
+}
+
+// Sections (arcs) and their member units
+pub async fn find_section_id_by_mapping(
+    pool: &AnyPool,
+    series_id: &str,
+    source_id: &str,
+    external_id: &str,
+) -> Result<Option<String>> {
+    let id = sqlx::query_scalar::<_, String>(
+        "SELECT id FROM sections WHERE series_id = ? AND source_id = ? AND external_id = ? LIMIT 1",
+    )
+    .bind(series_id)
+    .bind(source_id)
+    .bind(external_id)
+    .fetch_optional(pool)
+    .await?;
+    Ok(id)
+}
+
+pub async fn upsert_section(pool: &AnyPool, s: &SectionInsert) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO sections(\n            id, series_id, source_id, external_id, number_text, number_num, title, lang, position\n         ) VALUES(?, ?, ?, ?, ?, ?, ?, ?, ?)\n         ON CONFLICT(series_id, source_id, external_id) DO UPDATE SET\n           number_text=excluded.number_text, number_num=excluded.number_num, title=excluded.title,\n           lang=excluded.lang, position=excluded.position, updated_at=CURRENT_TIMESTAMP",
+    )
+    .bind(&s.id)
+    .bind(&s.series_id)
+    .bind(&s.source_id)
+    .bind(&s.external_id)
+    .bind(&s.number_text)
+    .bind(s.number_num)
+    .bind(&s.title)
+    .bind(&s.lang)
+    .bind(s.position)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Replace a section's members with `unit_ids` (canonical chapter/episode ids, in order).
+pub async fn replace_section_units(
+    pool: &AnyPool,
+    section_id: &str,
+    unit_ids: &[String],
+) -> Result<()> {
+    let mut tx = pool.begin().await?;
+    sqlx::query("DELETE FROM section_units WHERE section_id = ?")
+        .bind(section_id)
+        .execute(&mut *tx)
+        .await?;
+    for (i, unit_id) in unit_ids.iter().enumerate() {
+        sqlx::query(
+            "INSERT INTO section_units(section_id, unit_id, position) VALUES(?, ?, ?)\n             ON CONFLICT(section_id, unit_id) DO NOTHING",
+        )
+        .bind(section_id)
+        .bind(unit_id)
+        .bind(i as i64)
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Sections of a series in source order, each with its member unit ids.
+pub async fn list_sections_for_series(
+    pool: &AnyPool,
+    series_id: &str,
+) -> Result<Vec<(SectionInsert, Vec<String>)>> {
+    let rows = sqlx::query_as::<_, (String, String, String, String, f64, i64, String, String, i64)>(
+        "SELECT id, source_id, external_id, COALESCE(number_text, ''), COALESCE(number_num, 0.0),\n                number_num IS NOT NULL, COALESCE(title, ''), COALESCE(lang, ''), position\n         FROM sections WHERE series_id = ? ORDER BY position, id",
     )
     .bind(series_id)
     .fetch_all(pool)
     .await?;
+    let mut out = Vec::with_capacity(rows.len());
+    for (id, source_id, external_id, number_text, number_num, has_num, title, lang, position) in
+        rows
+    {
+        let units = sqlx::query_scalar::<_, String>(
+            "SELECT unit_id FROM section_units WHERE section_id = ? ORDER BY position",
+        )
+        .bind(&id)
+        .fetch_all(pool)
+        .await?;
+        out.push((
+            SectionInsert {
+                id,
+                series_id: series_id.to_string(),
+                source_id,
+                external_id,
+                number_text: non_empty(number_text),
+                number_num: (has_num != 0).then_some(number_num),
+                title: non_empty(title),
+                lang: non_empty(lang),
+                position,
+            },
+            units,
+        ));
+    }
+    Ok(out)
+}
+
+// Runtime allowed-host overrides (plugin, host, allowed)
+pub async fn set_plugin_host_override(
+    pool: &AnyPool,
+    plugin: &str,
+    host: &str,
+    allowed: bool,
+) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO plugin_host_overrides(plugin, host, allowed) VALUES(?, ?, ?)\n         ON CONFLICT(plugin, host) DO UPDATE SET allowed=excluded.allowed, updated_at=CURRENT_TIMESTAMP",
+    )
+    .bind(plugin)
+    .bind(host)
+    .bind(allowed as i64)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+pub async fn list_plugin_host_overrides(pool: &AnyPool) -> Result<Vec<(String, String, bool)>> {
+    let rows = sqlx::query_as::<_, (String, String, i64)>(
+        "SELECT plugin, host, allowed FROM plugin_host_overrides ORDER BY updated_at",
+    )
+    .fetch_all(pool)
+    .await?;
+    Ok(rows
+        .into_iter()
+        .map(|(plugin, host, allowed)| (plugin, host, allowed != 0))
+        .collect())
+}
+
+// Settings
+pub async fn get_setting(pool: &AnyPool, key: &str) -> Result<Option<String>> {
+    let row = sqlx::query_scalar::<_, String>("SELECT value FROM settings WHERE key = ?")
+        .bind(key)
+        .fetch_optional(pool)
+        .await?;
+    Ok(row)
+}
+
+pub async fn set_setting(pool: &AnyPool, key: &str, value: &str) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO settings(key, value) VALUES(?, ?)\n         ON CONFLICT(key) DO UPDATE SET value=excluded.value, updated_at=CURRENT_TIMESTAMP",
+    )
+    .bind(key)
+    .bind(value)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+pub async fn delete_setting(pool: &AnyPool, key: &str) -> Result<()> {
+    sqlx::query("DELETE FROM settings WHERE key = ?")
+        .bind(key)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+// Custom per-series fields (series_id, key, value)
+pub async fn set_series_custom_field(
+    pool: &AnyPool,
+    series_id: &str,
+    key: &str,
+    value: &str,
+) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO series_custom(series_id, key, value) VALUES(?, ?, ?)\n         ON CONFLICT(series_id, key) DO UPDATE SET value=excluded.value, updated_at=CURRENT_TIMESTAMP",
+    )
+    .bind(series_id)
+    .bind(key)
+    .bind(value)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+pub async fn delete_series_custom_field(pool: &AnyPool, series_id: &str, key: &str) -> Result<()> {
+    sqlx::query("DELETE FROM series_custom WHERE series_id = ? AND key = ?")
+        .bind(series_id)
+        .bind(key)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+pub async fn get_series_custom_fields(
+    pool: &AnyPool,
+    series_id: &str,
+) -> Result<Vec<(String, String)>> {
+    let rows = sqlx::query_as::<_, (String, String)>(
+        "SELECT key, value FROM series_custom WHERE series_id = ? ORDER BY key",
+    )
+    .bind(series_id)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
+
+/// (id, title) of series whose `key` field equals `value` exactly, by title.
+pub async fn list_series_by_custom_field(
+    pool: &AnyPool,
+    key: &str,
+    value: &str,
+) -> Result<Vec<(String, String)>> {
+    let rows = sqlx::query_as::<_, (String, String)>(
+        "SELECT s.id, s.title FROM series_custom c JOIN series s ON s.id = c.series_id
+         WHERE c.key = ? AND c.value = ? ORDER BY s.title",
+    )
+    .bind(key)
+    .bind(value)
+    .fetch_all(pool)
+    .await?;
     Ok(rows)
 }
+
+// Per-series source order for page fetches
+/// Replace a series' source order; an empty list clears it. Duplicates keep their first position.
+pub async fn set_series_source_priority(
+    pool: &AnyPool,
+    series_id: &str,
+    source_ids: &[String],
+) -> Result<()> {
+    let mut tx = pool.begin().await?;
+    sqlx::query("DELETE FROM series_source_priority WHERE series_id = ?")
+        .bind(series_id)
+        .execute(&mut *tx)
+        .await?;
+    for (position, source_id) in source_ids.iter().enumerate() {
+        sqlx::query(
+            "INSERT INTO series_source_priority(series_id, source_id, position) VALUES(?, ?, ?)
+             ON CONFLICT(series_id, source_id) DO NOTHING",
+        )
+        .bind(series_id)
+        .bind(source_id)
+        .bind(position as i64)
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+    Ok(())
+}
+
+pub async fn get_series_source_priority(pool: &AnyPool, series_id: &str) -> Result<Vec<String>> {
+    let rows = sqlx::query_scalar::<_, String>(
+        "SELECT source_id FROM series_source_priority WHERE series_id = ? ORDER BY position",
+    )
+    .bind(series_id)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
+
+// Download records
+pub async fn upsert_download(pool: &AnyPool, d: &DownloadInsert) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO downloads(unit_id, kind, path, size_bytes, sha256, page_count) VALUES(?, ?, ?, ?, ?, ?)\n         ON CONFLICT(unit_id, path) DO UPDATE SET\n           kind=excluded.kind, size_bytes=excluded.size_bytes, sha256=excluded.sha256,\n           page_count=COALESCE(excluded.page_count, downloads.page_count), created_at=CURRENT_TIMESTAMP",
+    )
+    .bind(&d.unit_id)
+    .bind(&d.kind)
+    .bind(&d.path)
+    .bind(d.size_bytes)
+    .bind(&d.sha256)
+    .bind(d.page_count)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Point download records at a moved file or folder: `old` itself and anything under it.
+pub async fn rebase_download_paths(pool: &AnyPool, old: &str, new: &str) -> Result<u64> {
+    let sep = std::path::MAIN_SEPARATOR.to_string();
+    let res = sqlx::query(
+        "UPDATE downloads SET path = ? || substr(path, ?)\n         WHERE path = ? OR substr(path, 1, ?) = ?",
+    )
+    .bind(new)
+    .bind(old.chars().count() as i64 + 1)
+    .bind(old)
+    .bind(old.chars().count() as i64 + 1)
+    .bind(format!("{}{}", old, sep))
+    .execute(pool)
+    .await?;
+    Ok(res.rows_affected())
+}
+
+pub async fn list_downloads_for_unit(pool: &AnyPool, unit_id: &str) -> Result<Vec<DownloadInsert>> {
+    let rows = sqlx::query_as::<_, (String, String, String, i64, String, i64)>(
+        "SELECT unit_id, kind, path, size_bytes, sha256, COALESCE(page_count, -1) FROM downloads WHERE unit_id = ? ORDER BY path",
+    )
+    .bind(unit_id)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows
+        .into_iter()
+        .map(
+            |(unit_id, kind, path, size_bytes, sha256, page_count)| DownloadInsert {
+                unit_id,
+                kind,
+                path,
+                size_bytes,
+                sha256,
+                page_count: (page_count >= 0).then_some(page_count),
+            },
+        )
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::Database;
+
+    async fn database(dir: &tempfile::TempDir) -> Database {
+        let url = format!("sqlite://{}?mode=rwc", dir.path().join("t.db").display());
+        let db = Database::connect(Some(&url)).await.unwrap();
+        db.run_migrations().await.unwrap();
+        db
+    }
+
+    async fn series(pool: &AnyPool, id: &str) {
+        upsert_series(
+            pool,
+            &SeriesInsert {
+                id: id.to_string(),
+                kind: "manga".to_string(),
+                title: id.to_string(),
+                alt_titles: None,
+                description: None,
+                cover_url: None,
+                tags: None,
+                status: None,
+            },
+        )
+        .await
+        .unwrap();
+    }
+
+    async fn chapter(
+        pool: &AnyPool,
+        id: &str,
+        series_id: &str,
+        source_id: &str,
+        external_id: &str,
+        lang: Option<&str>,
+    ) {
+        upsert_source(
+            pool,
+            &SourceInsert {
+                id: source_id.to_string(),
+                version: "1".to_string(),
+            },
+        )
+        .await
+        .unwrap();
+        upsert_chapter(
+            pool,
+            &ChapterInsert {
+                id: id.to_string(),
+                series_id: series_id.to_string(),
+                source_id: source_id.to_string(),
+                external_id: external_id.to_string(),
+                number_text: None,
+                number_num: None,
+                title: None,
+                lang: lang.map(str::to_string),
+                volume: None,
+                published_at: None,
+            },
+        )
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn series_languages_are_grouped_by_source() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = database(&dir).await;
+        let pool = db.pool();
+        series(pool, "s1").await;
+        series(pool, "s2").await;
+        chapter(pool, "a1", "s1", "a", "x1", Some("fr")).await;
+        chapter(pool, "a2", "s1", "a", "x2", Some("en")).await;
+        chapter(pool, "a3", "s1", "a", "x3", Some("en")).await;
+        chapter(pool, "b1", "s1", "b", "y1", Some(" es ")).await;
+        chapter(pool, "b2", "s1", "b", "y2", Some("en")).await;
+        chapter(pool, "c1", "s1", "c", "z1", None).await;
+        chapter(pool, "d1", "s2", "a", "w1", Some("de")).await;
+
+        let owned = |langs: &[&str]| langs.iter().map(|l| l.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            series_languages_by_source(pool, "s1").await.unwrap(),
+            vec![
+                ("a".to_string(), owned(&["en", "fr"])),
+                ("b".to_string(), owned(&["en", "es"])),
+                ("c".to_string(), Vec::new()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn describe_id_tells_canonical_from_external_ids() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = database(&dir).await;
+        let pool = db.pool();
+        series(pool, "s1").await;
+        chapter(pool, "ch-b", "s1", "b", "ext", None).await;
+        chapter(pool, "ch-a", "s1", "a", "ext", None).await;
+        upsert_series_source(
+            pool,
+            &SeriesSourceInsert {
+                series_id: "s1".to_string(),
+                source_id: "a".to_string(),
+                external_id: "media-1".to_string(),
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            describe_id(pool, "s1").await.unwrap(),
+            IdKind::CanonicalSeries
+        );
+        assert_eq!(
+            describe_id(pool, "ch-b").await.unwrap(),
+            IdKind::CanonicalChapter
+        );
+        assert_eq!(
+            describe_id(pool, "media-1").await.unwrap(),
+            IdKind::ExternalSeries {
+                source_id: "a".to_string(),
+                series_id: "s1".to_string(),
+            }
+        );
+        // Shared by two sources: the lowest source id wins
+        assert_eq!(
+            describe_id(pool, "ext").await.unwrap(),
+            IdKind::ExternalChapter {
+                source_id: "a".to_string(),
+                chapter_id: "ch-a".to_string(),
+            }
+        );
+        assert_eq!(describe_id(pool, "nope").await.unwrap(), IdKind::Unknown);
+    }
+
+    #[tokio::test]
+    async fn chapter_lookup_by_external_id_is_scoped_to_source() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = database(&dir).await;
+        let pool = db.pool();
+        series(pool, "s1").await;
+        series(pool, "s2").await;
+        chapter(pool, "a-c1", "s1", "a", "c1", None).await;
+        chapter(pool, "b-c1", "s2", "b", "c1", None).await;
+
+        assert_eq!(
+            find_chapter_id_by_source_external(pool, "a", "c1")
+                .await
+                .unwrap()
+                .as_deref(),
+            Some("a-c1")
+        );
+        assert_eq!(
+            find_chapter_id_by_source_external(pool, "b", "c1")
+                .await
+                .unwrap()
+                .as_deref(),
+            Some("b-c1")
+        );
+        assert!(find_chapter_id_by_source_external(pool, "other", "c1")
+            .await
+            .unwrap()
+            .is_none());
+    }
+}
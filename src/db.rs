@@ -2,9 +2,13 @@ use anyhow::{Context, Result};
 use directories::ProjectDirs;
 use sqlx::any::AnyPoolOptions;
 use sqlx::{any::AnyConnectOptions, migrate::Migrator, AnyPool, ConnectOptions};
-use std::sync::Once;
-use std::{path::PathBuf, str::FromStr};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Once};
+use std::time::Duration;
 
+use crate::error::TouringError;
 use crate::storage::Storage;
 
 // Ensure drivers are installed exactly once for sqlx::any
@@ -13,47 +17,171 @@ static INSTALL_DRIVERS: Once = Once::new();
 // Embed SQL migrations from the migrations/ directory
 static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
 
+// Default ceiling for a single cache row
+const DEFAULT_MAX_CACHE_PAYLOAD_BYTES: usize = 4 * 1024 * 1024;
+
+// How long to wait for a free pooled connection before reporting the database as busy
+const DEFAULT_ACQUIRE_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Fallback when the default data directory can't hold a database file
+const EPHEMERAL_SQLITE_URL: &str = "sqlite::memory:";
+
 #[derive(Clone)]
 pub struct Database {
     pool: AnyPool,
+    // URL the pool was opened with (after defaulting), for `reconnect`
+    url: String,
+    // Payloads larger than this are not cached (0 = unlimited); shared by clones
+    max_cache_payload_bytes: Arc<AtomicUsize>,
+    acquire_timeout: Duration,
+    // In-memory fallback; nothing survives the process
+    ephemeral: bool,
+    // Opened with `connect_read_only`; cache reads skip the LRU stamp and cache writes are dropped
+    read_only: bool,
 }
 
 impl Database {
     // Create a connection pool. If database_url is None, use a sensible default
-    // (SQLite file in the user's data directory).
+    // (SQLite file in the user's data directory), falling back to an in-memory database
+    // when that file can't be created or opened.
     pub async fn connect(database_url: Option<&str>) -> Result<Self> {
         // Register compiled-in drivers for sqlx::any
-        INSTALL_DRIVERS.call_once(|| sqlx::any::install_default_drivers());
+        INSTALL_DRIVERS.call_once(sqlx::any::install_default_drivers);
 
-        let url = match database_url {
-            Some(u) if !u.trim().is_empty() => u.to_string(),
-            _ => default_sqlite_url()?,
+        let acquire_timeout = std::env::var("TOURING_DB_ACQUIRE_TIMEOUT_MS")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .map(Duration::from_millis)
+            .unwrap_or(DEFAULT_ACQUIRE_TIMEOUT);
+
+        let (pool, url, ephemeral) = match database_url {
+            Some(u) if !u.trim().is_empty() => {
+                (open_pool(u, acquire_timeout).await?, u.to_string(), false)
+            }
+            _ => {
+                let default = match default_sqlite_url() {
+                    Ok(url) => open_pool(&url, acquire_timeout)
+                        .await
+                        .map(|pool| (pool, url)),
+                    Err(e) => Err(e),
+                };
+                match default {
+                    Ok((pool, url)) => (pool, url, false),
+                    Err(e) => {
+                        tracing::warn!(
+                            error = %format!("{e:#}"),
+                            "default database unavailable; using an in-memory database (nothing will be saved)"
+                        );
+                        (
+                            open_pool(EPHEMERAL_SQLITE_URL, acquire_timeout).await?,
+                            EPHEMERAL_SQLITE_URL.to_string(),
+                            true,
+                        )
+                    }
+                }
+            }
         };
 
-        // Parse options to tweak connection settings (e.g., logging)
-        let opts = AnyConnectOptions::from_str(&url)
-            .with_context(|| format!("invalid database URL: {url}"))?;
-        // Quiet by default; callers can enable SQLX_LOG if they want
-        let opts = opts.disable_statement_logging();
+        let max_cache_payload_bytes = std::env::var("TOURING_MAX_CACHE_PAYLOAD_BYTES")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(DEFAULT_MAX_CACHE_PAYLOAD_BYTES);
 
-        // Use fewer connections on mobile platforms for better performance
-        let max_connections = if cfg!(target_os = "ios") || cfg!(target_os = "android") {
-            3 // Mobile: fewer connections, less overhead
-        } else {
-            10 // Desktop: more connections for concurrency
-        };
+        Ok(Self {
+            pool,
+            url,
+            max_cache_payload_bytes: Arc::new(AtomicUsize::new(max_cache_payload_bytes)),
+            acquire_timeout,
+            ephemeral,
+            read_only: false,
+        })
+    }
 
-        let pool = AnyPoolOptions::new()
-            .max_connections(max_connections)
-            .connect_with(opts)
-            .await
-            .with_context(|| format!("failed to connect to database: {url}"))?;
+    /// Open an existing SQLite database with `mode=ro`, so SQLite itself rejects every write.
+    /// There is no default path or in-memory fallback: the file must already exist.
+    pub async fn connect_read_only(database_url: &str) -> Result<Self> {
+        let mut db = Self::connect(Some(&read_only_url(database_url)?)).await?;
+        db.read_only = true;
+        Ok(db)
+    }
+
+    /// Replace the pool with a fresh one opened from the original URL, e.g. after a network
+    /// database dropped its connections. Clones made earlier keep the old pool. The in-memory
+    /// fallback can't be reopened (its contents live in the old pool's connection).
+    pub async fn reconnect(&mut self) -> Result<()> {
+        if self.ephemeral {
+            anyhow::bail!("cannot reconnect the in-memory database without losing its contents");
+        }
+        self.pool = open_pool(&self.url, self.acquire_timeout).await?;
+        Ok(())
+    }
 
-        Ok(Self { pool })
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// `Err(TouringError::ReadOnly)` on a read-only database.
+    pub fn ensure_writable(&self, operation: &str) -> Result<()> {
+        if self.read_only {
+            return Err(TouringError::ReadOnly {
+                operation: operation.to_string(),
+            }
+            .into());
+        }
+        Ok(())
+    }
+
+    /// True when running on the in-memory fallback because the default data directory
+    /// was not writable; the library works but nothing persists past this process.
+    pub fn is_ephemeral(&self) -> bool {
+        self.ephemeral
+    }
+
+    /// How long a query waits for a pooled connection (`TOURING_DB_ACQUIRE_TIMEOUT_MS`, default 10s).
+    pub fn acquire_timeout(&self) -> Duration {
+        self.acquire_timeout
+    }
+
+    /// Turn a pool acquire timeout inside `err` into `TouringError::DatabaseBusy` and a
+    /// rejected write into `TouringError::ReadOnly`; other errors pass through unchanged.
+    pub fn map_busy(&self, err: anyhow::Error) -> anyhow::Error {
+        match TouringError::classify(&err) {
+            Some(TouringError::DatabaseBusy { .. }) => {
+                anyhow::Error::new(TouringError::DatabaseBusy {
+                    timeout: self.acquire_timeout,
+                })
+            }
+            Some(e @ TouringError::ReadOnly { .. }) => anyhow::Error::new(e),
+            _ => err,
+        }
+    }
+
+    pub fn max_cache_payload_bytes(&self) -> usize {
+        self.max_cache_payload_bytes.load(Ordering::Relaxed)
+    }
+
+    /// Largest payload `put_cache` will store; bigger ones are skipped with a warning. 0 = unlimited.
+    pub fn set_max_cache_payload_bytes(&self, limit: usize) {
+        self.max_cache_payload_bytes.store(limit, Ordering::Relaxed);
     }
 
     pub async fn run_migrations(&self) -> Result<()> {
-        match MIGRATOR.run(&self.pool).await {
+        self.ensure_writable("running migrations")?;
+        // SQLite table rebuilds (drop + rename) must not trigger ON DELETE CASCADE, and the
+        // pragma is ignored inside the migration's transaction, so set it on the connection.
+        let mut conn = self.pool.acquire().await?;
+        let _ = sqlx::query("PRAGMA foreign_keys = OFF")
+            .execute(&mut *conn)
+            .await;
+        let result = self.run_migrations_on(&mut conn).await;
+        let _ = sqlx::query("PRAGMA foreign_keys = ON")
+            .execute(&mut *conn)
+            .await;
+        result
+    }
+
+    async fn run_migrations_on(&self, conn: &mut sqlx::AnyConnection) -> Result<()> {
+        match MIGRATOR.run(&mut *conn).await {
             Ok(_) => Ok(()),
             Err(e) => {
                 let msg = e.to_string();
@@ -62,10 +190,10 @@ impl Database {
                     msg.contains("UNIQUE constraint failed: _sqlx_migrations.version");
                 if looks_modified || duplicate_version {
                     let _ = sqlx::query("DELETE FROM _sqlx_migrations")
-                        .execute(&self.pool)
+                        .execute(&mut *conn)
                         .await;
                     MIGRATOR
-                        .run(&self.pool)
+                        .run(&mut *conn)
                         .await
                         .context("running migrations after ledger reset")
                 } else {
@@ -80,6 +208,7 @@ impl Database {
     }
 
     pub async fn clear_cache_prefix(&self, prefix: Option<&str>) -> Result<u64> {
+        self.ensure_writable("clearing the cache")?;
         let result = if let Some(p) = prefix {
             let like = format!("{}%", p);
             sqlx::query("DELETE FROM search_cache WHERE key LIKE ?")
@@ -94,7 +223,96 @@ impl Database {
         Ok(result.rows_affected())
     }
 
+    /// Delete the given cache keys in one transaction. Returns rows deleted.
+    pub async fn delete_cache_keys(&self, keys: &[String]) -> Result<u64> {
+        self.ensure_writable("deleting cache keys")?;
+        let mut tx = self.pool.begin().await?;
+        let mut deleted = 0;
+        for key in keys {
+            deleted += sqlx::query("DELETE FROM search_cache WHERE key = ?")
+                .bind(key)
+                .execute(&mut *tx)
+                .await?
+                .rows_affected();
+        }
+        tx.commit().await?;
+        Ok(deleted)
+    }
+
+    /// Cache keys starting with `prefix` (all keys if `None`) with their `expires_at`, in key
+    /// order. The match is exact and case-sensitive (unlike `LIKE`) so normalization
+    /// differences show up.
+    pub async fn list_cache_keys(
+        &self,
+        prefix: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<(String, i64)>> {
+        let prefix = prefix.unwrap_or("");
+        let rows = sqlx::query_as::<_, (String, i64)>(
+            "SELECT key, CAST(expires_at AS INTEGER) FROM search_cache
+             WHERE substr(key, 1, length(?)) = ? ORDER BY key LIMIT ?",
+        )
+        .bind(prefix)
+        .bind(prefix)
+        .bind(limit.min(i64::MAX as usize) as i64)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Evict cache rows until key+payload bytes total at most `max_bytes`: expired rows go
+    /// first, then the least recently read. Returns the number of rows deleted.
+    pub async fn prune_cache_to_size(&self, max_bytes: u64, now: i64) -> Result<u64> {
+        self.ensure_writable("pruning the cache")?;
+        let rows = sqlx::query_as::<_, (String, i64)>(
+            "SELECT key, LENGTH(key) + LENGTH(payload) FROM search_cache
+             ORDER BY expires_at <= ? DESC, last_accessed_at ASC, expires_at ASC",
+        )
+        .bind(now)
+        .fetch_all(&self.pool)
+        .await?;
+        let mut total: u64 = rows.iter().map(|(_, size)| *size as u64).sum();
+        let mut tx = self.pool.begin().await?;
+        let mut deleted = 0;
+        for (key, size) in rows {
+            if total <= max_bytes {
+                break;
+            }
+            deleted += sqlx::query("DELETE FROM search_cache WHERE key = ?")
+                .bind(&key)
+                .execute(&mut *tx)
+                .await?
+                .rows_affected();
+            total = total.saturating_sub(size as u64);
+        }
+        tx.commit().await?;
+        Ok(deleted)
+    }
+
+    /// Write a consistent snapshot of the database to `path` with `VACUUM INTO`, which is safe
+    /// while other connections keep working. SQLite only; `path` must not exist yet.
+    pub async fn backup_to(&self, path: &Path) -> Result<()> {
+        let mut conn = self.pool.acquire().await?;
+        let backend = conn.backend_name().to_string();
+        if !backend.eq_ignore_ascii_case("sqlite") {
+            anyhow::bail!("backup is only supported on SQLite (database is {backend})");
+        }
+        if tokio::fs::try_exists(path).await.unwrap_or(false) {
+            anyhow::bail!("backup target already exists: {}", path.display());
+        }
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        sqlx::query("VACUUM INTO ?")
+            .bind(path.to_string_lossy().into_owned())
+            .execute(&mut *conn)
+            .await
+            .with_context(|| format!("backing up database to {}", path.display()))?;
+        Ok(())
+    }
+
     pub async fn vacuum(&self) -> Result<()> {
+        self.ensure_writable("vacuum")?;
         // Best-effort: works on SQLite
         let _ = sqlx::query("VACUUM").execute(&self.pool).await;
         Ok(())
@@ -111,12 +329,40 @@ impl Storage for Database {
         .bind(now)
         .fetch_optional(&self.pool)
         .await?;
+        if row.is_some() && !self.read_only {
+            // Refresh the LRU stamp used by `prune_cache_to_size`, at most once a minute per
+            // key so hot reads don't each take the write lock. Best-effort: a busy database
+            // only costs some LRU precision.
+            if let Err(e) = sqlx::query(
+                "UPDATE search_cache SET last_accessed_at = unixepoch()
+                 WHERE key = ? AND last_accessed_at < unixepoch() - 60",
+            )
+            .bind(key)
+            .execute(&self.pool)
+            .await
+            {
+                tracing::debug!(key, error = %e, "cache access stamp not updated");
+            }
+        }
         Ok(row)
     }
 
     async fn put_cache(&self, key: &str, payload: &str, expires_at: i64) -> Result<()> {
+        if self.read_only {
+            return Ok(());
+        }
+        let limit = self.max_cache_payload_bytes();
+        if limit > 0 && payload.len() > limit {
+            tracing::warn!(
+                key,
+                bytes = payload.len(),
+                limit,
+                "cache payload too large; not caching"
+            );
+            return Ok(());
+        }
         sqlx::query(
-            "INSERT INTO search_cache(key, payload, expires_at) VALUES (?, ?, ?)\n             ON CONFLICT(key) DO UPDATE SET payload=excluded.payload, expires_at=excluded.expires_at",
+            "INSERT INTO search_cache(key, payload, expires_at, last_accessed_at) VALUES (?, ?, ?, unixepoch())\n             ON CONFLICT(key) DO UPDATE SET payload=excluded.payload, expires_at=excluded.expires_at, last_accessed_at=excluded.last_accessed_at",
         )
         .bind(key)
         .bind(payload)
@@ -127,6 +373,48 @@ impl Storage for Database {
     }
 }
 
+async fn open_pool(url: &str, acquire_timeout: Duration) -> Result<AnyPool> {
+    // Parse options to tweak connection settings (e.g., logging)
+    let opts =
+        AnyConnectOptions::from_str(url).with_context(|| format!("invalid database URL: {url}"))?;
+    // Quiet by default; callers can enable SQLX_LOG if they want
+    let opts = opts.disable_statement_logging();
+
+    let options = if url == EPHEMERAL_SQLITE_URL {
+        // Every in-memory connection is its own database, so keep exactly one alive forever
+        AnyPoolOptions::new()
+            .max_connections(1)
+            .min_connections(1)
+            .idle_timeout(None)
+            .max_lifetime(None)
+    } else {
+        // Use fewer connections on mobile platforms for better performance
+        let max_connections = if cfg!(target_os = "ios") || cfg!(target_os = "android") {
+            3 // Mobile: fewer connections, less overhead
+        } else {
+            10 // Desktop: more connections for concurrency
+        };
+        AnyPoolOptions::new().max_connections(max_connections)
+    };
+
+    options
+        .acquire_timeout(acquire_timeout)
+        .connect_with(opts)
+        .await
+        .with_context(|| format!("failed to connect to database: {url}"))
+}
+
+fn read_only_url(url: &str) -> Result<String> {
+    if !url.starts_with("sqlite:") {
+        anyhow::bail!("read-only mode needs a SQLite database URL, got: {url}");
+    }
+    if url.contains(":memory:") {
+        anyhow::bail!("read-only mode needs an existing database file, got: {url}");
+    }
+    let sep = if url.contains('?') { '&' } else { '?' };
+    Ok(format!("{url}{sep}mode=ro"))
+}
+
 fn default_sqlite_url() -> Result<String> {
     let proj = ProjectDirs::from("dev", "touring", "touring")
         .context("unable to determine data directory for default sqlite path")?;
@@ -141,11 +429,14 @@ fn default_sqlite_url() -> Result<String> {
             .with_context(|| format!("creating db parent dir: {}", parent.display()))?;
     }
 
-    // Ensure the file exists so SQLite can open it in rw mode
-    let _ = std::fs::OpenOptions::new()
+    // Ensure the file exists so SQLite can open it in rw mode; failing here means the
+    // directory is read-only and `connect` should fall back
+    std::fs::OpenOptions::new()
         .create(true)
+        .truncate(false)
         .write(true)
-        .open(&path);
+        .open(&path)
+        .with_context(|| format!("opening database file: {}", path.display()))?;
 
     // Encode spaces in the path for a valid sqlite URL
     let mut path_str = path.to_string_lossy().to_string();
@@ -0,0 +1,71 @@
+use std::fmt;
+use std::time::Duration;
+
+/// Typed failures callers may want to react to. They travel inside `anyhow::Error`; match them
+/// with `err.downcast_ref::<TouringError>()` (or [`TouringError::classify`]).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TouringError {
+    /// No database connection became free within the pool's acquire timeout, usually because
+    /// too many operations are running concurrently. Retrying later is reasonable.
+    DatabaseBusy { timeout: Duration },
+    /// Offline mode is on and `operation` needs live data (a plugin call or network fetch)
+    /// that isn't available from the cache or database.
+    Offline { operation: String },
+    /// The library was opened with `Touring::open_read_only` and `operation` would write.
+    ReadOnly { operation: String },
+    /// `operation` (a plugin export call) was aborted by `Touring::cancel_all_plugin_calls`.
+    Cancelled { operation: String },
+}
+
+impl fmt::Display for TouringError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TouringError::DatabaseBusy { timeout } => write!(
+                f,
+                "database busy: no connection available within {:?} (too many concurrent operations?)",
+                timeout
+            ),
+            TouringError::Offline { operation } => {
+                write!(f, "offline mode: {} requires network access", operation)
+            }
+            TouringError::ReadOnly { operation } => {
+                write!(f, "read-only database: {} would write", operation)
+            }
+            TouringError::Cancelled { operation } => write!(f, "{} cancelled", operation),
+        }
+    }
+}
+
+impl std::error::Error for TouringError {}
+
+impl TouringError {
+    /// Recognise a typed error anywhere in `err`'s chain, including a raw sqlx pool timeout
+    /// from a call that didn't map it (reported with a zero `timeout`, as it isn't known there)
+    /// and SQLite refusing a write on a read-only connection.
+    pub fn classify(err: &anyhow::Error) -> Option<TouringError> {
+        for cause in err.chain() {
+            if let Some(e) = cause.downcast_ref::<TouringError>() {
+                return Some(e.clone());
+            }
+            if let Some(sqlx::Error::PoolTimedOut) = cause.downcast_ref::<sqlx::Error>() {
+                return Some(TouringError::DatabaseBusy {
+                    timeout: Duration::ZERO,
+                });
+            }
+            if let Some(sqlx::Error::Database(db)) = cause.downcast_ref::<sqlx::Error>() {
+                // SQLITE_READONLY, possibly as an extended result code
+                if db
+                    .code()
+                    .and_then(|c| c.parse::<i32>().ok())
+                    .map(|c| c & 0xff)
+                    == Some(8)
+                {
+                    return Some(TouringError::ReadOnly {
+                        operation: "write".to_string(),
+                    });
+                }
+            }
+        }
+        None
+    }
+}
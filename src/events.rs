@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tokio::sync::broadcast;
+
+/// Events kept for subscribers that fall behind; slower receivers get `RecvError::Lagged`.
+const EVENT_CAPACITY: usize = 256;
+
+/// Library change announced to `Touring::subscribe` receivers.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum LibraryEvent {
+    /// A canonical series was created (search persistence, chapter fetch, title resolution).
+    SeriesAdded {
+        series_id: String,
+        title: String,
+    },
+    SeriesDeleted {
+        series_id: String,
+    },
+    /// Progress was written or cleared; `chapter_id` is the canonical chapter.
+    ChapterProgressUpdated {
+        series_id: String,
+        chapter_id: String,
+    },
+    /// `kind` is "chapter" or "episode"; `path` is the image directory or output file.
+    DownloadCompleted {
+        unit_id: String,
+        kind: String,
+        path: PathBuf,
+    },
+    MetadataRefreshed {
+        series_id: String,
+    },
+}
+
+/// Fan-out of `LibraryEvent`s. Publishing never blocks and is a no-op without subscribers.
+#[derive(Clone)]
+pub struct EventBus {
+    tx: broadcast::Sender<LibraryEvent>,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        let (tx, _) = broadcast::channel(EVENT_CAPACITY);
+        Self { tx }
+    }
+
+    pub fn publish(&self, event: LibraryEvent) {
+        // Err only means nobody is listening
+        let _ = self.tx.send(event);
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<LibraryEvent> {
+        self.tx.subscribe()
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,257 @@
+use aes::cipher::{block_padding::Pkcs7, BlockDecryptMut, KeyIvInit};
+use anyhow::{anyhow, Result};
+use url::Url;
+
+/// One entry of a master playlist.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HlsVariant {
+    pub uri: String,
+    pub bandwidth: Option<u64>,
+    pub resolution: Option<(u32, u32)>,
+}
+
+/// `#EXT-X-KEY` in effect for a segment. Only `AES-128` is supported for decryption.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HlsKey {
+    pub method: String,
+    pub uri: String,
+    pub iv: Option<[u8; 16]>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HlsSegment {
+    pub uri: String,
+    pub sequence: u64,
+    pub key: Option<HlsKey>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MediaPlaylist {
+    /// `#EXT-X-MAP` initialization section (fMP4 streams), written before the first segment.
+    pub init_uri: Option<String>,
+    pub segments: Vec<HlsSegment>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Playlist {
+    Master(Vec<HlsVariant>),
+    Media(MediaPlaylist),
+}
+
+/// HLS by MIME type or a `.m3u8` path.
+pub fn is_hls(url: &str, mime: Option<&str>) -> bool {
+    if let Some(m) = mime {
+        let m = m.to_ascii_lowercase();
+        if m.contains("mpegurl") {
+            return true;
+        }
+    }
+    let path = url.split(['?', '#']).next().unwrap_or(url);
+    path.to_ascii_lowercase().ends_with(".m3u8")
+}
+
+/// Parse a playlist; relative URIs are resolved against `base`.
+pub fn parse_playlist(text: &str, base: &Url) -> Result<Playlist> {
+    let mut lines = text.lines().map(str::trim).filter(|l| !l.is_empty());
+    if lines.next() != Some("#EXTM3U") {
+        return Err(anyhow!("not an HLS playlist (missing #EXTM3U)"));
+    }
+
+    let mut variants = Vec::new();
+    let mut pending_variant: Option<HlsVariant> = None;
+    let mut media = MediaPlaylist::default();
+    let mut sequence = 0u64;
+    let mut key: Option<HlsKey> = None;
+    let mut is_media = false;
+
+    for line in lines {
+        if let Some(attrs) = line.strip_prefix("#EXT-X-STREAM-INF:") {
+            let attrs = parse_attributes(attrs);
+            pending_variant = Some(HlsVariant {
+                uri: String::new(),
+                bandwidth: attr(&attrs, "BANDWIDTH").and_then(|b| b.parse().ok()),
+                resolution: attr(&attrs, "RESOLUTION").and_then(|r| {
+                    let (w, h) = r.split_once(['x', 'X'])?;
+                    Some((w.parse().ok()?, h.parse().ok()?))
+                }),
+            });
+        } else if let Some(n) = line.strip_prefix("#EXT-X-MEDIA-SEQUENCE:") {
+            sequence = n.trim().parse().unwrap_or(0);
+        } else if let Some(attrs) = line.strip_prefix("#EXT-X-KEY:") {
+            let attrs = parse_attributes(attrs);
+            let method = attr(&attrs, "METHOD").unwrap_or("NONE").to_string();
+            key = if method.eq_ignore_ascii_case("NONE") {
+                None
+            } else {
+                let uri = attr(&attrs, "URI").ok_or_else(|| anyhow!("#EXT-X-KEY without URI"))?;
+                Some(HlsKey {
+                    method,
+                    uri: base.join(uri)?.to_string(),
+                    iv: attr(&attrs, "IV").map(parse_iv).transpose()?,
+                })
+            };
+        } else if let Some(attrs) = line.strip_prefix("#EXT-X-MAP:") {
+            let attrs = parse_attributes(attrs);
+            if let Some(uri) = attr(&attrs, "URI") {
+                media.init_uri = Some(base.join(uri)?.to_string());
+            }
+        } else if line.starts_with("#EXTINF") {
+            is_media = true;
+        } else if line.starts_with('#') {
+            continue;
+        } else if let Some(mut variant) = pending_variant.take() {
+            variant.uri = base.join(line)?.to_string();
+            variants.push(variant);
+        } else {
+            media.segments.push(HlsSegment {
+                uri: base.join(line)?.to_string(),
+                sequence,
+                key: key.clone(),
+            });
+            sequence += 1;
+        }
+    }
+
+    if !variants.is_empty() && !is_media {
+        Ok(Playlist::Master(variants))
+    } else {
+        Ok(Playlist::Media(media))
+    }
+}
+
+/// Pick a variant by quality label ("720p" matches height 720) or, failing that, the one
+/// with the highest bandwidth.
+pub fn select_variant<'a>(
+    variants: &'a [HlsVariant],
+    quality: Option<&str>,
+) -> Option<&'a HlsVariant> {
+    let wanted = quality.and_then(|q| {
+        let q = q.trim();
+        q.strip_suffix(['p', 'P']).unwrap_or(q).parse::<u32>().ok()
+    });
+    if let Some(height) = wanted {
+        if let Some(v) = variants
+            .iter()
+            .find(|v| v.resolution.is_some_and(|(_, h)| h == height))
+        {
+            return Some(v);
+        }
+    }
+    variants.iter().max_by_key(|v| v.bandwidth.unwrap_or(0))
+}
+
+/// Decrypt an AES-128-CBC segment. Without an explicit IV the media sequence number is used,
+/// big-endian, as the spec requires.
+pub fn decrypt_segment(
+    data: &[u8],
+    key: &[u8],
+    iv: Option<[u8; 16]>,
+    sequence: u64,
+) -> Result<Vec<u8>> {
+    let key: [u8; 16] = key
+        .try_into()
+        .map_err(|_| anyhow!("AES-128 key must be 16 bytes, got {}", key.len()))?;
+    let iv = iv.unwrap_or_else(|| (sequence as u128).to_be_bytes());
+    let mut buf = data.to_vec();
+    let len = cbc::Decryptor::<aes::Aes128>::new(&key.into(), &iv.into())
+        .decrypt_padded_mut::<Pkcs7>(&mut buf)
+        .map_err(|_| anyhow!("segment decryption failed (bad key or padding)"))?
+        .len();
+    buf.truncate(len);
+    Ok(buf)
+}
+
+// KEY=value pairs separated by commas; values may be quoted and contain commas.
+fn parse_attributes(s: &str) -> Vec<(String, String)> {
+    let mut out = Vec::new();
+    let mut rest = s.trim();
+    while !rest.is_empty() {
+        let Some((name, after)) = rest.split_once('=') else {
+            break;
+        };
+        let (value, tail) = if let Some(quoted) = after.strip_prefix('"') {
+            match quoted.split_once('"') {
+                Some((v, t)) => (v, t),
+                None => (quoted, ""),
+            }
+        } else {
+            after.split_once(',').map_or((after, ""), |(v, t)| (v, t))
+        };
+        out.push((name.trim().to_ascii_uppercase(), value.to_string()));
+        rest = tail.trim_start_matches(',').trim_start();
+    }
+    out
+}
+
+fn attr<'a>(attrs: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    attrs
+        .iter()
+        .find(|(k, _)| k == name)
+        .map(|(_, v)| v.as_str())
+}
+
+fn parse_iv(s: &str) -> Result<[u8; 16]> {
+    let hex = s
+        .strip_prefix("0x")
+        .or_else(|| s.strip_prefix("0X"))
+        .unwrap_or(s);
+    let value = u128::from_str_radix(hex, 16).map_err(|_| anyhow!("invalid IV: {}", s))?;
+    Ok(value.to_be_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aes::cipher::BlockEncryptMut;
+
+    #[test]
+    fn parses_master_and_encrypted_media_playlists() {
+        let base = Url::parse("https://cdn.example/show/master.m3u8").unwrap();
+        let master = "#EXTM3U\n\
+            #EXT-X-STREAM-INF:BANDWIDTH=800000,RESOLUTION=640x360,CODECS=\"avc1.4d401e,mp4a.40.2\"\n\
+            360/index.m3u8\n\
+            #EXT-X-STREAM-INF:BANDWIDTH=2500000,RESOLUTION=1280x720\n\
+            https://other.example/720/index.m3u8\n";
+        let Playlist::Master(variants) = parse_playlist(master, &base).unwrap() else {
+            panic!("expected master playlist");
+        };
+        assert_eq!(variants[0].uri, "https://cdn.example/show/360/index.m3u8");
+        assert_eq!(variants[0].resolution, Some((640, 360)));
+        assert_eq!(
+            select_variant(&variants, Some("360p")).unwrap().bandwidth,
+            Some(800000)
+        );
+        assert_eq!(
+            select_variant(&variants, None).unwrap().resolution,
+            Some((1280, 720))
+        );
+
+        let media = "#EXTM3U\n#EXT-X-MEDIA-SEQUENCE:7\n\
+            #EXTINF:4.0,\nseg7.ts\n\
+            #EXT-X-KEY:METHOD=AES-128,URI=\"key.bin\",IV=0x0000000000000000000000000000000A\n\
+            #EXTINF:4.0,\nseg8.ts\n";
+        let Playlist::Media(media) = parse_playlist(media, &base).unwrap() else {
+            panic!("expected media playlist");
+        };
+        assert_eq!(media.segments.len(), 2);
+        assert_eq!(media.segments[0].sequence, 7);
+        assert!(media.segments[0].key.is_none());
+        let key = media.segments[1].key.as_ref().unwrap();
+        assert_eq!(key.uri, "https://cdn.example/show/key.bin");
+        assert_eq!(key.iv, Some(10u128.to_be_bytes()));
+    }
+
+    #[test]
+    fn decrypts_with_sequence_iv() {
+        let key = [7u8; 16];
+        let plain = b"transport stream bytes".to_vec();
+        let mut buf = plain.clone();
+        buf.resize(32, 0);
+        let iv = 3u128.to_be_bytes();
+        let enc = cbc::Encryptor::<aes::Aes128>::new(&key.into(), &iv.into())
+            .encrypt_padded_mut::<Pkcs7>(&mut buf, plain.len())
+            .unwrap()
+            .to_vec();
+        assert_eq!(decrypt_segment(&enc, &key, None, 3).unwrap(), plain);
+    }
+}
@@ -1,8 +1,14 @@
 pub mod aggregator;
+pub mod comicinfo;
 pub mod dao;
 pub mod db;
+pub mod error;
+pub mod events;
+pub mod hls;
 pub mod mapping;
 pub mod plugins;
+pub mod probe;
+pub mod rate_limit;
 pub mod storage;
 pub mod types;
 
@@ -10,21 +16,34 @@ pub mod types;
 
 /// Convenience re-exports for embedders.
 pub mod prelude {
+    pub use crate::aggregator::EpisodeStreams;
+    pub use crate::error::TouringError;
+    pub use crate::events::LibraryEvent;
     pub use crate::plugins::{
-        Asset, AssetKind, Media, MediaType, ProviderCapabilities, Unit, UnitKind,
+        Asset, AssetKind, Media, MediaType, PluginConfig, PluginStatus, ProviderCapabilities, Unit,
+        UnitKind,
     };
+    pub use crate::probe::StreamInfo;
+    pub use crate::types::CacheCodec;
     pub use crate::{
-        ChapterInfo, DownloadProgress, DownloadResult, EpisodeInfo, LibraryStats, SeriesInfo,
-        SeriesMetadataUpdate, SeriesSource,
+        AggregateCapabilities, BulkPathReport, ChapterInfo, ChapterListItem, ChapterPage,
+        ChapterSort, DownloadOptions, DownloadProgress, DownloadResult, EpisodeInfo, IdKind,
+        LibraryStats, ManifestChapter, ReadingEntry, SeriesInfo, SeriesManifest,
+        SeriesMetadataUpdate, SeriesSource, SeriesStatistics, SourceInfo, StoredStream,
     };
 }
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-use crate::aggregator::Aggregator;
-use crate::plugins::{Asset, Media, ProviderCapabilities, Unit};
+use crate::aggregator::{Aggregator, EpisodeStreams};
+use crate::events::LibraryEvent;
+use crate::plugins::{
+    Asset, AssetKind, Media, MediaType, PluginConfig, PluginStatus, ProviderCapabilities, Unit,
+    UnitKind,
+};
+use crate::probe::StreamInfo;
 
 // --- Data structures for UI API ---
 
@@ -55,6 +74,17 @@ pub struct SeriesSource {
     pub external_id: String,
 }
 
+/// A source known to the database and/or currently loaded as a plugin.
+/// Plugins loaded but never recorded in `sources` have no version/timestamps.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceInfo {
+    pub id: String,
+    pub version: Option<String>,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+    pub loaded: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChapterInfo {
     pub id: String,
@@ -83,6 +113,60 @@ pub struct EpisodeInfo {
     pub stream_count: usize,
 }
 
+/// A stored section (arc) of a series with its member chapter/episode ids in order.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SectionInfo {
+    pub id: String,
+    pub series_id: String,
+    pub source_id: String,
+    pub external_id: String,
+    pub number_text: Option<String>,
+    pub number_num: Option<f64>,
+    pub title: Option<String>,
+    pub lang: Option<String>,
+    pub unit_ids: Vec<String>,
+}
+
+/// One entry of a series table of contents (`get_series_reading_order`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ReadingEntry {
+    /// Section header; its chapters follow immediately.
+    Section(SectionInfo),
+    Chapter(ChapterInfo),
+}
+
+/// A stream persisted for an episode by `get_episode_streams`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredStream {
+    pub url: String,
+    pub quality: Option<String>,
+    pub mime: Option<String>,
+}
+
+/// Union of the capabilities of every loaded plugin (deduplicated, first-seen order).
+#[derive(Debug, Clone, Default)]
+pub struct AggregateCapabilities {
+    pub media_types: Vec<MediaType>,
+    pub unit_kinds: Vec<UnitKind>,
+    pub asset_kinds: Vec<AssetKind>,
+    /// Plugins that reported capabilities.
+    pub sources: Vec<String>,
+}
+
+impl AggregateCapabilities {
+    pub fn supports_media_type(&self, media_type: &MediaType) -> bool {
+        self.media_types
+            .iter()
+            .any(|m| same_media_type(m, media_type))
+    }
+
+    pub fn supports_asset_kind(&self, asset_kind: &AssetKind) -> bool {
+        self.asset_kinds
+            .iter()
+            .any(|a| same_asset_kind(a, asset_kind))
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChapterProgress {
     pub chapter_id: String,
@@ -92,6 +176,56 @@ pub struct ChapterProgress {
     pub updated_at: i64,
 }
 
+/// Ordering for chapter lists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ChapterSort {
+    #[default]
+    NumberAsc,
+    NumberDesc,
+}
+
+/// What an id string refers to (`describe_id`). Canonical ids are checked first (series,
+/// chapter, episode), then source external ids (series mapping, chapter, episode), so a
+/// canonical id always wins over an external id that happens to collide with it. This is
+/// the same order the "canonical or external" methods like `get_chapter_meta` use.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IdKind {
+    CanonicalSeries,
+    CanonicalChapter,
+    CanonicalEpisode,
+    /// A source's media id mapped in `series_sources`.
+    ExternalSeries {
+        source_id: String,
+        series_id: String,
+    },
+    ExternalChapter {
+        source_id: String,
+        chapter_id: String,
+    },
+    ExternalEpisode {
+        source_id: String,
+        episode_id: String,
+    },
+    Unknown,
+}
+
+/// A chapter annotated with its reading progress and whether it has been downloaded.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChapterListItem {
+    pub chapter: ChapterInfo,
+    pub progress: Option<ChapterProgress>,
+    pub downloaded: bool,
+}
+
+/// One page of a chapter list; `total` counts all chapters matching the filter.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChapterPage {
+    pub items: Vec<ChapterListItem>,
+    pub total: usize,
+    pub page: usize,
+    pub page_size: usize,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DownloadProgress {
     pub current: usize,
@@ -107,6 +241,138 @@ pub struct DownloadResult {
     pub error: Option<String>,
 }
 
+/// Outcome of `clone_progress_to_source`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProgressCloneReport {
+    /// Source chapters whose progress was carried to a destination chapter.
+    pub mapped: usize,
+    /// Source chapter ids with progress but no numbered counterpart on the destination.
+    pub unmatched: Vec<String>,
+}
+
+/// What `verify_all_downloads` found wrong with a downloaded item.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DownloadProblem {
+    /// The series' download folder itself is gone.
+    MissingFolder,
+    /// A recorded file no longer exists (or can't be read).
+    Missing,
+    /// A recorded file's size or SHA-256 no longer matches.
+    Corrupt,
+    /// A chapter without a download record has neither a CBZ nor an image folder at its
+    /// expected path (see `get_download_path_tree`); it may never have been downloaded.
+    MissingChapter,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DownloadIssue {
+    pub series_id: String,
+    /// Chapter/episode id; `None` for series-level problems.
+    pub unit_id: Option<String>,
+    pub path: String,
+    pub problem: DownloadProblem,
+}
+
+/// Outcome of `move_series_download_path`: entry names moved, and those left behind with why.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MoveReport {
+    pub from: PathBuf,
+    pub to: PathBuf,
+    pub moved: Vec<String>,
+    pub failed: Vec<(String, String)>,
+}
+
+/// Outcome of `bulk_set_series_download_path`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BulkPathReport {
+    pub updated: Vec<String>,
+    /// Series ids that don't exist; nothing was written for them.
+    pub skipped: Vec<String>,
+}
+
+/// Read-only description of a series and the layout a download would write
+/// (`export_series_manifest`), for external downloaders or reconciling an existing folder.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeriesManifest {
+    pub series: SeriesInfo,
+    pub sources: Vec<SeriesSource>,
+    /// Folder chapter downloads go to (`resolve_download_dir`).
+    pub base_dir: PathBuf,
+    /// In download order.
+    pub chapters: Vec<ManifestChapter>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestChapter {
+    pub id: String,
+    pub external_id: String,
+    pub number_text: Option<String>,
+    pub number_num: Option<f64>,
+    pub title: Option<String>,
+    pub lang: Option<String>,
+    pub volume: Option<String>,
+    /// Image folder name under `base_dir`; the CBZ is `<folder>.cbz`.
+    pub folder: String,
+    pub downloaded: bool,
+}
+
+/// Tunables applied to every download made through `Touring`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DownloadOptions {
+    /// Record path/size/SHA-256 of written files in the `downloads` table.
+    pub record_checksums: bool,
+    /// Re-encode WebP pages as JPEG for readers that can't show WebP. Off by default (CPU cost).
+    pub convert_webp_to_jpeg: bool,
+    /// JPEG quality (1-100) used by `convert_webp_to_jpeg`.
+    pub jpeg_quality: u8,
+    /// Limit for each image/segment request, start to finish, so a stalled CDN can't hang a
+    /// whole series download. Independent of the plugin call timeout.
+    pub request_timeout: std::time::Duration,
+    /// Limit for establishing each connection.
+    pub connect_timeout: std::time::Duration,
+    /// Put a `ComicInfo.xml` (series, number, title, volume, language, page count) first in
+    /// CBZs from `download_chapter_cbz`, for Komga/Kavita libraries.
+    pub write_comic_info: bool,
+    /// Chapters fetched at once by the series downloads (minimum 1, the default). Pages still
+    /// go through each host's rate limit, so raising this mainly overlaps archive writing
+    /// and slow hosts.
+    pub chapter_concurrency: usize,
+    /// Space page, segment and cover requests with the per-host limiter plugins use
+    /// (`set_host_rate_limit`), so bulk downloads don't hit a CDN harder than the plugin
+    /// would. On by default; it only waits while a host interval is configured.
+    pub rate_limit_downloads: bool,
+}
+
+impl Default for DownloadOptions {
+    fn default() -> Self {
+        Self {
+            record_checksums: true,
+            convert_webp_to_jpeg: false,
+            jpeg_quality: 85,
+            request_timeout: std::time::Duration::from_secs(30),
+            connect_timeout: std::time::Duration::from_secs(10),
+            write_comic_info: false,
+            chapter_concurrency: 1,
+            rate_limit_downloads: true,
+        }
+    }
+}
+
+/// Per-series aggregate for detail screens (`get_series_statistics`).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SeriesStatistics {
+    pub chapters_count: usize,
+    pub episodes_count: usize,
+    pub read_count: usize,
+    pub unread_count: usize,
+    /// Chapters and episodes with recorded download files.
+    pub downloaded_count: usize,
+    /// Sum of per-chapter page counts (stored images, else the reader-reported total).
+    pub total_pages: usize,
+    pub languages: Vec<String>,
+    pub sources_count: usize,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LibraryStats {
     pub total_series: usize,
@@ -119,16 +385,215 @@ pub struct LibraryStats {
     pub expired_cache_entries: usize,
 }
 
+// (id, series_id, external_id, number_text, number_num, title, lang, volume/season)
+type UnitRow = (
+    String,
+    String,
+    String,
+    Option<String>,
+    Option<f64>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+);
+
+// Source id of chapters created by `import_cbz` that no plugin provides
+const LOCAL_SOURCE_ID: &str = "local";
+
+// Settings key for `set_preferred_quality`
+const PREFERRED_QUALITY_KEY: &str = "preferred_quality";
+
+// Settings key for `set_download_root`
+const DOWNLOAD_ROOT_KEY: &str = "download_root";
+
+// Segments fetched in parallel by `download_hls_episode` (written in order)
+const HLS_SEGMENT_CONCURRENCY: usize = 4;
+
+// Concurrent HEAD requests per chapter in `estimate_chapter_size`
+const SIZE_PROBE_CONCURRENCY: usize = 8;
+
+// Chapters actually probed by `estimate_series_size`; the rest are extrapolated
+const SERIES_SIZE_SAMPLE: usize = 10;
+
+// Title similarity (0..=1) needed by `fuzzy_resolve_series`; local rows must be near-exact,
+// live results only need to be the plausible best hit
+const LOCAL_TITLE_MATCH: f64 = 0.85;
+const LIVE_TITLE_MATCH: f64 = 0.5;
+
+// Progress updates further apart than this start a new reading session
+const READ_SESSION_IDLE_SECS: i64 = 30 * 60;
+
 /// High-level façade for embedders. Delegates all media/search/cache logic to `Aggregator`.
 pub struct Touring {
     agg: Aggregator,
+    // Root used when no `download_root` setting is stored: TOURING_DOWNLOAD_ROOT or the
+    // platform default
+    default_download_root: PathBuf,
+    // On-disk image cache (series covers), one subdirectory per series
+    image_cache_dir: PathBuf,
+    download_options: std::sync::RwLock<DownloadOptions>,
+    // Whether `search` and the `*_cached_with_sources` searches upsert series rows
+    auto_persist_search: std::sync::atomic::AtomicBool,
 }
 
 impl Touring {
     /// Initialize database and (optionally) run migrations. Does not start any internal runtimes.
     pub async fn connect(database_url: Option<&str>, run_migrations: bool) -> Result<Self> {
-        let agg = Aggregator::new(database_url, run_migrations).await?;
-        Ok(Self { agg })
+        Self::from_aggregator(Aggregator::new(database_url, run_migrations).await?)
+    }
+
+    /// Open an existing SQLite library for reporting or analysis without ever mutating it:
+    /// the connection uses `mode=ro`, migrations are skipped, searches neither persist series
+    /// nor write cache rows, and write methods fail with `TouringError::ReadOnly` (check with
+    /// `TouringError::classify`). The schema must already be current.
+    pub async fn open_read_only(database_url: &str) -> Result<Self> {
+        let touring = Self::from_aggregator(Aggregator::open_read_only(database_url).await?)?;
+        touring.set_auto_persist_search(false);
+        Ok(touring)
+    }
+
+    fn from_aggregator(agg: Aggregator) -> Result<Self> {
+        let default_download_root = std::env::var("TOURING_DOWNLOAD_ROOT")
+            .ok()
+            .filter(|s| !s.trim().is_empty())
+            .map(PathBuf::from)
+            .unwrap_or_else(default_download_root);
+        let image_cache_dir = std::env::var("TOURING_IMAGE_CACHE_DIR")
+            .ok()
+            .filter(|s| !s.trim().is_empty())
+            .map(PathBuf::from)
+            .unwrap_or_else(default_image_cache_dir);
+        // Persisting is the long-standing default; only an explicit "off" value disables it
+        let auto_persist_search = !matches!(
+            std::env::var("TOURING_AUTO_PERSIST_SEARCH")
+                .map(|v| v.trim().to_ascii_lowercase())
+                .as_deref(),
+            Ok("0") | Ok("false") | Ok("no")
+        );
+        Ok(Self {
+            agg,
+            default_download_root,
+            image_cache_dir,
+            download_options: std::sync::RwLock::new(DownloadOptions::default()),
+            auto_persist_search: auto_persist_search.into(),
+        })
+    }
+
+    /// Root directory used for series that have no stored download_path: the stored
+    /// `download_root` setting, else `TOURING_DOWNLOAD_ROOT`, else the platform default.
+    pub async fn download_root(&self) -> Result<PathBuf> {
+        let pool = self.agg.database().pool().clone();
+        Ok(crate::dao::get_setting(&pool, DOWNLOAD_ROOT_KEY)
+            .await?
+            .map(PathBuf::from)
+            .unwrap_or_else(|| self.default_download_root.clone()))
+    }
+
+    /// Persist the download root in the settings table so it survives restarts; `None` clears
+    /// it (falling back to `TOURING_DOWNLOAD_ROOT` or the platform default).
+    pub async fn set_download_root(&self, root: Option<&Path>) -> Result<()> {
+        let pool = self.agg.database().pool().clone();
+        match root.filter(|r| !r.as_os_str().is_empty()) {
+            Some(r) => {
+                crate::dao::set_setting(&pool, DOWNLOAD_ROOT_KEY, &r.to_string_lossy()).await
+            }
+            None => crate::dao::delete_setting(&pool, DOWNLOAD_ROOT_KEY).await,
+        }
+    }
+
+    /// Current download options.
+    pub fn download_options(&self) -> DownloadOptions {
+        self.download_options.read().unwrap().clone()
+    }
+
+    /// Replace the download options used by all download methods started from now on.
+    pub fn set_download_options(&self, options: DownloadOptions) {
+        *self.download_options.write().unwrap() = options;
+    }
+
+    /// Codec used for new cache rows.
+    pub fn cache_codec(&self) -> crate::types::CacheCodec {
+        self.agg.cache_codec()
+    }
+
+    /// Choose JSON or MessagePack for new cache rows (also `TOURING_CACHE_CODEC`).
+    pub fn set_cache_codec(&self, codec: crate::types::CacheCodec) {
+        self.agg.set_cache_codec(codec);
+    }
+
+    /// Skip caching payloads larger than `limit` bytes (data is still returned). 0 = unlimited.
+    /// Defaults to 4 MiB or `TOURING_MAX_CACHE_PAYLOAD_BYTES`.
+    pub fn set_max_cache_payload_bytes(&self, limit: usize) {
+        self.agg.set_max_cache_payload_bytes(limit);
+    }
+
+    /// True if `connect(None, ..)` fell back to an in-memory database because the default
+    /// data directory wasn't writable; the library, downloads and cache are lost on exit.
+    pub fn is_ephemeral(&self) -> bool {
+        self.agg.database().is_ephemeral()
+    }
+
+    /// Rebuild the database pool from the original URL when every call fails because the
+    /// connection is gone (a remote database after network loss or device sleep). Errors if
+    /// the database still can't be reached, or for the in-memory fallback.
+    pub async fn reconnect(&mut self) -> Result<()> {
+        self.agg.reconnect_database().await
+    }
+
+    /// True for a library opened with `open_read_only`.
+    pub fn is_read_only(&self) -> bool {
+        self.agg.database().is_read_only()
+    }
+
+    /// Library-wide default for whether `search`, `search_manga_cached_with_sources` and
+    /// `search_anime_cached_with_sources` create series rows for their results. On by default
+    /// (also `TOURING_AUTO_PERSIST_SEARCH=0`); turn off so exploratory searches leave the
+    /// library untouched. The `*_no_persist` methods never persist either way.
+    pub fn set_auto_persist_search(&self, enabled: bool) {
+        self.auto_persist_search
+            .store(enabled, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    pub fn auto_persist_search(&self) -> bool {
+        self.auto_persist_search
+            .load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    /// Return search results before their series are upserted; persistence then runs on a
+    /// spawned task (default off; also `TOURING_BACKGROUND_PERSIST=1`). Callers that look up
+    /// series right after searching should `flush_pending_persistence` first.
+    pub fn set_background_persist(&self, enabled: bool) {
+        self.agg.set_background_persist(enabled);
+    }
+
+    /// Wait until background search persistence has written every result returned so far.
+    pub async fn flush_pending_persistence(&self) {
+        self.agg.flush_pending_persistence().await
+    }
+
+    /// Whether offline mode is on (see `set_offline`).
+    pub fn is_offline(&self) -> bool {
+        self.agg.is_offline()
+    }
+
+    /// Offline mode: searches and chapter page lists are served from the cache/database only
+    /// (even if expired), while refreshes, chapter/episode/stream fetches, stream probes and
+    /// downloads fail fast with `TouringError::Offline` instead of waiting on timeouts.
+    /// Also `TOURING_OFFLINE=1`.
+    pub fn set_offline(&self, offline: bool) {
+        self.agg.set_offline(offline);
+    }
+
+    /// Receive library changes (series added/deleted, progress, downloads, metadata) as they
+    /// happen. Receivers that fall more than 256 events behind get `RecvError::Lagged`.
+    pub fn subscribe(&self) -> tokio::sync::broadcast::Receiver<LibraryEvent> {
+        self.agg.events().subscribe()
+    }
+
+    /// Load `.cwasm` (true) or `.wasm` (false) first when a plugin ships both, e.g. to debug
+    /// with the plain component. Applies to subsequent (re)loads; also `TOURING_PREFER_PRECOMPILED`.
+    pub fn set_prefer_precompiled(&self, prefer: bool) {
+        self.agg.set_prefer_precompiled(prefer);
     }
 
     /// Load all plugins from a directory.
@@ -141,11 +606,36 @@ impl Touring {
         self.agg.reload_plugins_from_directory(dir).await
     }
 
+    /// Register a plugin from component bytes bundled with the app (plain or precompiled for
+    /// this engine) when there is no plugin directory to load from. `config` takes the place
+    /// of the plugin's `.toml` (`PluginConfig::from_toml` parses one). Kept across directory
+    /// (re)loads; a directory plugin with the same name replaces it.
+    pub async fn load_plugin_bytes(
+        &mut self,
+        name: &str,
+        wasm: &[u8],
+        config: PluginConfig,
+    ) -> Result<()> {
+        self.agg.load_plugin_bytes(name, wasm, config).await
+    }
+
+    /// Eagerly instantiate all plugins (e.g. behind a splash screen) so the first search
+    /// doesn't pay the start-up cost. Returns per-plugin success/failure.
+    pub async fn warm_plugins(&self) -> Vec<(String, Result<()>)> {
+        self.agg.plugin_manager().warm_all().await
+    }
+
     /// List loaded plugin names.
     pub fn list_plugins(&self) -> Vec<String> {
         self.agg.list_plugins()
     }
 
+    /// Per-plugin health for a source-status view: whether it's loaded and when a fetch last
+    /// succeeded or failed (with the error). Does not load plugins.
+    pub async fn get_plugin_status(&self) -> Vec<PluginStatus> {
+        self.agg.get_plugin_status().await
+    }
+
     /// Get plugin capabilities (cached by default, or refresh).
     pub async fn get_capabilities(
         &self,
@@ -154,19 +644,166 @@ impl Touring {
         self.agg.get_capabilities(refresh).await
     }
 
+    /// Forget cached capabilities of one plugin (or all with `None`) so the next capabilities
+    /// call asks the plugin again, e.g. after it was updated in place. Errors for an unknown name.
+    pub async fn reset_plugin_capabilities_cache(&self, name: Option<&str>) -> Result<()> {
+        self.agg.reset_capabilities_cache(name).await
+    }
+
+    /// How long the all-plugin `get_capabilities(false)` and `get_allowed_hosts` results are
+    /// reused before plugins are asked again (default 10s, `TOURING_PLUGIN_INFO_TTL_MS`; zero
+    /// disables). Plugin (re)loads, host overrides and capability resets invalidate it.
+    pub fn set_plugin_info_cache_ttl(&self, ttl: std::time::Duration) {
+        self.agg.set_plugin_info_ttl(ttl);
+    }
+
+    /// Merge the cached capabilities of all loaded plugins, e.g. to hide an "Anime" tab
+    /// when no source supports anime. Plugins that fail to report are left out.
+    pub async fn aggregate_capabilities(&self) -> Result<AggregateCapabilities> {
+        let mut agg = AggregateCapabilities::default();
+        for (name, caps) in self.agg.get_capabilities(false).await? {
+            for m in caps.media_types {
+                if !agg.supports_media_type(&m) {
+                    agg.media_types.push(m);
+                }
+            }
+            for u in caps.unit_kinds {
+                if !agg.unit_kinds.iter().any(|x| same_unit_kind(x, &u)) {
+                    agg.unit_kinds.push(u);
+                }
+            }
+            for a in caps.asset_kinds {
+                if !agg.supports_asset_kind(&a) {
+                    agg.asset_kinds.push(a);
+                }
+            }
+            agg.sources.push(name);
+        }
+        Ok(agg)
+    }
+
+    /// Whether plugin `name` advertises `media_type` (cached capabilities; unknown plugin = false).
+    pub async fn plugin_supports(&self, name: &str, media_type: &MediaType) -> Result<bool> {
+        let caps = self
+            .agg
+            .plugin_manager()
+            .get_capabilities_for(name, false)
+            .await?;
+        Ok(caps.is_some_and(|c| c.media_types.iter().any(|m| same_media_type(m, media_type))))
+    }
+
+    /// Whether plugin `name` advertises `asset_kind` (cached capabilities; unknown plugin = false).
+    pub async fn plugin_supports_asset(&self, name: &str, asset_kind: &AssetKind) -> Result<bool> {
+        let caps = self
+            .agg
+            .plugin_manager()
+            .get_capabilities_for(name, false)
+            .await?;
+        Ok(caps.is_some_and(|c| c.asset_kinds.iter().any(|a| same_asset_kind(a, asset_kind))))
+    }
+
+    /// Abort the plugin calls in flight (e.g. when leaving a screen) so their workers are
+    /// free for the next request; an aborted plugin is re-instantiated on its next call.
+    /// Aborted searches and fetches fail with `TouringError::Cancelled` and nothing from
+    /// them is cached.
+    pub fn cancel_all_plugin_calls(&self) {
+        self.agg.plugin_manager().cancel_all_calls();
+    }
+
     /// Get allowed hosts per plugin.
     pub async fn get_allowed_hosts(&self) -> Result<Vec<(String, Vec<String>)>> {
         self.agg.get_allowed_hosts().await
     }
 
-    /// Search manga with per-source caching; upserts series + mappings. Returns (source, media).
+    /// Allowed hosts of a single plugin (only that plugin is instantiated); `None` if no
+    /// plugin with that name is loaded.
+    pub async fn get_plugin_allowed_hosts(&self, name: &str) -> Result<Option<Vec<String>>> {
+        self.agg.get_plugin_allowed_hosts(name).await
+    }
+
+    /// Allow an additional host for a plugin at runtime (persisted across reloads).
+    /// Has no effect on plugins without an allow-list, which already allow every host.
+    pub async fn add_allowed_host(&self, source: &str, host: &str) -> Result<()> {
+        self.agg.set_host_allowed(source, host, true).await
+    }
+
+    /// Remove a host from a plugin's allow-list at runtime (persisted across reloads).
+    /// Fails if it is the last allowed host, since an empty allow-list would deny every host.
+    pub async fn remove_allowed_host(&self, source: &str, host: &str) -> Result<()> {
+        self.agg.set_host_allowed(source, host, false).await
+    }
+
+    /// Raw output of one plugin export (`fetchmedialist`, `fetchunits` or `fetchassets`) as
+    /// JSON, for plugin authors checking what their wasm returns. Nothing is filtered, cached
+    /// or persisted. Requires the `debug-raw` feature.
+    #[cfg(feature = "debug-raw")]
+    pub async fn plugin_call_raw(&self, source: &str, op: &str, arg: &str) -> Result<String> {
+        self.agg.ensure_online("calling a plugin export")?;
+        self.agg
+            .plugin_manager()
+            .plugin_call_raw(source, op, arg)
+            .await
+    }
+
+    /// Minimum spacing between plugin HTTP requests to the same host, shared across all plugins.
+    /// `None` (or zero) disables host-level limiting. Defaults to `TOURING_HOST_RATE_LIMIT_MS`.
+    pub fn set_host_rate_limit(&self, interval: Option<std::time::Duration>) {
+        self.agg
+            .plugin_manager()
+            .host_rate_limiter()
+            .set_interval(interval.unwrap_or_default());
+    }
+
+    /// Searches skip sources that fail; when every source failed the search returns `Err`
+    /// (default) so callers can tell "search failed, retry" apart from "no matches".
+    /// Disable to get an empty list instead.
+    pub fn set_search_fail_when_all_sources_error(&self, enabled: bool) {
+        self.agg
+            .plugin_manager()
+            .set_fail_when_all_sources_error(enabled);
+    }
+
+    /// Search any media type with per-source caching; upserts series + mappings. Use this for
+    /// sources serving `MediaType::Other` content (novels, ...): the series kind is the
+    /// lowercased label. Returns (source, media).
+    pub async fn search(
+        &self,
+        media_type: MediaType,
+        query: &str,
+        refresh: bool,
+    ) -> Result<Vec<(String, Media)>> {
+        self.agg
+            .search_kind(media_type, query, refresh, self.auto_persist_search())
+            .await
+    }
+
+    /// `search`, then stable-sorted so results from sources declaring `lang_pref` in their
+    /// plugin TOML (`languages = ["en"]`) come first; order within each group is unchanged.
+    /// `None` behaves exactly like `search`.
+    pub async fn search_with_language_preference(
+        &self,
+        media_type: MediaType,
+        query: &str,
+        refresh: bool,
+        lang_pref: Option<&str>,
+    ) -> Result<Vec<(String, Media)>> {
+        let mut results = self.search(media_type, query, refresh).await?;
+        if let Some(lang) = lang_pref.map(str::trim).filter(|l| !l.is_empty()) {
+            let pm = self.agg.plugin_manager();
+            results.sort_by_key(|(source, _)| !pm.plugin_serves_language(source, lang));
+        }
+        Ok(results)
+    }
+
+    /// Search manga with per-source caching; upserts series + mappings unless
+    /// `set_auto_persist_search(false)`. Returns (source, media).
     pub async fn search_manga_cached_with_sources(
         &self,
         query: &str,
         refresh: bool,
     ) -> Result<Vec<(String, Media)>> {
         self.agg
-            .search_manga_cached_with_sources(query, refresh)
+            .search_kind(MediaType::Manga, query, refresh, self.auto_persist_search())
             .await
     }
 
@@ -179,14 +816,24 @@ impl Touring {
         self.agg.search_manga_no_persist(query, refresh).await
     }
 
-    /// Search anime with per-source caching; upserts series + mappings. Returns (source, media).
+    /// Search anime without persisting to database (UI display only). Returns (source, media).
+    pub async fn search_anime_no_persist(
+        &self,
+        query: &str,
+        refresh: bool,
+    ) -> Result<Vec<(String, Media)>> {
+        self.agg.search_anime_no_persist(query, refresh).await
+    }
+
+    /// Search anime with per-source caching; upserts series + mappings unless
+    /// `set_auto_persist_search(false)`. Returns (source, media).
     pub async fn search_anime_cached_with_sources(
         &self,
         query: &str,
         refresh: bool,
     ) -> Result<Vec<(String, Media)>> {
         self.agg
-            .search_anime_cached_with_sources(query, refresh)
+            .search_kind(MediaType::Anime, query, refresh, self.auto_persist_search())
             .await
     }
 
@@ -210,62 +857,328 @@ impl Touring {
         self.agg.preview_anime_episodes(external_anime_id).await
     }
 
+    /// Fetch sections/arcs for a media id (external). Fetch chapters or episodes first so the
+    /// stored sections can be linked to them (see `list_series_sections`).
+    pub async fn get_sections(&self, media_id: &str) -> Result<Vec<Unit>> {
+        self.agg.get_sections(media_id).await
+    }
+
+    /// Stored sections of a series in source order, with their member unit ids.
+    pub async fn list_series_sections(&self, series_id: &str) -> Result<Vec<SectionInfo>> {
+        let pool = self.agg.database().pool().clone();
+        let rows = crate::dao::list_sections_for_series(&pool, series_id).await?;
+        Ok(rows
+            .into_iter()
+            .map(|(s, unit_ids)| SectionInfo {
+                id: s.id,
+                series_id: s.series_id,
+                source_id: s.source_id,
+                external_id: s.external_id,
+                number_text: s.number_text,
+                number_num: s.number_num,
+                title: s.title,
+                lang: s.lang,
+                unit_ids,
+            })
+            .collect())
+    }
+
+    /// Linear reading order for a table of contents: chapters by number, except that chapters
+    /// belonging to a stored section are grouped under its header (in section order) where the
+    /// section's first chapter would appear. A chapter listed by several sections goes with the
+    /// first; sections without stored chapters are left out.
+    pub async fn get_series_reading_order(&self, series_id: &str) -> Result<Vec<ReadingEntry>> {
+        let chapters = self.list_chapters_for_series_with_images(series_id).await?;
+        let sections = self.list_series_sections(series_id).await?;
+
+        let index: std::collections::HashMap<&str, usize> = chapters
+            .iter()
+            .enumerate()
+            .map(|(i, c)| (c.id.as_str(), i))
+            .collect();
+        // owner[chapter] = section; members[section] = its chapters in section order
+        let mut owner: Vec<Option<usize>> = vec![None; chapters.len()];
+        let mut members: Vec<Vec<usize>> = vec![Vec::new(); sections.len()];
+        for (s, section) in sections.iter().enumerate() {
+            for unit_id in &section.unit_ids {
+                if let Some(&c) = index.get(unit_id.as_str()) {
+                    if owner[c].is_none() {
+                        owner[c] = Some(s);
+                        members[s].push(c);
+                    }
+                }
+            }
+        }
+
+        let mut chapters: Vec<Option<ChapterInfo>> = chapters.into_iter().map(Some).collect();
+        let mut sections: Vec<Option<SectionInfo>> = sections.into_iter().map(Some).collect();
+        let mut out = Vec::with_capacity(chapters.len() + sections.len());
+        for c in 0..chapters.len() {
+            match owner[c] {
+                Some(s) => {
+                    // Already emitted with its section otherwise
+                    if let Some(section) = sections[s].take() {
+                        out.push(ReadingEntry::Section(section));
+                        for &member in &members[s] {
+                            out.extend(chapters[member].take().map(ReadingEntry::Chapter));
+                        }
+                    }
+                }
+                None => out.extend(chapters[c].take().map(ReadingEntry::Chapter)),
+            }
+        }
+        Ok(out)
+    }
+
     /// Fetch episode streams for an episode id; persists streams (dedupe by (episode_id, url)).
     pub async fn get_episode_streams(&self, external_episode_id: &str) -> Result<Vec<Asset>> {
         self.agg.get_episode_streams(external_episode_id).await
     }
 
-    /// Fetch chapter images (URLs) with caching and optional refresh. Accepts canonical or external chapter id.
-    pub async fn get_chapter_images_with_refresh(
+    /// Like `get_episode_streams`, but reports whether the streams were persisted
+    /// (`episode_id`). An episode not stored yet is created under `series_id` if that series
+    /// is mapped to the serving source, mirroring how chapter fetches persist.
+    pub async fn get_episode_streams_for_series(
         &self,
-        chapter_id: &str,
-        refresh: bool,
-    ) -> Result<Vec<String>> {
+        external_episode_id: &str,
+        series_id: Option<&str>,
+    ) -> Result<EpisodeStreams> {
         self.agg
-            .get_chapter_images_with_refresh(chapter_id, refresh)
+            .get_episode_streams_for_series(external_episode_id, series_id)
             .await
     }
 
-    // Convenience: accepts canonical or external chapter id
-    pub async fn get_chapter_images(&self, chapter_id: &str) -> Result<Vec<String>> {
-        self.agg.get_chapter_images(chapter_id).await
-    }
-
-    // --- Series management APIs ---
-
-    pub async fn list_series(&self, kind: Option<&str>) -> Result<Vec<(String, String)>> {
+    /// Remember the user's preferred stream quality (e.g. "720p"); `None` clears it.
+    pub async fn set_preferred_quality(&self, quality: Option<&str>) -> Result<()> {
         let pool = self.agg.database().pool().clone();
-        crate::dao::list_series(&pool, kind).await
+        match quality.map(str::trim).filter(|q| !q.is_empty()) {
+            Some(q) => crate::dao::set_setting(&pool, PREFERRED_QUALITY_KEY, q).await,
+            None => crate::dao::delete_setting(&pool, PREFERRED_QUALITY_KEY).await,
+        }
     }
 
-    pub async fn list_chapters_for_series(
-        &self,
-        series_id: &str,
-    ) -> Result<Vec<(String, Option<f64>, Option<String>)>> {
+    pub async fn preferred_quality(&self) -> Result<Option<String>> {
         let pool = self.agg.database().pool().clone();
-        crate::dao::list_chapters_for_series(&pool, series_id).await
+        crate::dao::get_setting(&pool, PREFERRED_QUALITY_KEY).await
     }
 
-    pub async fn list_episodes_for_series(
-        &self,
-        series_id: &str,
-    ) -> Result<Vec<(String, Option<f64>, Option<String>)>> {
+    /// Pick a stored stream for a canonical episode id: the one matching the preferred
+    /// quality, else the highest known quality, else the first stored. `None` if no streams
+    /// have been fetched yet (call `get_episode_streams` first). Stored qualities are inferred
+    /// from asset height or URL (see `mapping::stream_quality`), not reported by plugins.
+    pub async fn get_preferred_stream(&self, episode_id: &str) -> Result<Option<StoredStream>> {
         let pool = self.agg.database().pool().clone();
-        crate::dao::list_episodes_for_series(&pool, series_id).await
+        let streams = crate::dao::list_streams(&pool, episode_id).await?;
+        let preferred = crate::dao::get_setting(&pool, PREFERRED_QUALITY_KEY).await?;
+        Ok(
+            pick_stream(streams, preferred.as_deref()).map(|s| StoredStream {
+                url: s.url,
+                quality: s.quality,
+                mime: s.mime,
+            }),
+        )
     }
 
-    pub async fn get_chapter_progress(&self, chapter_id: &str) -> Result<Option<ChapterProgress>> {
-        let pool = self.agg.database().pool().clone();
-        if let Some((canonical_id, _series_id)) =
-            crate::dao::find_chapter_identity(&pool, chapter_id).await?
-        {
-            crate::dao::get_chapter_progress(&pool, &canonical_id).await
-        } else {
-            Ok(None)
+    /// Probe a direct stream URL for size/type and, for MP4s, duration/resolution/codecs.
+    /// The URL's host must be permitted by at least one loaded plugin.
+    pub async fn get_stream_info(&self, url: &str) -> Result<StreamInfo> {
+        self.agg.ensure_online("probing a stream")?;
+        let pm = self.agg.plugin_manager();
+        if !pm.url_allowed_by_any(url).await? {
+            return Err(anyhow::anyhow!("host not allowed by any plugin: {}", url));
         }
+        if let Some(host) = url::Url::parse(url)?.host_str() {
+            pm.host_rate_limiter().acquire(host).await;
+        }
+        let client = self.download_client()?;
+        crate::probe::probe_stream(&client, url).await
     }
 
-    pub async fn get_chapter_progress_for_series(
+    /// Fetch chapter images (URLs) with caching and optional refresh. Accepts canonical or external chapter id.
+    pub async fn get_chapter_images_with_refresh(
+        &self,
+        chapter_id: &str,
+        refresh: bool,
+    ) -> Result<Vec<String>> {
+        self.agg
+            .get_chapter_images_with_refresh(chapter_id, refresh)
+            .await
+    }
+
+    /// Like `get_chapter_images_with_refresh`, plus the name of the plugin that served the
+    /// pages (`None` when no source returned any, or for cache entries written before this was recorded).
+    pub async fn get_chapter_images_with_source_name(
+        &self,
+        chapter_id: &str,
+        refresh: bool,
+    ) -> Result<(Option<String>, Vec<String>)> {
+        self.agg
+            .get_chapter_images_with_source_name(chapter_id, refresh)
+            .await
+    }
+
+    /// Resolve a chapter by its number within a series and fetch its images.
+    /// `lang` restricts candidates when several chapters share a number; `prefer_source`
+    /// picks that source's chapter when available. Errors if no chapter matches.
+    pub async fn get_chapter_images_for_number(
+        &self,
+        series_id: &str,
+        number_num: f64,
+        lang: Option<&str>,
+        prefer_source: Option<&str>,
+        refresh: bool,
+    ) -> Result<Vec<String>> {
+        let pool = self.agg.database().pool().clone();
+        let chapter_id = crate::dao::find_chapter_id_by_number(
+            &pool,
+            series_id,
+            number_num,
+            lang,
+            prefer_source,
+        )
+        .await?
+        .ok_or_else(|| {
+            anyhow::anyhow!("No chapter {} found for series {}", number_num, series_id)
+        })?;
+        self.get_chapter_images_with_refresh(&chapter_id, refresh)
+            .await
+    }
+
+    // Convenience: accepts canonical or external chapter id
+    pub async fn get_chapter_images(&self, chapter_id: &str) -> Result<Vec<String>> {
+        self.agg.get_chapter_images(chapter_id).await
+    }
+
+    // --- Series management APIs ---
+
+    pub async fn list_series(&self, kind: Option<&str>) -> Result<Vec<(String, String)>> {
+        let pool = self.agg.database().pool().clone();
+        crate::dao::list_series(&pool, kind).await
+    }
+
+    /// Attach user metadata the schema doesn't model (rating, shelf, notes) to a series;
+    /// `None` removes the field. Errors if the series doesn't exist.
+    pub async fn set_series_custom_field(
+        &self,
+        series_id: &str,
+        key: &str,
+        value: Option<&str>,
+    ) -> Result<()> {
+        let pool = self.agg.database().pool().clone();
+        let key = key.trim();
+        if key.is_empty() {
+            return Err(anyhow::anyhow!("custom field key must not be empty"));
+        }
+        match value {
+            Some(v) => {
+                if !crate::dao::series_exists(&pool, series_id).await? {
+                    return Err(anyhow::anyhow!("series not found: {}", series_id));
+                }
+                crate::dao::set_series_custom_field(&pool, series_id, key, v).await
+            }
+            None => crate::dao::delete_series_custom_field(&pool, series_id, key).await,
+        }
+    }
+
+    /// All custom fields of a series as (key, value), by key.
+    pub async fn get_series_custom_fields(&self, series_id: &str) -> Result<Vec<(String, String)>> {
+        let pool = self.agg.database().pool().clone();
+        crate::dao::get_series_custom_fields(&pool, series_id).await
+    }
+
+    /// (id, title) of series whose custom field `key` is exactly `value`, by title.
+    pub async fn list_series_by_custom_field(
+        &self,
+        key: &str,
+        value: &str,
+    ) -> Result<Vec<(String, String)>> {
+        let pool = self.agg.database().pool().clone();
+        crate::dao::list_series_by_custom_field(&pool, key.trim(), value).await
+    }
+
+    pub async fn list_chapters_for_series(
+        &self,
+        series_id: &str,
+    ) -> Result<Vec<(String, Option<f64>, Option<String>)>> {
+        let pool = self.agg.database().pool().clone();
+        crate::dao::list_chapters_for_series(&pool, series_id).await
+    }
+
+    /// All chapters of a series in number order with `has_images`/`image_count` from stored
+    /// image rows and unexpired cached page lists, in one query (nothing is fetched).
+    pub async fn list_chapters_for_series_with_images(
+        &self,
+        series_id: &str,
+    ) -> Result<Vec<ChapterInfo>> {
+        let pool = self.agg.database().pool().clone();
+        let (rows, _) =
+            crate::dao::list_chapters_with_state(&pool, series_id, None, false, i64::MAX, 0)
+                .await?;
+        Ok(rows.into_iter().map(chapter_info).collect())
+    }
+
+    /// Chapters published at or after `since_epoch` (unix seconds), newest first ("new this
+    /// week"). Dates are normalized when chapters are stored; unparseable ones never match.
+    pub async fn list_chapters_published_since(
+        &self,
+        series_id: &str,
+        since_epoch: i64,
+    ) -> Result<Vec<ChapterInfo>> {
+        let pool = self.agg.database().pool().clone();
+        let rows = crate::dao::list_chapters_published_since(&pool, series_id, since_epoch).await?;
+        Ok(rows.into_iter().map(chapter_info).collect())
+    }
+
+    pub async fn list_episodes_for_series(
+        &self,
+        series_id: &str,
+    ) -> Result<Vec<(String, Option<f64>, Option<String>)>> {
+        let pool = self.agg.database().pool().clone();
+        crate::dao::list_episodes_for_series(&pool, series_id).await
+    }
+
+    /// Cheap check whether a series with this canonical id exists.
+    pub async fn series_exists(&self, series_id: &str) -> Result<bool> {
+        let pool = self.agg.database().pool().clone();
+        crate::dao::series_exists(&pool, series_id).await
+    }
+
+    /// Cheap check whether a chapter with this canonical id exists.
+    pub async fn chapter_exists(&self, chapter_id: &str) -> Result<bool> {
+        let pool = self.agg.database().pool().clone();
+        crate::dao::chapter_exists(&pool, chapter_id).await
+    }
+
+    /// Bump a series' `updated_at` without changing any metadata.
+    pub async fn touch_series(&self, series_id: &str) -> Result<()> {
+        let pool = self.agg.database().pool().clone();
+        crate::dao::touch_series(&pool, series_id).await
+    }
+
+    /// Record that the user opened a series (drives `list_recently_opened`, not `updated_at`).
+    pub async fn mark_series_opened(&self, series_id: &str) -> Result<()> {
+        let pool = self.agg.database().pool().clone();
+        crate::dao::mark_series_opened(&pool, series_id).await
+    }
+
+    /// Most recently opened series as (id, title, last_opened_at epoch secs).
+    pub async fn list_recently_opened(&self, limit: usize) -> Result<Vec<(String, String, i64)>> {
+        let pool = self.agg.database().pool().clone();
+        crate::dao::list_recently_opened(&pool, limit as i64).await
+    }
+
+    pub async fn get_chapter_progress(&self, chapter_id: &str) -> Result<Option<ChapterProgress>> {
+        let pool = self.agg.database().pool().clone();
+        if let Some((canonical_id, _series_id)) =
+            crate::dao::find_chapter_identity(&pool, chapter_id).await?
+        {
+            crate::dao::get_chapter_progress(&pool, &canonical_id).await
+        } else {
+            Ok(None)
+        }
+    }
+
+    pub async fn get_chapter_progress_for_series(
         &self,
         series_id: &str,
     ) -> Result<Vec<ChapterProgress>> {
@@ -273,34 +1186,194 @@ impl Touring {
         crate::dao::get_chapter_progress_for_series(&pool, series_id).await
     }
 
+    /// "Continue reading" shelf: series ordered by when they were last read, each with its
+    /// most recently updated progress row.
+    pub async fn get_last_read_series(
+        &self,
+        limit: usize,
+    ) -> Result<Vec<(SeriesInfo, ChapterProgress)>> {
+        let pool = self.agg.database().pool().clone();
+        let progress = crate::dao::latest_progress_per_series(&pool, limit as i64).await?;
+        let ids: Vec<String> = progress.iter().map(|p| p.series_id.clone()).collect();
+        let infos = self.get_series_info_batch(&ids).await?;
+        // Both are in shelf order; a series deleted in between is simply dropped
+        let mut infos = infos.into_iter().peekable();
+        let mut out = Vec::with_capacity(progress.len());
+        for p in progress {
+            if infos.peek().is_some_and(|info| info.id == p.series_id) {
+                out.push((infos.next().unwrap(), p));
+            }
+        }
+        Ok(out)
+    }
+
+    /// Record reading progress. With `total_pages: None` the page count is taken from the
+    /// cached page list when there is one (never fetched), so completion can be detected.
     pub async fn set_chapter_progress(
         &self,
         chapter_id: &str,
         page_index: i64,
         total_pages: Option<i64>,
     ) -> Result<()> {
+        let db = self.agg.database();
+        let pool = db.pool().clone();
+        let write = async {
+            if let Some((canonical_id, series_id)) =
+                crate::dao::find_chapter_identity(&pool, chapter_id).await?
+            {
+                let total_pages = match total_pages {
+                    Some(total) => Some(total),
+                    None => self
+                        .agg
+                        .cached_page_count(&canonical_id)
+                        .await?
+                        .filter(|&n| n > 0)
+                        .map(|n| n as i64),
+                };
+                crate::dao::upsert_chapter_progress(
+                    &pool,
+                    &canonical_id,
+                    &series_id,
+                    page_index,
+                    total_pages,
+                )
+                .await?;
+                crate::dao::record_reading_activity(
+                    &pool,
+                    &canonical_id,
+                    &series_id,
+                    current_epoch(),
+                    READ_SESSION_IDLE_SECS,
+                )
+                .await?;
+                self.agg
+                    .events()
+                    .publish(LibraryEvent::ChapterProgressUpdated {
+                        series_id,
+                        chapter_id: canonical_id,
+                    });
+            }
+            Ok(())
+        };
+        write.await.map_err(|e| db.map_busy(e))
+    }
+
+    /// Number of pages for list views, from stored image rows or else the cached page list
+    /// (expired included); never fetches. `None` if neither is known or the chapter is unknown.
+    pub async fn page_count(&self, chapter_id: &str) -> Result<Option<usize>> {
         let pool = self.agg.database().pool().clone();
-        if let Some((canonical_id, series_id)) =
-            crate::dao::find_chapter_identity(&pool, chapter_id).await?
-        {
-            crate::dao::upsert_chapter_progress(
-                &pool,
-                &canonical_id,
-                &series_id,
-                page_index,
-                total_pages,
-            )
-            .await?;
+        let Some((canonical_id, _)) = crate::dao::find_chapter_identity(&pool, chapter_id).await?
+        else {
+            return Ok(None);
+        };
+        let stored = crate::dao::count_chapter_images(&pool, &canonical_id).await?;
+        if stored > 0 {
+            return Ok(Some(stored as usize));
         }
-        Ok(())
+        self.agg.cached_page_count(&canonical_id).await
+    }
+
+    /// How far into a chapter the reader is, 0..=100; `None` without progress or a known
+    /// page count.
+    pub async fn chapter_read_percentage(&self, chapter_id: &str) -> Result<Option<f64>> {
+        let pool = self.agg.database().pool().clone();
+        match crate::dao::find_chapter_identity(&pool, chapter_id).await? {
+            Some((canonical_id, _)) => {
+                crate::dao::chapter_read_percentage(&pool, &canonical_id).await
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Series progress 0..=100: the average of per-chapter completion, so a half-read
+    /// chapter counts half. 0 for a series without chapters.
+    pub async fn series_read_percentage(&self, series_id: &str) -> Result<f64> {
+        let pool = self.agg.database().pool().clone();
+        crate::dao::series_read_percentage(&pool, series_id).await
+    }
+
+    /// Unread chapters across every series in one query, for an app badge. Same notion of
+    /// "read" as `get_series_statistics`: progress reached the last known page.
+    pub async fn count_unread_across_library(&self) -> Result<usize> {
+        let pool = self.agg.database().pool().clone();
+        Ok(crate::dao::count_unread_chapters(&pool).await?.max(0) as usize)
+    }
+
+    /// Carry reading progress from one source's chapters to another's within a series by
+    /// matching chapter numbers (same language preferred). Newer progress already on the
+    /// destination is kept.
+    pub async fn clone_progress_to_source(
+        &self,
+        series_id: &str,
+        from_source: &str,
+        to_source: &str,
+    ) -> Result<ProgressCloneReport> {
+        let pool = self.agg.database().pool().clone();
+        let mut report = ProgressCloneReport::default();
+        let rows = crate::dao::list_progress_for_source(&pool, series_id, from_source).await?;
+        for (chapter_id, number, lang, page_index, total_pages, updated_at) in rows {
+            let target = match number {
+                Some(n) => {
+                    crate::dao::find_source_chapter_by_number(
+                        &pool,
+                        series_id,
+                        to_source,
+                        n,
+                        lang.as_deref(),
+                    )
+                    .await?
+                }
+                None => None,
+            };
+            match target {
+                Some(target) => {
+                    crate::dao::merge_chapter_progress(
+                        &pool,
+                        &target,
+                        series_id,
+                        page_index,
+                        total_pages,
+                        updated_at,
+                    )
+                    .await?;
+                    report.mapped += 1;
+                    self.agg
+                        .events()
+                        .publish(LibraryEvent::ChapterProgressUpdated {
+                            series_id: series_id.to_string(),
+                            chapter_id: target,
+                        });
+                }
+                None => report.unmatched.push(chapter_id),
+            }
+        }
+        Ok(report)
+    }
+
+    /// Total seconds spent reading a series (sum of reading-session spans).
+    pub async fn series_reading_time(&self, series_id: &str) -> Result<i64> {
+        let pool = self.agg.database().pool().clone();
+        crate::dao::reading_time_secs(&pool, Some(series_id)).await
+    }
+
+    /// Total seconds spent reading across the whole library.
+    pub async fn total_reading_time(&self) -> Result<i64> {
+        let pool = self.agg.database().pool().clone();
+        crate::dao::reading_time_secs(&pool, None).await
     }
 
     pub async fn clear_chapter_progress(&self, chapter_id: &str) -> Result<()> {
         let pool = self.agg.database().pool().clone();
-        if let Some((canonical_id, _series_id)) =
+        if let Some((canonical_id, series_id)) =
             crate::dao::find_chapter_identity(&pool, chapter_id).await?
         {
             let _ = crate::dao::clear_chapter_progress(&pool, &canonical_id).await?;
+            self.agg
+                .events()
+                .publish(LibraryEvent::ChapterProgressUpdated {
+                    series_id,
+                    chapter_id: canonical_id,
+                });
         }
         Ok(())
     }
@@ -321,9 +1394,112 @@ impl Touring {
         crate::dao::set_series_download_path(&pool, series_id, path).await
     }
 
+    /// Set (or clear, with `None`) the download path of many series in one transaction,
+    /// e.g. "everything under this root". Missing series are skipped and reported.
+    pub async fn bulk_set_series_download_path(
+        &self,
+        entries: Vec<(String, Option<String>)>,
+    ) -> Result<BulkPathReport> {
+        let pool = self.agg.database().pool().clone();
+        let (updated, skipped) = crate::dao::bulk_set_series_download_paths(&pool, &entries)
+            .await
+            .map_err(|e| self.agg.database().map_busy(e))?;
+        Ok(BulkPathReport { updated, skipped })
+    }
+
+    /// Change a series' download folder. With `move_files`, already-downloaded chapter
+    /// folders/CBZs and episode files are moved (names preserved, best-effort) from the
+    /// current folder first; existing entries at the destination are never overwritten.
+    /// The stored path is updated either way.
+    pub async fn move_series_download_path(
+        &self,
+        series_id: &str,
+        new_path: &Path,
+        move_files: bool,
+    ) -> Result<MoveReport> {
+        let old_dir = self.resolve_download_dir(series_id).await?;
+        let mut report = MoveReport {
+            from: old_dir.clone(),
+            to: new_path.to_path_buf(),
+            ..Default::default()
+        };
+
+        if move_files && old_dir != new_path && old_dir.is_dir() {
+            tokio::fs::create_dir_all(new_path).await?;
+            let old_abs = tokio::fs::canonicalize(&old_dir).await?;
+            let new_abs = tokio::fs::canonicalize(new_path).await?;
+            let pool = self.agg.database().pool().clone();
+
+            let mut names = Vec::new();
+            for (_, name) in self.chapter_output_names(series_id).await? {
+                names.push(format!("{}.cbz", name));
+                names.push(name);
+            }
+            for (_, number_num, number_text) in self.list_episodes_for_series(series_id).await? {
+                let name = number_text
+                    .or_else(|| number_num.map(crate::mapping::format_chapter_number))
+                    .unwrap_or_else(|| "episode".to_string());
+                names.push(format!("{}.txt", name));
+            }
+            names.sort();
+            names.dedup();
+
+            for name in names {
+                let from = old_dir.join(&name);
+                if !tokio::fs::try_exists(&from).await.unwrap_or(false) {
+                    continue;
+                }
+                let to = new_path.join(&name);
+                if tokio::fs::try_exists(&to).await.unwrap_or(false) {
+                    report
+                        .failed
+                        .push((name, "destination already exists".to_string()));
+                    continue;
+                }
+                match move_path(&from, &to).await {
+                    Ok(()) => {
+                        crate::dao::rebase_download_paths(
+                            &pool,
+                            &old_abs.join(&name).to_string_lossy(),
+                            &new_abs.join(&name).to_string_lossy(),
+                        )
+                        .await?;
+                        report.moved.push(name);
+                    }
+                    Err(e) => report.failed.push((name, e.to_string())),
+                }
+            }
+            // Leave the old folder alone if anything (ours or the user's) is still in it
+            let _ = tokio::fs::remove_dir(&old_dir).await;
+        }
+
+        self.set_series_download_path(series_id, Some(&new_path.to_string_lossy()))
+            .await?;
+        Ok(report)
+    }
+
+    /// Repair chapters stored with only a textual number ("12.5", "Vol.2 Ch.3") by parsing
+    /// `number_num` from it, so numeric ordering works. Returns chapters updated.
+    pub async fn backfill_chapter_numbers(&self, series_id: &str) -> Result<u64> {
+        let db = self.agg.database();
+        crate::dao::backfill_chapter_numbers(
+            db.pool(),
+            series_id,
+            crate::mapping::parse_chapter_number,
+        )
+        .await
+        .map_err(|e| db.map_busy(e))
+    }
+
     pub async fn delete_series(&self, series_id: &str) -> Result<u64> {
         let pool = self.agg.database().pool().clone();
-        crate::dao::delete_series(&pool, series_id).await
+        let deleted = crate::dao::delete_series(&pool, series_id).await?;
+        if deleted > 0 {
+            self.agg.events().publish(LibraryEvent::SeriesDeleted {
+                series_id: series_id.to_string(),
+            });
+        }
+        Ok(deleted)
     }
 
     pub async fn delete_chapter(&self, chapter_id: &str) -> Result<u64> {
@@ -346,6 +1522,14 @@ impl Touring {
         crate::dao::find_series_id_by_source_external(&pool, source_id, external_id).await
     }
 
+    /// Say whether `id` is a canonical series/chapter/episode id or a source's external id
+    /// (with the canonical row it maps to), so callers needn't rely on the silent
+    /// canonical-then-external fallback of the lookup methods. See `IdKind` for the order.
+    pub async fn describe_id(&self, id: &str) -> Result<IdKind> {
+        let pool = self.agg.database().pool().clone();
+        crate::dao::describe_id(&pool, id).await
+    }
+
     /// Get series_id and naming info for a chapter
     pub async fn get_chapter_meta(
         &self,
@@ -390,12 +1574,63 @@ impl Touring {
         self.get_series_download_path(series_id).await
     }
 
+    /// Resolve the directory downloads for a series are written to.
+    /// Order: stored series download_path, then `<download_root>/<sanitized title>`
+    /// (falling back to the series id when the series is unknown or has no usable title).
+    pub async fn resolve_download_dir(&self, series_id: &str) -> Result<PathBuf> {
+        if let Some(p) = self.get_series_download_path(series_id).await? {
+            return Ok(PathBuf::from(p));
+        }
+        let pool = self.agg.database().pool().clone();
+        let title: Option<String> = sqlx::query_scalar("SELECT title FROM series WHERE id = ?")
+            .bind(series_id)
+            .fetch_optional(&pool)
+            .await?;
+        let name = title
+            .map(|t| sanitize_path_component(&t))
+            .filter(|t| !t.is_empty())
+            .unwrap_or_else(|| sanitize_path_component(series_id));
+        Ok(self.download_root().await?.join(name))
+    }
+
     /// Clear cache entries by prefix. Returns number of rows removed.
     pub async fn clear_cache_prefix(&self, prefix: Option<&str>) -> Result<u64> {
-        self.agg
-            .clear_cache_prefix(prefix)
-            .await
-            .map_err(Into::into)
+        self.agg.clear_cache_prefix(prefix).await
+    }
+
+    /// Delete every file in the on-disk image cache (`image_cache_dir`); returns the bytes
+    /// freed. The database cache is left alone (see `clear_cache_prefix`).
+    pub async fn delete_all_cached_images(&self) -> Result<u64> {
+        Ok(remove_cached_files(&self.image_cache_dir).await?)
+    }
+
+    /// Delete the cached images of one series; returns the bytes freed.
+    pub async fn delete_series_cached_images(&self, series_id: &str) -> Result<u64> {
+        Ok(remove_cached_files(&self.series_image_cache_dir(series_id)).await?)
+    }
+
+    /// Forget everything cached for one series (page lists of its chapters, searches for its
+    /// title/external ids) so the next read refetches it. Returns cache rows deleted.
+    pub async fn clear_series_cache(&self, series_id: &str) -> Result<u64> {
+        self.agg.clear_series_cache(series_id).await
+    }
+
+    /// Diagnostic listing of cache keys (with `expires_at`, unix seconds) under a prefix such
+    /// as `"<source>|search|"` or `"all|pages|"`, for tracking down cache misses caused by
+    /// query normalization.
+    pub async fn list_cache_keys(
+        &self,
+        prefix: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<(String, i64)>> {
+        self.agg.list_cache_keys(prefix, limit).await
+    }
+
+    /// Size-bounded eviction: delete cache rows (expired first, then least recently read)
+    /// until the cache holds at most `max_bytes` of keys and payloads. Returns rows deleted.
+    /// Run `vacuum_db` afterwards to actually shrink the database file.
+    pub async fn prune_cache_to_size(&self, max_bytes: u64) -> Result<u64> {
+        self.agg.prune_cache_to_size(max_bytes).await
     }
 
     /// Vacuum/compact the database (SQLite only; no-op on others).
@@ -403,6 +1638,13 @@ impl Touring {
         self.agg.vacuum_db().await
     }
 
+    /// Export the library database to `path` as a standalone SQLite file while the app keeps
+    /// running (`VACUUM INTO`, a consistent snapshot, unlike copying the live file). Errors on
+    /// non-SQLite databases or if `path` already exists.
+    pub async fn backup_to(&self, path: &Path) -> Result<()> {
+        self.agg.backup_to(path).await
+    }
+
     /// Clear all data from the database (WARNING: This deletes all series, chapters, episodes, and sources).
     /// Returns the number of series deleted (chapters/episodes cascade automatically via foreign keys).
     pub async fn clear_database(&self) -> Result<u64> {
@@ -425,6 +1667,85 @@ impl Touring {
 
     // --- Download API for UI ---
 
+    /// Storage-integrity sweep: for every series with a download folder, check that the folder
+    /// exists, that each recorded chapter/episode file is present and still matches its
+    /// recorded size and SHA-256, and that unrecorded chapters have a file at their expected
+    /// path. Progress is reported once per series.
+    pub async fn verify_all_downloads<F>(&self, mut progress: F) -> Result<Vec<DownloadIssue>>
+    where
+        F: FnMut(DownloadProgress),
+    {
+        let pool = self.agg.database().pool().clone();
+        let series = crate::dao::list_series_download_paths(&pool).await?;
+        let total = series.len();
+        let mut issues = Vec::new();
+
+        for (i, (series_id, dir)) in series.into_iter().enumerate() {
+            progress(DownloadProgress {
+                current: i + 1,
+                total,
+                current_item: series_id.clone(),
+            });
+            if !tokio::fs::metadata(&dir)
+                .await
+                .map(|m| m.is_dir())
+                .unwrap_or(false)
+            {
+                issues.push(DownloadIssue {
+                    series_id,
+                    unit_id: None,
+                    path: dir,
+                    problem: DownloadProblem::MissingFolder,
+                });
+                continue;
+            }
+
+            let chapters = self.chapter_output_names(&series_id).await?;
+            let episodes = crate::dao::list_episodes_for_series(&pool, &series_id).await?;
+            let units = chapters
+                .into_iter()
+                .map(|(id, name)| (id, Some(name)))
+                .chain(episodes.into_iter().map(|(id, _, _)| (id, None)));
+            for (unit_id, chapter_name) in units {
+                let records = crate::dao::list_downloads_for_unit(&pool, &unit_id).await?;
+                if records.is_empty() {
+                    // Without a record (e.g. checksums off) the expected path is all there is
+                    let Some(name) = chapter_name else { continue };
+                    let dir = Path::new(&dir);
+                    let cbz = chapter_output_path(dir, &name, true);
+                    let folder = chapter_output_path(dir, &name, false);
+                    if !tokio::fs::try_exists(&cbz).await.unwrap_or(false)
+                        && !tokio::fs::try_exists(&folder).await.unwrap_or(false)
+                    {
+                        issues.push(DownloadIssue {
+                            series_id: series_id.clone(),
+                            unit_id: Some(unit_id),
+                            path: cbz.to_string_lossy().into_owned(),
+                            problem: DownloadProblem::MissingChapter,
+                        });
+                    }
+                    continue;
+                }
+                for rec in records {
+                    let problem = match file_matches(&rec.path, rec.size_bytes, &rec.sha256).await {
+                        Err(_) => Some(DownloadProblem::Missing),
+                        Ok(false) => Some(DownloadProblem::Corrupt),
+                        Ok(true) => None,
+                    };
+                    if let Some(problem) = problem {
+                        issues.push(DownloadIssue {
+                            series_id: series_id.clone(),
+                            unit_id: Some(unit_id.clone()),
+                            path: rec.path,
+                            problem,
+                        });
+                    }
+                }
+            }
+        }
+        Ok(issues)
+    }
+
     /// Download chapter images to a directory. Returns number of images downloaded.
     pub async fn download_chapter_images(
         &self,
@@ -432,149 +1753,754 @@ impl Touring {
         output_dir: &Path,
         force_overwrite: bool,
     ) -> Result<usize> {
+        let written = self
+            .write_chapter_images(chapter_id, output_dir, force_overwrite)
+            .await?;
+        for (_, path) in &written {
+            self.record_download(chapter_id, "chapter", path).await?;
+        }
+        if !written.is_empty() {
+            self.publish_download(chapter_id, "chapter", output_dir);
+        }
+        Ok(written.len())
+    }
+
+    /// Download chapter as CBZ archive. Returns true if downloaded successfully.
+    pub async fn download_chapter_cbz(
+        &self,
+        chapter_id: &str,
+        output_file: &Path,
+        force_overwrite: bool,
+    ) -> Result<bool> {
+        if !force_overwrite && tokio::fs::try_exists(output_file).await.unwrap_or(false) {
+            return Ok(false);
+        }
+
         let urls = self
             .get_chapter_images_with_refresh(chapter_id, false)
             .await?;
         if urls.is_empty() {
-            return Ok(0);
+            return Ok(false);
         }
 
-        tokio::fs::create_dir_all(output_dir).await.ok();
-        let client = reqwest::Client::builder()
+        let tmp_dir = output_file.with_extension("tmpdir");
+        let pages = self
+            .write_chapter_images(chapter_id, &tmp_dir, true)
+            .await?;
+        if pages.is_empty() {
+            return Ok(false);
+        }
+
+        // Create CBZ
+        let file = std::fs::File::create(output_file)?;
+        let mut zip = zip::ZipWriter::new(file);
+        let options =
+            zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+        use std::io::Write;
+
+        if self.download_options().write_comic_info {
+            let xml = self.comic_info(chapter_id, pages.len()).await?.to_xml();
+            zip.start_file("ComicInfo.xml", options)?;
+            zip.write_all(xml.as_bytes())?;
+        }
+
+        // Entry names come from the page index, never from whatever the filesystem reports
+        for (index, path) in &pages {
+            zip.start_file(page_file_name(*index), options)?;
+            let data = std::fs::read(path)?;
+            zip.write_all(&data)?;
+        }
+        zip.finish()?;
+
+        // Cleanup
+        let _ = std::fs::remove_dir_all(&tmp_dir);
+        self.record_download(chapter_id, "chapter", output_file)
+            .await?;
+        self.publish_download(chapter_id, "chapter", output_file);
+        Ok(true)
+    }
+
+    /// Download an episode's HLS stream into one file by concatenating its segments (the
+    /// `.ts`/`.m4s` output is not remuxed). Uses the stored streams (`get_episode_streams`
+    /// first); `variant` is a quality label such as "720p" matched against stored qualities
+    /// and master-playlist resolutions, else the preferred quality or the highest bandwidth.
+    /// AES-128 segments are decrypted. Every host involved must be allowed by a loaded plugin.
+    /// Progress counts segments; returns the number written.
+    pub async fn download_hls_episode<F>(
+        &self,
+        episode_id: &str,
+        variant: Option<&str>,
+        output_file: &Path,
+        mut progress: F,
+    ) -> Result<usize>
+    where
+        F: FnMut(DownloadProgress),
+    {
+        use futures::stream::{self, StreamExt, TryStreamExt};
+
+        self.agg.ensure_online("downloading an HLS episode")?;
+        let pool = self.agg.database().pool().clone();
+        let hls_streams: Vec<_> = crate::dao::list_streams(&pool, episode_id)
+            .await?
+            .into_iter()
+            .filter(|s| crate::hls::is_hls(&s.url, s.mime.as_deref()))
+            .collect();
+        let preferred = match variant {
+            Some(v) => Some(v.to_string()),
+            None => crate::dao::get_setting(&pool, PREFERRED_QUALITY_KEY).await?,
+        };
+        let stream = pick_stream(hls_streams, preferred.as_deref())
+            .ok_or_else(|| anyhow::anyhow!("no stored HLS stream for episode {}", episode_id))?;
+
+        let client = self.download_client()?;
+        let mut allowed_hosts = std::collections::HashSet::new();
+        let mut playlist_url = url::Url::parse(&stream.url)?;
+        let mut text = String::from_utf8(
+            self.fetch_hls_resource(&client, playlist_url.as_str(), &mut allowed_hosts)
+                .await?,
+        )?;
+        let media = loop {
+            match crate::hls::parse_playlist(&text, &playlist_url)? {
+                crate::hls::Playlist::Media(media) => break media,
+                crate::hls::Playlist::Master(variants) => {
+                    let chosen = crate::hls::select_variant(&variants, preferred.as_deref())
+                        .ok_or_else(|| anyhow::anyhow!("master playlist has no variants"))?;
+                    playlist_url = url::Url::parse(&chosen.uri)?;
+                    text = String::from_utf8(
+                        self.fetch_hls_resource(&client, chosen.uri.as_str(), &mut allowed_hosts)
+                            .await?,
+                    )?;
+                }
+            }
+        };
+        if media.segments.is_empty() {
+            return Err(anyhow::anyhow!("HLS playlist has no segments"));
+        }
+
+        // Keys are small and shared by many segments; fetch each once up front
+        let mut keys = std::collections::HashMap::new();
+        for key in media.segments.iter().filter_map(|s| s.key.as_ref()) {
+            if !key.method.eq_ignore_ascii_case("AES-128") {
+                return Err(anyhow::anyhow!(
+                    "unsupported HLS encryption: {}",
+                    key.method
+                ));
+            }
+            if !keys.contains_key(&key.uri) {
+                let bytes = self
+                    .fetch_hls_resource(&client, &key.uri, &mut allowed_hosts)
+                    .await?;
+                keys.insert(key.uri.clone(), bytes);
+            }
+        }
+        let init_bytes = match &media.init_uri {
+            Some(uri) => Some(
+                self.fetch_hls_resource(&client, uri, &mut allowed_hosts)
+                    .await?,
+            ),
+            None => None,
+        };
+        for segment in &media.segments {
+            self.check_download_host(&segment.uri, &mut allowed_hosts)
+                .await?;
+        }
+
+        if let Some(parent) = output_file.parent() {
+            tokio::fs::create_dir_all(parent).await.ok();
+        }
+        let partial = output_file.with_extension("part");
+        let mut file = tokio::fs::File::create(&partial).await?;
+        use tokio::io::AsyncWriteExt;
+        if let Some(init) = &init_bytes {
+            file.write_all(init).await?;
+        }
+
+        let total = media.segments.len();
+        let mut segments = stream::iter(media.segments.iter().map(|segment| {
+            let client = client.clone();
+            async move {
+                self.acquire_download_slot(&segment.uri).await;
+                let resp = client.get(&segment.uri).send().await?;
+                if !resp.status().is_success() {
+                    return Err(anyhow::anyhow!(
+                        "segment {} returned {}",
+                        segment.uri,
+                        resp.status()
+                    ));
+                }
+                Ok::<_, anyhow::Error>((segment, resp.bytes().await?.to_vec()))
+            }
+        }))
+        .buffered(HLS_SEGMENT_CONCURRENCY);
+
+        let mut written = 0;
+        while let Some((segment, data)) = segments.try_next().await? {
+            let data = match &segment.key {
+                Some(key) => {
+                    crate::hls::decrypt_segment(&data, &keys[&key.uri], key.iv, segment.sequence)?
+                }
+                None => data,
+            };
+            file.write_all(&data).await?;
+            written += 1;
+            progress(DownloadProgress {
+                current: written,
+                total,
+                current_item: segment.uri.clone(),
+            });
+        }
+        file.flush().await?;
+        drop(file);
+        tokio::fs::rename(&partial, output_file).await?;
+        self.record_download(episode_id, "episode", output_file)
+            .await?;
+        self.publish_download(episode_id, "episode", output_file);
+        Ok(written)
+    }
+
+    // HTTP client for page/segment downloads, bounded by the download timeouts
+    fn download_client(&self) -> Result<reqwest::Client> {
+        Ok(reqwest::Client::builder()
             .user_agent("touring/0.1")
-            .build()?;
-        let mut downloaded = 0;
+            .timeout(self.download_options().request_timeout)
+            .connect_timeout(self.download_options().connect_timeout)
+            .build()?)
+    }
+
+    // Hosts already confirmed are remembered so each is asked about only once per download
+    async fn check_download_host(
+        &self,
+        url: &str,
+        allowed: &mut std::collections::HashSet<String>,
+    ) -> Result<()> {
+        let host = url::Url::parse(url)?
+            .host_str()
+            .unwrap_or_default()
+            .to_string();
+        if allowed.contains(&host) {
+            return Ok(());
+        }
+        if !self.agg.plugin_manager().url_allowed_by_any(url).await? {
+            return Err(anyhow::anyhow!("host not allowed by any plugin: {}", url));
+        }
+        allowed.insert(host);
+        Ok(())
+    }
+
+    // Wait for the shared per-host slot before a download request (see
+    // `DownloadOptions::rate_limit_downloads`)
+    async fn acquire_download_slot(&self, url: &str) {
+        if !self.download_options().rate_limit_downloads {
+            return;
+        }
+        if let Some(host) = url::Url::parse(url)
+            .ok()
+            .and_then(|u| u.host_str().map(str::to_string))
+        {
+            self.agg
+                .plugin_manager()
+                .host_rate_limiter()
+                .acquire(&host)
+                .await;
+        }
+    }
+
+    async fn fetch_hls_resource(
+        &self,
+        client: &reqwest::Client,
+        url: &str,
+        allowed: &mut std::collections::HashSet<String>,
+    ) -> Result<Vec<u8>> {
+        self.check_download_host(url, allowed).await?;
+        self.acquire_download_slot(url).await;
+        let resp = client.get(url).send().await?;
+        if !resp.status().is_success() {
+            return Err(anyhow::anyhow!("GET {} returned {}", url, resp.status()));
+        }
+        Ok(resp.bytes().await?.to_vec())
+    }
+
+    /// Re-hash the recorded files of a chapter download and compare against the stored checksums.
+    /// Returns false when nothing was recorded, a file is missing, or any size/hash differs.
+    pub async fn verify_download(&self, chapter_id: &str) -> Result<bool> {
+        let pool = self.agg.database().pool().clone();
+        let canonical_id = crate::dao::find_chapter_identity(&pool, chapter_id)
+            .await?
+            .map(|(id, _)| id)
+            .unwrap_or_else(|| chapter_id.to_string());
+        let records = crate::dao::list_downloads_for_unit(&pool, &canonical_id).await?;
+        if records.is_empty() {
+            return Ok(false);
+        }
+        for rec in records {
+            let Ok(data) = tokio::fs::read(&rec.path).await else {
+                return Ok(false);
+            };
+            if data.len() as i64 != rec.size_bytes || sha256_hex(&data) != rec.sha256 {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    // Fetch chapter images into `output_dir`; returns (page index, path) for each file written.
+    async fn write_chapter_images(
+        &self,
+        chapter_id: &str,
+        output_dir: &Path,
+        force_overwrite: bool,
+    ) -> Result<Vec<(usize, PathBuf)>> {
+        self.agg.ensure_online("downloading chapter images")?;
+        let urls = self
+            .get_chapter_images_with_refresh(chapter_id, false)
+            .await?;
+        if urls.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        tokio::fs::create_dir_all(output_dir).await.ok();
+        let client = self.download_client()?;
+        let mut written = Vec::new();
 
         for (i, url) in urls.iter().enumerate() {
-            if url.starts_with("mock://") {
-                let fname = format!("{:04}.jpg", i + 1);
-                let path = output_dir.join(fname);
-                if !force_overwrite && tokio::fs::try_exists(&path).await.unwrap_or(false) {
-                    continue;
-                }
-                tokio::fs::write(&path, b"MOCK").await?;
-                downloaded += 1;
+            // A page URL we can't fetch (e.g. a plugin leaking `mock://`) leaves a gap rather
+            // than a placeholder file that would pass for a real page
+            if !is_http_url(url) {
+                tracing::warn!(chapter=%chapter_id, url, "skipping page with unsupported URL scheme");
                 continue;
             }
 
-            let fname = format!("{:04}.jpg", i + 1);
-            let path = output_dir.join(fname);
+            let path = output_dir.join(page_file_name(i));
             if !force_overwrite && tokio::fs::try_exists(&path).await.unwrap_or(false) {
                 continue;
             }
 
+            self.acquire_download_slot(url).await;
             let resp = client.get(url).send().await?;
             if !resp.status().is_success() {
                 continue;
             }
-            let bytes = resp.bytes().await?;
+            let mut bytes = resp.bytes().await?.to_vec();
+            if self.download_options().convert_webp_to_jpeg && is_webp(&bytes) {
+                let quality = self.download_options().jpeg_quality;
+                let webp = bytes.clone();
+                match tokio::task::spawn_blocking(move || webp_to_jpeg(&webp, quality)).await? {
+                    Ok(jpeg) => bytes = jpeg,
+                    Err(e) => {
+                        tracing::warn!(url, error=%e, "webp conversion failed; keeping original")
+                    }
+                }
+            }
             tokio::fs::write(&path, &bytes).await?;
-            downloaded += 1;
+            written.push((i, path));
         }
-        Ok(downloaded)
+        Ok(written)
     }
 
-    /// Download chapter as CBZ archive. Returns true if downloaded successfully.
-    pub async fn download_chapter_cbz(
+    async fn comic_info(
         &self,
         chapter_id: &str,
-        output_file: &Path,
-        force_overwrite: bool,
-    ) -> Result<bool> {
-        if !force_overwrite && tokio::fs::try_exists(output_file).await.unwrap_or(false) {
-            return Ok(false);
+        page_count: usize,
+    ) -> Result<crate::comicinfo::ComicInfo> {
+        let pool = self.agg.database().pool().clone();
+        let chapter = match crate::dao::find_chapter_identity(&pool, chapter_id).await? {
+            Some((canonical_id, _)) => {
+                crate::dao::get_chapter_with_state(&pool, &canonical_id).await?
+            }
+            None => None,
+        };
+        let Some(chapter) = chapter else {
+            return Ok(crate::comicinfo::ComicInfo {
+                page_count,
+                ..Default::default()
+            });
+        };
+        let series = self
+            .get_series_info(&chapter.series_id)
+            .await?
+            .map(|s| s.title)
+            .unwrap_or_default();
+        Ok(crate::comicinfo::ComicInfo {
+            series,
+            title: chapter.title,
+            number: chapter.number_text.or_else(|| {
+                chapter
+                    .number_num
+                    .map(crate::mapping::format_chapter_number)
+            }),
+            volume: chapter.volume,
+            language: chapter.lang,
+            page_count,
+        })
+    }
+
+    // Hash a freshly written file and record it in `downloads` (when enabled).
+    async fn record_download(&self, unit_id: &str, kind: &str, path: &Path) -> Result<()> {
+        if !self.download_options().record_checksums {
+            return Ok(());
         }
+        let db = self.agg.database();
+        let pool = db.pool().clone();
+        let unit_id = match kind {
+            "chapter" => crate::dao::find_chapter_identity(&pool, unit_id)
+                .await
+                .map_err(|e| db.map_busy(e))?
+                .map(|(id, _)| id)
+                .unwrap_or_else(|| unit_id.to_string()),
+            _ => unit_id.to_string(),
+        };
+        let data = tokio::fs::read(path).await?;
+        let abs = tokio::fs::canonicalize(path)
+            .await
+            .unwrap_or_else(|_| path.to_path_buf());
+        crate::dao::upsert_download(
+            &pool,
+            &crate::dao::DownloadInsert {
+                unit_id,
+                kind: kind.to_string(),
+                path: abs.to_string_lossy().to_string(),
+                size_bytes: data.len() as i64,
+                sha256: sha256_hex(&data),
+                page_count: None,
+            },
+        )
+        .await
+        .map_err(|e| db.map_busy(e))
+    }
 
-        let urls = self
-            .get_chapter_images_with_refresh(chapter_id, false)
-            .await?;
-        if urls.is_empty() {
-            return Ok(false);
+    /// Track a CBZ downloaded outside touring as chapter `chapter_number` of a series: the
+    /// archive is recorded in `downloads` (size, SHA-256, image entry count) so the chapter
+    /// shows as downloaded, and nothing is fetched. Without a matching chapter, a local-only
+    /// one (source "local") is created if `create_missing`, else `None` is returned. Returns
+    /// the canonical chapter id.
+    pub async fn import_cbz(
+        &self,
+        series_id: &str,
+        cbz_path: &Path,
+        chapter_number: f64,
+        create_missing: bool,
+    ) -> Result<Option<String>> {
+        let pool = self.agg.database().pool().clone();
+        if !crate::dao::series_exists(&pool, series_id).await? {
+            return Err(anyhow::anyhow!("series not found: {}", series_id));
         }
+        let data = tokio::fs::read(cbz_path).await?;
+        let page_count =
+            cbz_page_count(&data).with_context(|| format!("reading CBZ {}", cbz_path.display()))?;
 
-        let tmp_dir = output_file.with_extension("tmpdir");
-        let downloaded = self
-            .download_chapter_images(chapter_id, &tmp_dir, true)
-            .await?;
-        if downloaded == 0 {
-            return Ok(false);
+        let chapter_id = match crate::dao::find_chapter_id_by_number(
+            &pool,
+            series_id,
+            chapter_number,
+            None,
+            None,
+        )
+        .await?
+        {
+            Some(id) => id,
+            None if create_missing => {
+                self.agg.upsert_source(LOCAL_SOURCE_ID, "local").await?;
+                let label = crate::mapping::format_chapter_number(chapter_number);
+                let id = uuid::Uuid::new_v4().to_string();
+                crate::dao::upsert_chapter(
+                    &pool,
+                    &crate::dao::ChapterInsert {
+                        id: id.clone(),
+                        series_id: series_id.to_string(),
+                        source_id: LOCAL_SOURCE_ID.to_string(),
+                        external_id: format!("local:{}", label),
+                        number_text: Some(label),
+                        number_num: Some(chapter_number),
+                        title: None,
+                        lang: None,
+                        volume: None,
+                        published_at: None,
+                    },
+                )
+                .await?;
+                id
+            }
+            None => return Ok(None),
+        };
+
+        let abs = tokio::fs::canonicalize(cbz_path).await?;
+        crate::dao::upsert_download(
+            &pool,
+            &crate::dao::DownloadInsert {
+                unit_id: chapter_id.clone(),
+                kind: "chapter".to_string(),
+                path: abs.to_string_lossy().to_string(),
+                size_bytes: data.len() as i64,
+                sha256: sha256_hex(&data),
+                page_count: Some(page_count as i64),
+            },
+        )
+        .await?;
+        self.publish_download(&chapter_id, "chapter", &abs);
+        Ok(Some(chapter_id))
+    }
+
+    fn publish_download(&self, unit_id: &str, kind: &str, path: &Path) {
+        self.agg.events().publish(LibraryEvent::DownloadCompleted {
+            unit_id: unit_id.to_string(),
+            kind: kind.to_string(),
+            path: path.to_path_buf(),
+        });
+    }
+
+    /// Save a series' cover to `dest_file`, e.g. `cover` next to its CBZs for media servers.
+    /// The extension is replaced with one matching the served content type. The cover host
+    /// must be allowed by one of the series' sources. Returns the written path, or `None` if
+    /// the series has no cover or the file exists and `force` is false.
+    pub async fn download_cover(
+        &self,
+        series_id: &str,
+        dest_file: &Path,
+        force: bool,
+    ) -> Result<Option<PathBuf>> {
+        self.agg.ensure_online("downloading a cover")?;
+        let Some(cover_url) = self
+            .get_series_info(series_id)
+            .await?
+            .and_then(|info| info.cover_url)
+        else {
+            return Ok(None);
+        };
+        self.save_series_image(series_id, &cover_url, dest_file, force)
+            .await
+    }
+
+    // Fetch an image for a series to `dest_file` (extension from the content type); the host
+    // must be allowed by one of the series' sources. `None` if the file exists and !force.
+    async fn save_series_image(
+        &self,
+        series_id: &str,
+        cover_url: &str,
+        dest_file: &Path,
+        force: bool,
+    ) -> Result<Option<PathBuf>> {
+        let pool = self.agg.database().pool().clone();
+        let pm = self.agg.plugin_manager();
+        let mut allowed = false;
+        for (source_id, _) in crate::dao::list_series_sources(&pool, series_id).await? {
+            if pm.url_allowed_by_plugin(&source_id, cover_url).await? {
+                allowed = true;
+                break;
+            }
         }
+        if !allowed {
+            return Err(anyhow::anyhow!(
+                "cover host not allowed by the series' sources: {}",
+                cover_url
+            ));
+        }
+        self.acquire_download_slot(cover_url).await;
 
-        // Create CBZ
-        let file = std::fs::File::create(output_file)?;
-        let mut zip = zip::ZipWriter::new(file);
-        let options =
-            zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+        let resp = self.download_client()?.get(cover_url).send().await?;
+        if !resp.status().is_success() {
+            return Err(anyhow::anyhow!(
+                "cover {} returned {}",
+                cover_url,
+                resp.status()
+            ));
+        }
+        let content_type = resp
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let path = dest_file.with_extension(image_extension(content_type.as_deref(), cover_url));
+        if !force && tokio::fs::try_exists(&path).await.unwrap_or(false) {
+            return Ok(None);
+        }
+        let bytes = resp.bytes().await?;
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::write(&path, &bytes).await?;
+        Ok(Some(path))
+    }
 
-        let mut entries: Vec<_> = std::fs::read_dir(&tmp_dir)?
-            .filter_map(|e| e.ok())
-            .collect();
-        entries.sort_by_key(|e| e.file_name());
+    /// Give a series without a `cover_url` one: the first page of its lowest-numbered chapter
+    /// (page list from the cache, fetched if needed) is downloaded into the image cache (see
+    /// `cached_cover`) and recorded as the cover. Network use only happens on this explicit
+    /// call. Returns the cover URL, existing or new; `None` if the series is unknown or has
+    /// no pages.
+    pub async fn ensure_series_cover(&self, series_id: &str) -> Result<Option<String>> {
+        let Some(info) = self.get_series_info(series_id).await? else {
+            return Ok(None);
+        };
+        if let Some(cover_url) = info.cover_url.filter(|u| !u.trim().is_empty()) {
+            return Ok(Some(cover_url));
+        }
+        let pool = self.agg.database().pool().clone();
+        let Some((chapter_id, _, _)) = crate::dao::list_chapters_for_series(&pool, series_id)
+            .await?
+            .into_iter()
+            .next()
+        else {
+            return Ok(None);
+        };
+        let Some(first_page) = self
+            .get_chapter_images(&chapter_id)
+            .await?
+            .into_iter()
+            .next()
+        else {
+            return Ok(None);
+        };
+        self.agg.ensure_online("caching a cover")?;
+        let dest = self.series_image_cache_dir(series_id).join("cover");
+        self.save_series_image(series_id, &first_page, &dest, true)
+            .await?;
+        self.update_series_metadata(
+            series_id,
+            SeriesMetadataUpdate {
+                title: None,
+                description: None,
+                cover_url: Some(Some(first_page.clone())),
+                status: None,
+            },
+        )
+        .await?;
+        Ok(Some(first_page))
+    }
+
+    /// Directory of the on-disk image cache (`TOURING_IMAGE_CACHE_DIR`, default
+    /// `<user cache dir>/images`).
+    pub fn image_cache_dir(&self) -> &Path {
+        &self.image_cache_dir
+    }
 
-        for entry in entries {
+    /// Cover cached by `ensure_series_cover`, if any.
+    pub async fn cached_cover(&self, series_id: &str) -> Result<Option<PathBuf>> {
+        let dir = self.series_image_cache_dir(series_id);
+        let mut entries = match tokio::fs::read_dir(&dir).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        while let Some(entry) = entries.next_entry().await? {
             let path = entry.path();
-            if path.is_file() {
-                let name = path.file_name().unwrap().to_string_lossy().to_string();
-                zip.start_file(name, options)?;
-                let data = std::fs::read(&path)?;
-                use std::io::Write;
-                zip.write_all(&data)?;
+            if path.file_stem().is_some_and(|stem| stem == "cover") {
+                return Ok(Some(path));
             }
         }
-        zip.finish()?;
+        Ok(None)
+    }
 
-        // Cleanup
-        let _ = std::fs::remove_dir_all(&tmp_dir);
-        Ok(true)
+    fn series_image_cache_dir(&self, series_id: &str) -> PathBuf {
+        self.image_cache_dir
+            .join(sanitize_path_component(series_id))
     }
 
-    /// Download all chapters for a series to a base directory. Returns (chapters_processed, chapters_downloaded).
+    /// Approximate download size of a chapter from `HEAD` `Content-Length`s of its pages
+    /// (hosts must be allowed by a loaded plugin). Pages without a length are extrapolated from
+    /// the others; `None` if no page reports one.
+    pub async fn estimate_chapter_size(&self, chapter_id: &str) -> Result<Option<u64>> {
+        use futures::stream::{self, StreamExt};
+
+        self.agg.ensure_online("estimating download size")?;
+        let urls = self
+            .get_chapter_images_with_refresh(chapter_id, false)
+            .await?;
+        let mut allowed = std::collections::HashSet::new();
+        for url in &urls {
+            self.check_download_host(url, &mut allowed).await?;
+        }
+        let client = self.download_client()?;
+        let lengths: Vec<Option<u64>> = stream::iter(urls.iter().map(|url| {
+            let client = client.clone();
+            async move {
+                self.acquire_download_slot(url).await;
+                let resp = client.head(url).send().await.ok()?;
+                if !resp.status().is_success() {
+                    return None;
+                }
+                resp.headers()
+                    .get(reqwest::header::CONTENT_LENGTH)?
+                    .to_str()
+                    .ok()?
+                    .parse()
+                    .ok()
+            }
+        }))
+        .buffer_unordered(SIZE_PROBE_CONCURRENCY)
+        .collect()
+        .await;
+        Ok(extrapolate_total(&lengths, lengths.len()))
+    }
+
+    /// Approximate download size of a whole series. Up to 10 evenly spaced chapters are
+    /// probed with `estimate_chapter_size` and the average is scaled to the chapter count;
+    /// `None` if no sampled chapter could be sized.
+    pub async fn estimate_series_size(&self, series_id: &str) -> Result<Option<u64>> {
+        let pool = self.agg.database().pool().clone();
+        let chapters = crate::dao::list_chapters_for_series(&pool, series_id).await?;
+        let step = chapters.len().div_ceil(SERIES_SIZE_SAMPLE).max(1);
+        let mut sizes = Vec::new();
+        for (chapter_id, _, _) in chapters.iter().step_by(step) {
+            match self.estimate_chapter_size(chapter_id).await {
+                Ok(size) => sizes.push(size),
+                Err(e) => {
+                    tracing::warn!(chapter_id, error=%e, "size estimate failed");
+                    sizes.push(None);
+                }
+            }
+        }
+        Ok(extrapolate_total(&sizes, chapters.len()))
+    }
+
+    /// Download all chapters for a series to a base directory (defaults to `resolve_download_dir`),
+    /// `DownloadOptions::chapter_concurrency` at a time. Stops at the first failed chapter.
+    /// Returns (chapters_processed, chapters_downloaded).
     pub async fn download_series_chapters(
         &self,
         series_id: &str,
-        base_dir: &Path,
+        base_dir: Option<&Path>,
         as_cbz: bool,
         force_overwrite: bool,
     ) -> Result<(usize, usize)> {
-        let chapters = self.list_chapters_for_series(series_id).await?;
+        use futures::stream::{self, StreamExt};
+
+        let base_dir = match base_dir {
+            Some(d) => d.to_path_buf(),
+            None => self.resolve_download_dir(series_id).await?,
+        };
+        let chapters = self.chapter_output_names(series_id).await?;
         let mut processed = 0;
         let mut downloaded = 0;
 
-        tokio::fs::create_dir_all(base_dir).await.ok();
+        tokio::fs::create_dir_all(&base_dir).await.ok();
 
-        for (chapter_id, number_num, number_text) in chapters {
-            processed += 1;
-            let name = number_text
-                .or_else(|| number_num.map(|n| format!("{:.3}", n)))
-                .unwrap_or_else(|| format!("chapter_{}", processed));
-
-            if as_cbz {
-                let output_file = base_dir.join(format!("{}.cbz", name));
-                if self
-                    .download_chapter_cbz(&chapter_id, &output_file, force_overwrite)
-                    .await?
-                {
-                    downloaded += 1;
-                }
-            } else {
-                let output_dir = base_dir.join(name);
-                let count = self
-                    .download_chapter_images(&chapter_id, &output_dir, force_overwrite)
-                    .await?;
-                if count > 0 {
-                    downloaded += 1;
+        let mut results = stream::iter(chapters)
+            .map(|(chapter_id, name)| {
+                let output = chapter_output_path(&base_dir, &name, as_cbz);
+                async move {
+                    self.download_series_chapter(&chapter_id, &output, as_cbz, force_overwrite)
+                        .await
                 }
+            })
+            .buffer_unordered(self.download_options().chapter_concurrency.max(1));
+        while let Some(result) = results.next().await {
+            processed += 1;
+            if result? {
+                downloaded += 1;
             }
         }
 
         Ok((processed, downloaded))
     }
 
-    /// Download series with progress callback. Callback receives (current, total, item_name).
+    /// Download series with progress callback, `DownloadOptions::chapter_concurrency` chapters
+    /// at a time. The callback runs as each chapter finishes: `current` counts finished
+    /// chapters (1..=total) and `current_item` names the one that just finished. Failed
+    /// chapters are counted as processed, not downloaded. `base_dir` defaults to
+    /// `resolve_download_dir`.
     pub async fn download_series_chapters_with_progress<F>(
         &self,
         series_id: &str,
-        base_dir: &Path,
+        base_dir: Option<&Path>,
         as_cbz: bool,
         force_overwrite: bool,
         mut progress_callback: F,
@@ -582,42 +2508,41 @@ impl Touring {
     where
         F: FnMut(DownloadProgress),
     {
-        let chapters = self.list_chapters_for_series(series_id).await?;
+        use futures::stream::{self, StreamExt};
+
+        let base_dir = match base_dir {
+            Some(d) => d.to_path_buf(),
+            None => self.resolve_download_dir(series_id).await?,
+        };
+        let chapters = self.chapter_output_names(series_id).await?;
         let total = chapters.len();
         let mut processed = 0;
         let mut downloaded = 0;
 
-        tokio::fs::create_dir_all(base_dir).await.ok();
-
-        for (chapter_id, number_num, number_text) in chapters {
+        tokio::fs::create_dir_all(&base_dir).await.ok();
+
+        let mut results = stream::iter(chapters)
+            .map(|(chapter_id, name)| {
+                let output = chapter_output_path(&base_dir, &name, as_cbz);
+                async move {
+                    let success = self
+                        .download_series_chapter(&chapter_id, &output, as_cbz, force_overwrite)
+                        .await
+                        .unwrap_or(false);
+                    (name, success)
+                }
+            })
+            .buffer_unordered(self.download_options().chapter_concurrency.max(1));
+        while let Some((name, success)) = results.next().await {
             processed += 1;
-            let name = number_text
-                .or_else(|| number_num.map(|n| format!("{:.3}", n)))
-                .unwrap_or_else(|| format!("chapter_{}", processed));
-
+            if success {
+                downloaded += 1;
+            }
             progress_callback(DownloadProgress {
                 current: processed,
                 total,
-                current_item: name.clone(),
+                current_item: name,
             });
-
-            let success = if as_cbz {
-                let output_file = base_dir.join(format!("{}.cbz", name));
-                self.download_chapter_cbz(&chapter_id, &output_file, force_overwrite)
-                    .await
-                    .unwrap_or(false)
-            } else {
-                let output_dir = base_dir.join(name);
-                let count = self
-                    .download_chapter_images(&chapter_id, &output_dir, force_overwrite)
-                    .await
-                    .unwrap_or(0);
-                count > 0
-            };
-
-            if success {
-                downloaded += 1;
-            }
         }
 
         Ok(DownloadResult {
@@ -628,41 +2553,126 @@ impl Touring {
         })
     }
 
+    // One chapter of a series download; true if anything was written
+    async fn download_series_chapter(
+        &self,
+        chapter_id: &str,
+        output: &Path,
+        as_cbz: bool,
+        force_overwrite: bool,
+    ) -> Result<bool> {
+        if as_cbz {
+            self.download_chapter_cbz(chapter_id, output, force_overwrite)
+                .await
+        } else {
+            Ok(self
+                .download_chapter_images(chapter_id, output, force_overwrite)
+                .await?
+                > 0)
+        }
+    }
+
     /// Get download status for a series (how many chapters are already downloaded).
+    /// `base_dir` defaults to `resolve_download_dir`.
     pub async fn get_series_download_status(
         &self,
         series_id: &str,
-        base_dir: &Path,
+        base_dir: Option<&Path>,
         as_cbz: bool,
     ) -> Result<(usize, usize)> {
-        let chapters = self.list_chapters_for_series(series_id).await?;
+        let base_dir = match base_dir {
+            Some(d) => d.to_path_buf(),
+            None => self.resolve_download_dir(series_id).await?,
+        };
+        let chapters = self.chapter_output_names(series_id).await?;
         let total = chapters.len();
         let mut downloaded = 0;
 
-        for (_, number_num, number_text) in chapters
-            .iter()
-            .enumerate()
-            .map(|(_i, (id, num, text))| (id, num, text))
-        {
-            let name = number_text
-                .clone()
-                .or_else(|| number_num.map(|n| format!("{:.3}", n)))
-                .unwrap_or_else(|| format!("chapter_{}", downloaded + 1));
-
-            let exists = if as_cbz {
-                let output_file = base_dir.join(format!("{}.cbz", name));
-                tokio::fs::try_exists(&output_file).await.unwrap_or(false)
-            } else {
-                let output_dir = base_dir.join(name);
-                tokio::fs::try_exists(&output_dir).await.unwrap_or(false)
-            };
+        for (_, name) in chapters {
+            let output = chapter_output_path(&base_dir, &name, as_cbz);
+            if tokio::fs::try_exists(&output).await.unwrap_or(false) {
+                downloaded += 1;
+            }
+        }
+
+        Ok((downloaded, total))
+    }
+
+    /// Where `download_series_chapters` would write each chapter (CBZ file or image folder),
+    /// as (chapter_id, path) in download order. Nothing is created. Two chapters sharing a
+    /// path would overwrite each other, so UIs can flag duplicates before downloading.
+    pub async fn get_download_path_tree(
+        &self,
+        series_id: &str,
+        as_cbz: bool,
+    ) -> Result<Vec<(String, PathBuf)>> {
+        let base_dir = self.resolve_download_dir(series_id).await?;
+        Ok(self
+            .chapter_output_names(series_id)
+            .await?
+            .into_iter()
+            .map(|(chapter_id, name)| {
+                let path = chapter_output_path(&base_dir, &name, as_cbz);
+                (chapter_id, path)
+            })
+            .collect())
+    }
 
-            if exists {
-                downloaded += 1;
-            }
-        }
+    /// Series metadata, sources, chapters and the folder/CBZ names a series download would
+    /// use, without fetching anything. `None` if the series is unknown.
+    pub async fn export_series_manifest(&self, series_id: &str) -> Result<Option<SeriesManifest>> {
+        let Some(series) = self.get_series_info(series_id).await? else {
+            return Ok(None);
+        };
+        let sources = self.get_series_sources(series_id).await?;
+        let base_dir = self.resolve_download_dir(series_id).await?;
+        let pool = self.agg.database().pool().clone();
+        let (rows, _) =
+            crate::dao::list_chapters_with_state(&pool, series_id, None, false, i64::MAX, 0)
+                .await?;
+        let mut existing = std::collections::HashSet::new();
+        let chapters = rows
+            .into_iter()
+            .map(|row| {
+                let downloaded = row.downloaded;
+                let chapter = chapter_info(row);
+                let folder = chapter_output_name(&chapter, &mut existing);
+                ManifestChapter {
+                    id: chapter.id,
+                    external_id: chapter.external_id,
+                    number_text: chapter.number_text,
+                    number_num: chapter.number_num,
+                    title: chapter.title,
+                    lang: chapter.lang,
+                    volume: chapter.volume,
+                    folder,
+                    downloaded,
+                }
+            })
+            .collect();
+        Ok(Some(SeriesManifest {
+            series,
+            sources,
+            base_dir,
+            chapters,
+        }))
+    }
 
-        Ok((downloaded, total))
+    // (chapter_id, output stem) for every chapter in download order, collisions disambiguated
+    async fn chapter_output_names(&self, series_id: &str) -> Result<Vec<(String, String)>> {
+        let pool = self.agg.database().pool().clone();
+        let (rows, _) =
+            crate::dao::list_chapters_with_state(&pool, series_id, None, false, i64::MAX, 0)
+                .await?;
+        let mut existing = std::collections::HashSet::new();
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let chapter = chapter_info(row);
+                let name = chapter_output_name(&chapter, &mut existing);
+                (chapter.id, name)
+            })
+            .collect())
     }
 
     // --- Series Management API for UI ---
@@ -727,6 +2737,32 @@ impl Touring {
         }))
     }
 
+    /// `get_series_info` for many series at once (one query per 300 ids instead of four per
+    /// series), in the order of `ids`; unknown ids are skipped.
+    pub async fn get_series_info_batch(&self, ids: &[String]) -> Result<Vec<SeriesInfo>> {
+        let pool = self.agg.database().pool().clone();
+        let mut by_id: std::collections::HashMap<String, SeriesInfo> =
+            crate::dao::series_summaries(&pool, ids)
+                .await?
+                .into_iter()
+                .map(|row| {
+                    let info = SeriesInfo {
+                        id: row.id.clone(),
+                        kind: row.kind,
+                        title: row.title,
+                        description: row.description,
+                        cover_url: row.cover_url,
+                        status: row.status,
+                        download_path: row.download_path,
+                        chapters_count: row.chapters as usize,
+                        episodes_count: row.episodes as usize,
+                    };
+                    (row.id, info)
+                })
+                .collect();
+        Ok(ids.iter().filter_map(|id| by_id.remove(id)).collect())
+    }
+
     /// Update series metadata (title, description, status, etc.).
     pub async fn update_series_metadata(
         &self,
@@ -766,6 +2802,9 @@ impl Touring {
             }
             q = q.bind(series_id);
             q.execute(&pool).await?;
+            self.agg.events().publish(LibraryEvent::MetadataRefreshed {
+                series_id: series_id.to_string(),
+            });
         }
 
         Ok(())
@@ -789,6 +2828,90 @@ impl Touring {
             .collect())
     }
 
+    /// Languages each source offers for a series, from its stored chapters, for a
+    /// source + language picker. Sources without any chapter are absent; chapters without a
+    /// language contribute nothing to their source's list.
+    pub async fn get_series_languages_by_source(
+        &self,
+        series_id: &str,
+    ) -> Result<Vec<(String, Vec<String>)>> {
+        let pool = self.agg.database().pool().clone();
+        crate::dao::series_languages_by_source(&pool, series_id).await
+    }
+
+    /// Sources to try first, in order, when fetching page lists for this series' chapters;
+    /// other plugins follow in the global (name) order. An empty list restores the global
+    /// order. Source ids needn't be loaded; unknown ones are skipped at fetch time.
+    pub async fn set_series_source_priority(
+        &self,
+        series_id: &str,
+        source_ids: Vec<String>,
+    ) -> Result<()> {
+        let pool = self.agg.database().pool().clone();
+        if !source_ids.is_empty() && !crate::dao::series_exists(&pool, series_id).await? {
+            return Err(anyhow::anyhow!("series not found: {}", series_id));
+        }
+        crate::dao::set_series_source_priority(&pool, series_id, &source_ids).await
+    }
+
+    pub async fn get_series_source_priority(&self, series_id: &str) -> Result<Vec<String>> {
+        let pool = self.agg.database().pool().clone();
+        crate::dao::get_series_source_priority(&pool, series_id).await
+    }
+
+    /// Every loaded plugin (by name) with whether it is already linked to the series in
+    /// `series_sources`, for an "add another source" picker. Plugins are not queried.
+    pub async fn available_sources_for_series(
+        &self,
+        series_id: &str,
+    ) -> Result<Vec<(String, bool)>> {
+        let pool = self.agg.database().pool().clone();
+        let mapped: std::collections::HashSet<String> =
+            crate::dao::list_series_sources(&pool, series_id)
+                .await?
+                .into_iter()
+                .map(|(source_id, _)| source_id)
+                .collect();
+        Ok(self
+            .list_plugins()
+            .into_iter()
+            .map(|name| {
+                let linked = mapped.contains(&name);
+                (name, linked)
+            })
+            .collect())
+    }
+
+    /// All sources recorded in the database plus any loaded plugin not yet recorded,
+    /// each flagged with whether its plugin is currently loaded.
+    pub async fn list_all_sources(&self) -> Result<Vec<SourceInfo>> {
+        let pool = self.agg.database().pool().clone();
+        let loaded = self.list_plugins();
+        let mut out: Vec<SourceInfo> = crate::dao::list_sources(&pool)
+            .await?
+            .into_iter()
+            .map(|(id, version, created_at, updated_at)| SourceInfo {
+                loaded: loaded.contains(&id),
+                id,
+                version: Some(version),
+                created_at: Some(created_at),
+                updated_at: Some(updated_at),
+            })
+            .collect();
+        for name in loaded {
+            if !out.iter().any(|s| s.id == name) {
+                out.push(SourceInfo {
+                    id: name,
+                    version: None,
+                    created_at: None,
+                    updated_at: None,
+                    loaded: true,
+                });
+            }
+        }
+        Ok(out)
+    }
+
     /// Add a new source mapping for a series.
     pub async fn add_series_source(
         &self,
@@ -824,10 +2947,33 @@ impl Touring {
         Ok(res.rows_affected())
     }
 
+    /// Re-map a series from `old_external` to `new_external` on one source atomically.
+    /// Chapters and episodes reference the series rather than the mapping, so they stay
+    /// attached; the next chapter fetch merges in the new media's units. Returns false if the
+    /// old mapping doesn't exist.
+    pub async fn replace_series_source(
+        &self,
+        series_id: &str,
+        source_id: &str,
+        old_external: &str,
+        new_external: &str,
+    ) -> Result<bool> {
+        let db = self.agg.database();
+        crate::dao::replace_series_source(
+            db.pool(),
+            series_id,
+            source_id,
+            old_external,
+            new_external,
+        )
+        .await
+        .map_err(|e| db.map_busy(e))
+    }
+
     /// Get detailed chapter information including download status.
     pub async fn get_chapter_info(&self, chapter_id: &str) -> Result<Option<ChapterInfo>> {
         let pool = self.agg.database().pool().clone();
-        let row: Option<(String, String, String, Option<String>, Option<f64>, Option<String>, Option<String>, Option<String>)> = sqlx::query_as(
+        let row: Option<UnitRow> = sqlx::query_as(
             "SELECT id, series_id, external_id, number_text, number_num, title, lang, volume FROM chapters WHERE id = ?"
         )
         .bind(chapter_id)
@@ -860,10 +3006,72 @@ impl Touring {
         }))
     }
 
+    /// `get_chapter_info` for the chapter a given source knows as `external_id`. Unlike the
+    /// global external-id fallback elsewhere, ids that collide across sources can't mismatch.
+    pub async fn get_chapter_by_external(
+        &self,
+        source: &str,
+        external_id: &str,
+    ) -> Result<Option<ChapterInfo>> {
+        let pool = self.agg.database().pool().clone();
+        match crate::dao::find_chapter_id_by_source_external(&pool, source, external_id).await? {
+            Some(chapter_id) => self.get_chapter_info(&chapter_id).await,
+            None => Ok(None),
+        }
+    }
+
+    /// One page (0-based) of a series' chapters with progress and download state, for chapter
+    /// list screens. `filter_lang` restricts to one language. Image info reflects stored image
+    /// rows / cached page lists only; nothing is fetched from plugins.
+    pub async fn get_series_chapters_paginated_with_read_state(
+        &self,
+        series_id: &str,
+        page: usize,
+        page_size: usize,
+        filter_lang: Option<&str>,
+        sort: ChapterSort,
+    ) -> Result<ChapterPage> {
+        let pool = self.agg.database().pool().clone();
+        let page_size = page_size.max(1);
+        let (rows, total) = crate::dao::list_chapters_with_state(
+            &pool,
+            series_id,
+            filter_lang,
+            matches!(sort, ChapterSort::NumberDesc),
+            page_size as i64,
+            (page * page_size) as i64,
+        )
+        .await?;
+
+        let items = rows
+            .into_iter()
+            .map(|row| ChapterListItem {
+                progress: row.progress.map(|(page_index, total_pages, updated_at)| {
+                    ChapterProgress {
+                        chapter_id: row.id.clone(),
+                        series_id: row.series_id.clone(),
+                        page_index,
+                        total_pages,
+                        updated_at,
+                    }
+                }),
+                downloaded: row.downloaded,
+                chapter: chapter_info(row),
+            })
+            .collect();
+
+        Ok(ChapterPage {
+            items,
+            total: total as usize,
+            page,
+            page_size,
+        })
+    }
+
     /// Get detailed episode information.
     pub async fn get_episode_info(&self, episode_id: &str) -> Result<Option<EpisodeInfo>> {
         let pool = self.agg.database().pool().clone();
-        let row: Option<(String, String, String, Option<String>, Option<f64>, Option<String>, Option<String>, Option<String>)> = sqlx::query_as(
+        let row: Option<UnitRow> = sqlx::query_as(
             "SELECT id, series_id, external_id, number_text, number_num, title, lang, season FROM episodes WHERE id = ?"
         )
         .bind(episode_id)
@@ -896,6 +3104,58 @@ impl Touring {
         }))
     }
 
+    /// Fetch episodes for a followed anime and return only the ones not seen before.
+    /// New episodes are persisted; progress and metadata on existing rows are preserved.
+    pub async fn check_for_new_episodes(&self, series_id: &str) -> Result<Vec<EpisodeInfo>> {
+        let mut out = Vec::new();
+        for id in self.agg.check_for_new_episodes(series_id).await? {
+            if let Some(info) = self.get_episode_info(&id).await? {
+                out.push(info);
+            }
+        }
+        Ok(out)
+    }
+
+    /// Search stored chapters by title or number label (e.g. to find a named one-shot),
+    /// optionally within one series. Uses stored data only; nothing is fetched.
+    pub async fn search_local_chapters(
+        &self,
+        query: &str,
+        series_id: Option<&str>,
+        limit: Option<usize>,
+    ) -> Result<Vec<ChapterInfo>> {
+        let pool = self.agg.database().pool().clone();
+        let rows = crate::dao::search_chapters(&pool, query, series_id, limit.unwrap_or(50) as i64)
+            .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| ChapterInfo {
+                has_images: row.image_count > 0 || row.has_cached_pages,
+                image_count: row.image_count as usize,
+                id: row.id,
+                series_id: row.series_id,
+                external_id: row.external_id,
+                number_text: row.number_text,
+                number_num: row.number_num,
+                title: row.title,
+                lang: row.lang,
+                volume: row.volume,
+            })
+            .collect())
+    }
+
+    /// Titles for a typeahead dropdown: local series whose title starts with `prefix`, recently
+    /// read and larger series first. Only titles are read, so it is cheap enough per keystroke;
+    /// an empty prefix returns nothing.
+    pub async fn search_suggestions(&self, prefix: &str, limit: usize) -> Result<Vec<String>> {
+        let prefix = prefix.trim_start();
+        if prefix.is_empty() || limit == 0 {
+            return Ok(Vec::new());
+        }
+        let pool = self.agg.database().pool().clone();
+        crate::dao::series_title_suggestions(&pool, prefix, limit).await
+    }
+
     /// Search series in local database (for UI autocomplete/filtering).
     pub async fn search_local_series(
         &self,
@@ -908,8 +3168,8 @@ impl Touring {
         let limit_val = limit.unwrap_or(50) as i64;
 
         let rows = if let Some(k) = kind {
-            sqlx::query_as::<_, (String, String, String, Option<String>, Option<String>, Option<String>)>(
-                "SELECT id, kind, title, description, cover_url, status FROM series WHERE title LIKE ? AND kind = ? ORDER BY title LIMIT ?"
+            sqlx::query_as::<_, (String, String, String, String, String, String)>(
+                "SELECT id, kind, title, COALESCE(description, ''), COALESCE(cover_url, ''), COALESCE(status, '') FROM series WHERE title LIKE ? AND kind = ? ORDER BY title LIMIT ?"
             )
             .bind(&search_term)
             .bind(k)
@@ -917,8 +3177,8 @@ impl Touring {
             .fetch_all(&pool)
             .await?
         } else {
-            sqlx::query_as::<_, (String, String, String, Option<String>, Option<String>, Option<String>)>(
-                "SELECT id, kind, title, description, cover_url, status FROM series WHERE title LIKE ? ORDER BY title LIMIT ?"
+            sqlx::query_as::<_, (String, String, String, String, String, String)>(
+                "SELECT id, kind, title, COALESCE(description, ''), COALESCE(cover_url, ''), COALESCE(status, '') FROM series WHERE title LIKE ? ORDER BY title LIMIT ?"
             )
             .bind(&search_term)
             .bind(limit_val)
@@ -948,9 +3208,9 @@ impl Touring {
                 id,
                 kind,
                 title,
-                description,
-                cover_url,
-                status,
+                description: (!description.is_empty()).then_some(description),
+                cover_url: (!cover_url.is_empty()).then_some(cover_url),
+                status: (!status.is_empty()).then_some(status),
                 download_path,
                 chapters_count: chapters_count as usize,
                 episodes_count: episodes_count as usize,
@@ -960,6 +3220,61 @@ impl Touring {
         Ok(result)
     }
 
+    /// Resolve a human title (e.g. from a deep link) to a series: the closest local match of
+    /// `kind` if one is close enough, otherwise the best result of a live search, which is
+    /// persisted. `None` when neither yields a plausible match.
+    pub async fn fuzzy_resolve_series(
+        &self,
+        title: &str,
+        kind: MediaType,
+    ) -> Result<Option<SeriesInfo>> {
+        let kind_str = crate::mapping::series_kind(&kind);
+        let wanted = normalize_title(title);
+        if wanted.is_empty() {
+            return Ok(None);
+        }
+
+        let local = self
+            .search_local_series(title.trim(), Some(&kind_str), Some(50))
+            .await?
+            .into_iter()
+            .map(|info| {
+                (
+                    title_similarity(&wanted, &normalize_title(&info.title)),
+                    info,
+                )
+            })
+            .max_by(|a, b| a.0.total_cmp(&b.0));
+        if let Some((score, info)) = local {
+            if score >= LOCAL_TITLE_MATCH {
+                return Ok(Some(info));
+            }
+        }
+
+        let best = self
+            .agg
+            .search_kind(kind, title, false, false)
+            .await?
+            .into_iter()
+            .map(|(source, media)| {
+                let score = title_similarity(&wanted, &normalize_title(&media.title));
+                (score, source, media)
+            })
+            .max_by(|a, b| a.0.total_cmp(&b.0));
+        let Some((score, source, media)) = best else {
+            return Ok(None);
+        };
+        if score < LIVE_TITLE_MATCH {
+            return Ok(None);
+        }
+        self.agg.upsert_source(&source, "unknown").await?;
+        let series_id = self
+            .agg
+            .get_or_create_series_id(&source, &media.id, &media)
+            .await?;
+        self.get_series_info(&series_id).await
+    }
+
     /// Get statistics about the library (total series, chapters, episodes, etc.).
     pub async fn get_library_stats(&self) -> Result<LibraryStats> {
         let pool = self.agg.database().pool().clone();
@@ -1007,6 +3322,25 @@ impl Touring {
         })
     }
 
+    /// Counts for a series detail header in one call; `None` if the series doesn't exist.
+    pub async fn get_series_statistics(&self, series_id: &str) -> Result<Option<SeriesStatistics>> {
+        let pool = self.agg.database().pool().clone();
+        if !crate::dao::series_exists(&pool, series_id).await? {
+            return Ok(None);
+        }
+        let row = crate::dao::series_statistics(&pool, series_id).await?;
+        Ok(Some(SeriesStatistics {
+            chapters_count: row.chapters as usize,
+            episodes_count: row.episodes as usize,
+            read_count: row.read_chapters as usize,
+            unread_count: (row.chapters - row.read_chapters).max(0) as usize,
+            downloaded_count: row.downloaded_units as usize,
+            total_pages: row.total_pages as usize,
+            languages: row.languages,
+            sources_count: row.sources as usize,
+        }))
+    }
+
     /// Refresh metadata for a series from all its sources.
     pub async fn refresh_series_metadata(&self, series_id: &str) -> Result<bool> {
         let sources = self.get_series_sources(series_id).await?;
@@ -1050,6 +3384,242 @@ impl Touring {
     }
 }
 
+// Generated WIT types don't derive PartialEq
+fn same_media_type(a: &MediaType, b: &MediaType) -> bool {
+    match (a, b) {
+        (MediaType::Manga, MediaType::Manga) | (MediaType::Anime, MediaType::Anime) => true,
+        (MediaType::Other(x), MediaType::Other(y)) => x.eq_ignore_ascii_case(y),
+        _ => false,
+    }
+}
+
+fn same_unit_kind(a: &UnitKind, b: &UnitKind) -> bool {
+    match (a, b) {
+        (UnitKind::Other(x), UnitKind::Other(y)) => x.eq_ignore_ascii_case(y),
+        _ => std::mem::discriminant(a) == std::mem::discriminant(b),
+    }
+}
+
+fn same_asset_kind(a: &AssetKind, b: &AssetKind) -> bool {
+    match (a, b) {
+        (AssetKind::Other(x), AssetKind::Other(y)) => x.eq_ignore_ascii_case(y),
+        _ => std::mem::discriminant(a) == std::mem::discriminant(b),
+    }
+}
+
+// Lowercase alphanumerics with single spaces, so punctuation/spacing differences don't matter
+fn normalize_title(title: &str) -> String {
+    title
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() {
+                c.to_lowercase().next().unwrap_or(c)
+            } else {
+                ' '
+            }
+        })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// Dice coefficient over character bigrams of normalized titles
+fn title_similarity(a: &str, b: &str) -> f64 {
+    if a == b {
+        return 1.0;
+    }
+    let bigrams = |s: &str| {
+        let chars: Vec<char> = s.chars().collect();
+        chars.windows(2).map(|w| (w[0], w[1])).collect::<Vec<_>>()
+    };
+    let (x, mut y) = (bigrams(a), bigrams(b));
+    if x.is_empty() || y.is_empty() {
+        return 0.0;
+    }
+    let total = x.len() + y.len();
+    let mut shared = 0;
+    for bigram in &x {
+        if let Some(i) = y.iter().position(|other| other == bigram) {
+            y.swap_remove(i);
+            shared += 1;
+        }
+    }
+    2.0 * shared as f64 / total as f64
+}
+
+// "720p" / "720" -> 720
+fn quality_rank(q: &str) -> Option<u32> {
+    let q = q.trim();
+    q.strip_suffix(['p', 'P']).unwrap_or(q).parse().ok()
+}
+
+fn pick_stream(
+    streams: Vec<crate::dao::StreamInsert>,
+    preferred: Option<&str>,
+) -> Option<crate::dao::StreamInsert> {
+    if let Some(pref) = preferred {
+        let pref_rank = quality_rank(pref);
+        let hit = streams.iter().position(|s| {
+            s.quality.as_deref().is_some_and(|q| {
+                q.eq_ignore_ascii_case(pref.trim())
+                    || (pref_rank.is_some() && quality_rank(q) == pref_rank)
+            })
+        });
+        if let Some(i) = hit {
+            return streams.into_iter().nth(i);
+        }
+    }
+    let best = streams
+        .iter()
+        .enumerate()
+        .filter_map(|(i, s)| s.quality.as_deref().and_then(quality_rank).map(|r| (r, i)))
+        .max_by_key(|(r, i)| (*r, std::cmp::Reverse(*i)))
+        .map(|(_, i)| i)
+        .unwrap_or(0);
+    streams.into_iter().nth(best)
+}
+
+// File extension for an image: from the MIME type, else the URL path, else "jpg".
+fn image_extension(content_type: Option<&str>, url: &str) -> String {
+    let mime = content_type
+        .map(|ct| {
+            ct.split(';')
+                .next()
+                .unwrap_or(ct)
+                .trim()
+                .to_ascii_lowercase()
+        })
+        .unwrap_or_default();
+    let from_mime = match mime.as_str() {
+        "image/jpeg" | "image/jpg" => Some("jpg"),
+        "image/png" => Some("png"),
+        "image/webp" => Some("webp"),
+        "image/gif" => Some("gif"),
+        "image/avif" => Some("avif"),
+        _ => None,
+    };
+    if let Some(ext) = from_mime {
+        return ext.to_string();
+    }
+    url.split(['?', '#'])
+        .next()
+        .and_then(|path| Path::new(path).extension())
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase)
+        .filter(|ext| {
+            matches!(
+                ext.as_str(),
+                "jpg" | "jpeg" | "png" | "webp" | "gif" | "avif"
+            )
+        })
+        .unwrap_or_else(|| "jpg".to_string())
+}
+
+// RIFF container with a WEBP form type
+fn is_webp(data: &[u8]) -> bool {
+    data.len() >= 12 && &data[0..4] == b"RIFF" && &data[8..12] == b"WEBP"
+}
+
+fn webp_to_jpeg(data: &[u8], quality: u8) -> Result<Vec<u8>> {
+    let img = image::load_from_memory_with_format(data, image::ImageFormat::WebP)?;
+    // JPEG has no alpha channel
+    let rgb = img.to_rgb8();
+    let mut out = Vec::new();
+    let encoder =
+        image::codecs::jpeg::JpegEncoder::new_with_quality(&mut out, quality.clamp(1, 100));
+    rgb.write_with_encoder(encoder)?;
+    Ok(out)
+}
+
+/// File/folder stem for a chapter download: its number label, else the parsed number, else
+/// `chapter_<n>` by position. If another chapter in `existing_names` already took that name
+/// (same number from another language or volume), the language, then the volume, then a
+/// counter is appended. The chosen name is added to `existing_names`; pass the same set for
+/// every chapter of a series, in list order.
+pub fn chapter_output_name(
+    chapter: &ChapterInfo,
+    existing_names: &mut std::collections::HashSet<String>,
+) -> String {
+    // Plugin-supplied, so it must not add path separators or `..` to the download dir
+    let base = chapter
+        .number_text
+        .as_deref()
+        .map(sanitize_path_component)
+        .filter(|b| !b.is_empty())
+        .or_else(|| {
+            chapter
+                .number_num
+                .map(crate::mapping::format_chapter_number)
+        })
+        .unwrap_or_else(|| format!("chapter_{}", existing_names.len() + 1));
+
+    let mut candidates = vec![base.clone()];
+    if let Some(lang) = chapter.lang.as_deref().filter(|l| !l.is_empty()) {
+        candidates.push(format!("{} [{}]", base, sanitize_path_component(lang)));
+    }
+    if let Some(volume) = chapter.volume.as_deref().filter(|v| !v.is_empty()) {
+        candidates.push(format!(
+            "{} [vol {}]",
+            base,
+            sanitize_path_component(volume)
+        ));
+    }
+    let name = candidates
+        .into_iter()
+        .find(|c| !existing_names.contains(c))
+        .unwrap_or_else(|| {
+            (2..)
+                .map(|n| format!("{} ({})", base, n))
+                .find(|c| !existing_names.contains(c))
+                .expect("unbounded counter")
+        });
+    existing_names.insert(name.clone());
+    name
+}
+
+fn is_http_url(url: &str) -> bool {
+    url::Url::parse(url).is_ok_and(|u| matches!(u.scheme(), "http" | "https"))
+}
+
+fn chapter_info(row: crate::dao::ChapterStateRow) -> ChapterInfo {
+    ChapterInfo {
+        has_images: row.image_count > 0 || row.has_cached_pages,
+        image_count: row.image_count as usize,
+        id: row.id,
+        series_id: row.series_id,
+        external_id: row.external_id,
+        number_text: row.number_text,
+        number_num: row.number_num,
+        title: row.title,
+        lang: row.lang,
+        volume: row.volume,
+    }
+}
+
+fn chapter_output_path(base_dir: &Path, name: &str, as_cbz: bool) -> PathBuf {
+    if as_cbz {
+        base_dir.join(format!("{}.cbz", name))
+    } else {
+        base_dir.join(name)
+    }
+}
+
+// Sum of the known sizes scaled up to `count` items; `None` when nothing is known.
+fn extrapolate_total(sizes: &[Option<u64>], count: usize) -> Option<u64> {
+    let known: Vec<u64> = sizes.iter().flatten().copied().collect();
+    if known.is_empty() {
+        return None;
+    }
+    let sum: u64 = known.iter().sum();
+    Some(sum * count as u64 / known.len() as u64)
+}
+
+// Zero-padded, 1-based page name shared by image folders and CBZ entries
+fn page_file_name(index: usize) -> String {
+    format!("{:04}.jpg", index + 1)
+}
+
 // Local helper needed for stats (avoid reaching into aggregator internals)
 fn current_epoch() -> i64 {
     std::time::SystemTime::now()
@@ -1057,3 +3627,277 @@ fn current_epoch() -> i64 {
         .unwrap_or_default()
         .as_secs() as i64
 }
+
+// Image entries in a CBZ (ComicInfo.xml and other metadata don't count); errors if the data
+// isn't a zip or holds no images
+fn cbz_page_count(data: &[u8]) -> Result<usize> {
+    let archive = zip::ZipArchive::new(std::io::Cursor::new(data))?;
+    let pages = archive
+        .file_names()
+        .filter(|name| {
+            let ext = name.rsplit_once('.').map(|(_, e)| e.to_ascii_lowercase());
+            matches!(
+                ext.as_deref(),
+                Some("jpg" | "jpeg" | "png" | "webp" | "gif" | "avif" | "bmp")
+            )
+        })
+        .count();
+    if pages == 0 {
+        return Err(anyhow::anyhow!("archive contains no images"));
+    }
+    Ok(pages)
+}
+
+// Size from metadata first, then a streaming SHA-256, so large files aren't read into memory
+// and a size mismatch doesn't read them at all. `Err` if the file can't be read.
+async fn file_matches(path: &str, size: i64, sha256: &str) -> std::io::Result<bool> {
+    use sha2::{Digest, Sha256};
+    use tokio::io::AsyncReadExt;
+
+    if tokio::fs::metadata(path).await?.len() as i64 != size {
+        return Ok(false);
+    }
+    let mut file = tokio::fs::File::open(path).await?;
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; 64 * 1024];
+    loop {
+        let n = file.read(&mut buf).await?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    let digest: String = hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    Ok(digest == sha256)
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    use sha2::{Digest, Sha256};
+    Sha256::digest(data)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+// Default image cache: `<user cache dir>/images`, or a relative `image-cache` dir if unknown
+fn default_image_cache_dir() -> PathBuf {
+    directories::ProjectDirs::from("dev", "touring", "touring")
+        .map(|p| p.cache_dir().join("images"))
+        .unwrap_or_else(|| PathBuf::from("image-cache"))
+}
+
+// Default download root: `<user data dir>/downloads`, or a relative `downloads` dir if unknown
+fn default_download_root() -> PathBuf {
+    directories::ProjectDirs::from("dev", "touring", "touring")
+        .map(|p| p.data_dir().join("downloads"))
+        .unwrap_or_else(|| PathBuf::from("downloads"))
+}
+
+// Remove all files below `dir` (and the emptied subdirectories, keeping `dir` itself);
+// returns their total size. A missing directory frees nothing.
+async fn remove_cached_files(dir: &Path) -> std::io::Result<u64> {
+    let mut freed = 0;
+    let mut pending = vec![dir.to_path_buf()];
+    let mut subdirs = Vec::new();
+    while let Some(current) = pending.pop() {
+        let mut entries = match tokio::fs::read_dir(&current).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e),
+        };
+        while let Some(entry) = entries.next_entry().await? {
+            let meta = entry.metadata().await?;
+            if meta.is_dir() {
+                pending.push(entry.path());
+                subdirs.push(entry.path());
+            } else {
+                tokio::fs::remove_file(entry.path()).await?;
+                freed += meta.len();
+            }
+        }
+    }
+    // Deepest first, so parents are empty by the time they are removed
+    for sub in subdirs.iter().rev() {
+        let _ = tokio::fs::remove_dir(sub).await;
+    }
+    Ok(freed)
+}
+
+// Rename, falling back to copy + delete when crossing filesystems
+async fn move_path(from: &Path, to: &Path) -> std::io::Result<()> {
+    if tokio::fs::rename(from, to).await.is_ok() {
+        return Ok(());
+    }
+    if tokio::fs::metadata(from).await?.is_dir() {
+        copy_dir(from, to).await?;
+        tokio::fs::remove_dir_all(from).await
+    } else {
+        tokio::fs::copy(from, to).await?;
+        tokio::fs::remove_file(from).await
+    }
+}
+
+async fn copy_dir(from: &Path, to: &Path) -> std::io::Result<()> {
+    tokio::fs::create_dir_all(to).await?;
+    let mut entries = tokio::fs::read_dir(from).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        let target = to.join(entry.file_name());
+        if entry.file_type().await?.is_dir() {
+            Box::pin(copy_dir(&entry.path(), &target)).await?;
+        } else {
+            tokio::fs::copy(entry.path(), &target).await?;
+        }
+    }
+    Ok(())
+}
+
+// Make a single path component safe across filesystems (no separators/reserved chars)
+fn sanitize_path_component(name: &str) -> String {
+    let cleaned: String = name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    cleaned.trim().trim_end_matches('.').trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chapter_output_names_stay_inside_the_series_dir() {
+        let chapter = |number_text: &str| ChapterInfo {
+            id: String::new(),
+            series_id: String::new(),
+            external_id: String::new(),
+            number_text: Some(number_text.to_string()),
+            number_num: Some(3.0),
+            title: None,
+            lang: None,
+            volume: None,
+            has_images: false,
+            image_count: 0,
+        };
+        let mut existing = std::collections::HashSet::new();
+        let names: Vec<String> = ["../x", "1/2", "..", "1/2"]
+            .into_iter()
+            .map(|n| chapter_output_name(&chapter(n), &mut existing))
+            .collect();
+        assert_eq!(names, vec![".._x", "1_2", "3", "1_2 (2)"]);
+        let base = Path::new("/library/series");
+        for name in &names {
+            let path = chapter_output_path(base, name, true);
+            assert_eq!(path.parent(), Some(base));
+        }
+    }
+
+    #[tokio::test]
+    async fn verify_reports_unrecorded_missing_chapters_and_corrupt_files() {
+        use crate::plugins::mock::{page, unit, MockSource};
+        use crate::plugins::UnitKind;
+
+        let dir = tempfile::tempdir().unwrap();
+        let url = format!("sqlite://{}?mode=rwc", dir.path().join("t.db").display());
+        let mut agg = Aggregator::new(Some(&url), true).await.unwrap();
+        let units = (1..=3)
+            .map(|n| unit(&format!("c{n}"), n as f32, UnitKind::Chapter))
+            .collect();
+        agg.register_mock_source(
+            "src",
+            MockSource::new()
+                .with_units("m1", units)
+                .with_assets("c1", vec![page("mock://1")]),
+        );
+        agg.get_manga_chapters("m1").await.unwrap();
+        let touring = Touring::from_aggregator(agg).unwrap();
+        let pool = touring.agg.database().pool().clone();
+        let series_id = crate::dao::find_series_id_by_source_external(&pool, "src", "m1")
+            .await
+            .unwrap()
+            .unwrap();
+        let series_dir = dir.path().join("series");
+        tokio::fs::create_dir_all(series_dir.join("2"))
+            .await
+            .unwrap();
+        let c1_path = series_dir.join("1.cbz");
+        tokio::fs::write(&c1_path, b"archive").await.unwrap();
+        crate::dao::set_series_download_path(&pool, &series_id, series_dir.to_str())
+            .await
+            .unwrap();
+        let (c1, _) = crate::dao::find_chapter_identity(&pool, "c1")
+            .await
+            .unwrap()
+            .unwrap();
+        crate::dao::upsert_download(
+            &pool,
+            &crate::dao::DownloadInsert {
+                unit_id: c1.clone(),
+                kind: "chapter".to_string(),
+                path: c1_path.to_string_lossy().into_owned(),
+                size_bytes: 7,
+                sha256: sha256_hex(b"archivf"),
+                page_count: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        let issues = touring.verify_all_downloads(|_| {}).await.unwrap();
+        let found: Vec<_> = issues
+            .iter()
+            .map(|i| (i.problem.clone(), i.path.clone()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (
+                    DownloadProblem::Corrupt,
+                    c1_path.to_string_lossy().into_owned()
+                ),
+                (
+                    DownloadProblem::MissingChapter,
+                    series_dir.join("3.cbz").to_string_lossy().into_owned()
+                ),
+            ]
+        );
+        assert!(
+            file_matches(c1_path.to_str().unwrap(), 7, &sha256_hex(b"archive"))
+                .await
+                .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn removing_cached_files_reports_bytes_freed() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("images");
+        tokio::fs::create_dir_all(root.join("s1/nested"))
+            .await
+            .unwrap();
+        tokio::fs::create_dir_all(root.join("s2")).await.unwrap();
+        tokio::fs::write(root.join("s1/cover.jpg"), b"abc")
+            .await
+            .unwrap();
+        tokio::fs::write(root.join("s1/nested/p1.png"), b"de")
+            .await
+            .unwrap();
+        tokio::fs::write(root.join("s2/cover.webp"), b"fghij")
+            .await
+            .unwrap();
+
+        assert_eq!(remove_cached_files(&root.join("s1")).await.unwrap(), 5);
+        assert!(root.join("s2/cover.webp").exists());
+        assert_eq!(remove_cached_files(&root).await.unwrap(), 5);
+        assert!(root.exists());
+        assert!(std::fs::read_dir(&root).unwrap().next().is_none());
+        assert_eq!(remove_cached_files(&root.join("missing")).await.unwrap(), 0);
+    }
+}
@@ -4,7 +4,8 @@ use clap::Parser;
 use cli::{Cli, Commands, DownloadCmd, SeriesCmd};
 use std::io::Write; // for zip.write_all
 use std::path::{Path, PathBuf};
-use touring::prelude::MediaType;
+use touring::mapping::format_chapter_number;
+use touring::prelude::{AssetKind, MediaType, UnitKind};
 use tracing_subscriber::{fmt, EnvFilter};
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -84,8 +85,28 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 None => println!("Not found. Make sure you've searched that media first so the series/mapping exists."),
             }
         }
-        Commands::Capabilities { refresh } => {
+        Commands::Capabilities { refresh, json } => {
             let caps = rt.block_on(touring.get_capabilities(refresh))?;
+            if json {
+                // Capabilities are cached by the call above, so the union reflects the same data
+                let union = rt.block_on(touring.aggregate_capabilities())?;
+                let plugins: serde_json::Map<String, serde_json::Value> = caps.iter().map(|(name, c)| {
+                    (name.clone(), serde_json::json!({
+                        "media_types": c.media_types.iter().map(media_label).collect::<Vec<_>>(),
+                        "unit_kinds": c.unit_kinds.iter().map(unit_label).collect::<Vec<_>>(),
+                        "asset_kinds": c.asset_kinds.iter().map(asset_label).collect::<Vec<_>>(),
+                    }))
+                }).collect();
+                println!("{}", serde_json::to_string_pretty(&serde_json::json!({
+                    "plugins": plugins,
+                    "union": {
+                        "media_types": union.media_types.iter().map(media_label).collect::<Vec<_>>(),
+                        "unit_kinds": union.unit_kinds.iter().map(unit_label).collect::<Vec<_>>(),
+                        "asset_kinds": union.asset_kinds.iter().map(asset_label).collect::<Vec<_>>(),
+                    },
+                }))?);
+                return Ok(());
+            }
             for (name, c) in caps {
                 let media: Vec<String> = c.media_types.into_iter().map(|m| format!("{:?}", m)).collect();
                 let units: Vec<String> = c.unit_kinds.into_iter().map(|u| format!("{:?}", u)).collect();
@@ -155,7 +176,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             else {
                 println!("Found {} chapters for manga {}:", units.len(), manga_id);
                 for u in units {
-                    let num = u.number.map(|n| n.to_string()).or(u.number_text.clone()).unwrap_or_default();
+                    let num = u.number.map(|n| format_chapter_number(n as f64)).or(u.number_text.clone()).unwrap_or_default();
                     println!("  {}: {}{}", u.id, if num.is_empty() { "".to_string() } else { format!("Ch. {} ", num) }, u.title);
                     if let Some(lang) = &u.lang { println!("    lang: {}", lang); }
                     if let Some(g) = &u.group { println!("    group: {}", g); }
@@ -171,7 +192,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             else {
                 println!("Found {} episodes for anime {}:", units.len(), anime_id);
                 for u in units {
-                    let num = u.number.map(|n| n.to_string()).or(u.number_text.clone()).unwrap_or_default();
+                    let num = u.number.map(|n| format_chapter_number(n as f64)).or(u.number_text.clone()).unwrap_or_default();
                     println!("  {}: {}{}", u.id, if num.is_empty() { "".to_string() } else { format!("Ep. {} ", num) }, u.title);
                     if let Some(lang) = &u.lang { println!("    lang: {}", lang); }
                     if let Some(s) = &u.group { println!("    season: {}", s); }
@@ -223,15 +244,19 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 } else {
                     match rt.block_on(touring.get_chapter_meta(&chapter_id))? {
                         Some((series_id, number_num, number_text)) => {
-                            let base = match rt.block_on(touring.get_series_path(&series_id))? {
-                                Some(p) => PathBuf::from(p),
+                            // Same (collision-free) name a series download would use
+                            let planned = rt.block_on(touring.get_download_path_tree(&series_id, cbz))?
+                                .into_iter()
+                                .find(|(id, _)| *id == chapter_id)
+                                .map(|(_, path)| path);
+                            match planned {
+                                Some(path) => path,
                                 None => {
-                                    eprintln!("Error: no --out provided and no stored download_path for series {}.", series_id);
-                                    return Ok(());
+                                    let base = rt.block_on(touring.resolve_download_dir(&series_id))?;
+                                    let name = number_text.or_else(|| number_num.map(format_chapter_number)).unwrap_or_else(|| "chapter".to_string());
+                                    if cbz { base.join(format!("{}.cbz", name)) } else { base.join(name) }
                                 }
-                            };
-                            let name = number_text.or_else(|| number_num.map(|n| format!("{:.3}", n))).unwrap_or_else(|| "chapter".to_string());
-                            if cbz { base.join(format!("{}.cbz", name)) } else { base.join(name) }
+                            }
                         }
                         None => {
                             eprintln!("Error: chapter not found: {}", chapter_id);
@@ -240,10 +265,11 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                     }
                 };
 
+                let mock = mock > 0;
                 if cbz {
-                    rt.block_on(save_cbz_mockable(&chapter_id, &urls, &target, force))?;
+                    rt.block_on(save_cbz_mockable(&chapter_id, &urls, &target, force, mock))?;
                 } else {
-                    rt.block_on(save_images_mockable(&chapter_id, &urls, &target, force))?;
+                    rt.block_on(save_images_mockable(&chapter_id, &urls, &target, force, mock))?;
                 }
                 println!("Saved {} images.", urls.len());
             }
@@ -259,14 +285,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 } else {
                     match rt.block_on(touring.get_episode_meta(&episode_id))? {
                         Some((series_id, number_num, number_text)) => {
-                            let base = match rt.block_on(touring.get_series_path(&series_id))? {
-                                Some(p) => PathBuf::from(p),
-                                None => {
-                                    eprintln!("Error: no --out provided and no stored download_path for series {}.", series_id);
-                                    return Ok(());
-                                }
-                            };
-                            let name = number_text.or_else(|| number_num.map(|n| format!("{:.3}", n))).unwrap_or_else(|| "episode".to_string());
+                            let base = rt.block_on(touring.resolve_download_dir(&series_id))?;
+                            let name = number_text.or_else(|| number_num.map(format_chapter_number)).unwrap_or_else(|| "episode".to_string());
                             base.join(format!("{}.txt", name))
                         }
                         None => {
@@ -284,16 +304,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 // Resolve output base directory
                 let base_out: PathBuf = match out {
                     Some(o) => PathBuf::from(o),
-                    None => match rt.block_on(touring.get_series_download_path(&series_id))? {
-                        Some(p) => PathBuf::from(p),
-                        None => {
-                            eprintln!(
-                                "Error: no --out provided and no stored series download_path for {}.\nSet one with: touring series set-path {} --path <directory>",
-                                series_id, series_id
-                            );
-                            return Ok(());
-                        }
-                    },
+                    None => rt.block_on(touring.resolve_download_dir(&series_id))?,
                 };
 
                 // Ensure base directory exists (for creating per-entry subdirectories/files)
@@ -305,13 +316,9 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
                 if !chapters.is_empty() {
                     println!("Downloading {} chapters to {}...", chapters.len(), base_out.display());
-                    for (cid, number_num, number_text) in chapters {
-                        let name = number_text.clone().or_else(|| number_num.map(|n| format!("{:.3}", n))).unwrap_or_else(|| "chapter".to_string());
-                        let ch_out = if cbz {
-                            base_out.join(format!("{}.cbz", name))
-                        } else {
-                            base_out.join(name)
-                        };
+                    for (cid, planned) in rt.block_on(touring.get_download_path_tree(&series_id, cbz))? {
+                        let Some(file_name) = planned.file_name() else { continue };
+                        let ch_out = base_out.join(file_name);
                         let urls = rt.block_on(touring.get_chapter_images(&cid))?;
                         if urls.is_empty() { continue; }
                         if cbz {
@@ -324,7 +331,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 } else if !episodes.is_empty() {
                     println!("Downloading {} episodes to {}...", episodes.len(), base_out.display());
                     for (eid, number_num, number_text) in episodes {
-                        let name = number_text.clone().or_else(|| number_num.map(|n| format!("{:.3}", n))).unwrap_or_else(|| "episode".to_string());
+                        let name = number_text.clone().or_else(|| number_num.map(format_chapter_number)).unwrap_or_else(|| "episode".to_string());
                         let ep_out = base_out.join(format!("{}.txt", name));
                         let streams = rt.block_on(touring.get_episode_streams(&eid))?;
                         if streams.is_empty() { continue; }
@@ -382,10 +389,8 @@ async fn save_images(
     for (i, url) in urls.iter().enumerate() {
         let fname = format!("{:04}.jpg", i + 1);
         let path = out_dir.join(fname);
-        if !force {
-            if tokio::fs::try_exists(&path).await.unwrap_or(false) {
-                continue;
-            }
+        if !force && tokio::fs::try_exists(&path).await.unwrap_or(false) {
+            continue;
         }
         let resp = client.get(url).send().await?;
         if !resp.status().is_success() {
@@ -439,6 +444,7 @@ async fn save_images_mockable(
     urls: &[String],
     out_dir: &Path,
     force: bool,
+    mock: bool,
 ) -> Result<(), Box<dyn std::error::Error>> {
     tokio::fs::create_dir_all(out_dir).await.ok();
     let client = reqwest::Client::builder()
@@ -447,16 +453,18 @@ async fn save_images_mockable(
     for (i, url) in urls.iter().enumerate() {
         let fname = format!("{:04}.jpg", i + 1);
         let path = out_dir.join(fname);
-        if !force {
-            if tokio::fs::try_exists(&path).await.unwrap_or(false) {
-                continue;
-            }
+        if !force && tokio::fs::try_exists(&path).await.unwrap_or(false) {
+            continue;
         }
-        if url.starts_with("mock://") {
-            // write simple placeholder bytes
+        if mock {
+            // --mock: write simple placeholder bytes
             tokio::fs::write(&path, b"MOCK").await?;
             continue;
         }
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            eprintln!("Skipping {}: unsupported URL scheme", url);
+            continue;
+        }
         let resp = client.get(url).send().await?;
         if !resp.status().is_success() {
             eprintln!("Failed to download {}: {}", url, resp.status());
@@ -473,13 +481,14 @@ async fn save_cbz_mockable(
     urls: &[String],
     out_file: &Path,
     force: bool,
+    mock: bool,
 ) -> Result<(), Box<dyn std::error::Error>> {
     if !force && tokio::fs::try_exists(out_file).await.unwrap_or(false) {
         return Ok(());
     }
     let tmp_dir = out_file.with_extension("tmpdir");
     tokio::fs::create_dir_all(&tmp_dir).await.ok();
-    save_images_mockable(_chapter_id, urls, &tmp_dir, true).await?;
+    save_images_mockable(_chapter_id, urls, &tmp_dir, true, mock).await?;
     // Zip the directory into a CBZ
     let file = std::fs::File::create(out_file)?;
     let mut zip = zip::ZipWriter::new(file);
@@ -504,3 +513,32 @@ async fn save_cbz_mockable(
     let _ = std::fs::remove_dir_all(&tmp_dir);
     Ok(())
 }
+
+fn media_label(m: &MediaType) -> String {
+    match m {
+        MediaType::Manga => "manga".to_string(),
+        MediaType::Anime => "anime".to_string(),
+        MediaType::Other(s) => s.clone(),
+    }
+}
+
+fn unit_label(u: &UnitKind) -> String {
+    match u {
+        UnitKind::Chapter => "chapter".to_string(),
+        UnitKind::Episode => "episode".to_string(),
+        UnitKind::Section => "section".to_string(),
+        UnitKind::Other(s) => s.clone(),
+    }
+}
+
+fn asset_label(a: &AssetKind) -> String {
+    match a {
+        AssetKind::Page => "page".to_string(),
+        AssetKind::Image => "image".to_string(),
+        AssetKind::Audio => "audio".to_string(),
+        AssetKind::Video => "video".to_string(),
+        AssetKind::Subtitle => "subtitle".to_string(),
+        AssetKind::File => "file".to_string(),
+        AssetKind::Other(s) => s.clone(),
+    }
+}
@@ -1,11 +1,20 @@
-use crate::dao::{ChapterInsert, SeriesInsert, SeriesSourceInsert};
-use crate::plugins::{Media, MediaType, Unit, UnitKind};
+use crate::dao::{ChapterInsert, EpisodeInsert, SectionInsert, SeriesInsert, SeriesSourceInsert};
+use crate::plugins::{Asset, Media, MediaType, Unit, UnitKind};
 
-fn kind_str(mt: &MediaType) -> &'static str {
+/// `series.kind` for a media type: "manga", "anime", or the lowercased `Other` label
+/// (e.g. "novel"; "other" when the label is blank).
+pub fn series_kind(mt: &MediaType) -> String {
     match mt {
-        MediaType::Manga => "manga",
-        MediaType::Anime => "anime",
-        MediaType::Other(_) => "other",
+        MediaType::Manga => "manga".to_string(),
+        MediaType::Anime => "anime".to_string(),
+        MediaType::Other(label) => {
+            let label = label.trim().to_lowercase();
+            if label.is_empty() {
+                "other".to_string()
+            } else {
+                label
+            }
+        }
     }
 }
 
@@ -13,7 +22,7 @@ pub fn series_id_from(source_id: &str, media: &Media) -> String {
     format!(
         "series:{}:{}:{}",
         source_id,
-        kind_str(&media.mediatype),
+        series_kind(&media.mediatype),
         media.id
     )
 }
@@ -31,7 +40,7 @@ pub fn chapter_id_from(source_id: &str, unit: &Unit) -> String {
 pub fn series_insert_from_media(id: String, media: &Media) -> SeriesInsert {
     SeriesInsert {
         id,
-        kind: kind_str(&media.mediatype).to_string(),
+        kind: series_kind(&media.mediatype),
         title: media.title.clone(),
         alt_titles: None,
         description: media.description.clone(),
@@ -59,7 +68,119 @@ pub fn chapter_insert_from_unit(
     source_id: String,
     u: &Unit,
 ) -> ChapterInsert {
+    let mut number_text = u.number_text.clone();
+    let mut number_num = u
+        .number
+        .map(|n| n as f64)
+        .or_else(|| u.number_text.as_deref().and_then(parse_chapter_number));
+    // Title-only sources ("Chapter 45: The End"); the title itself is kept as is
+    if number_text.is_none() && number_num.is_none() {
+        number_num = parse_title_chapter_number(&u.title);
+        number_text = number_num.map(format_chapter_number);
+    }
     ChapterInsert {
+        id,
+        series_id,
+        source_id,
+        external_id: u.id.clone(),
+        number_text,
+        number_num,
+        title: Some(u.title.clone()).filter(|s| !s.is_empty()),
+        lang: u.lang.clone(),
+        volume: u.group.clone(),
+        published_at: u.published_at.clone(),
+    }
+}
+
+/// Chapter number from display text: "12.5", "Chapter 7", "Vol.2 Ch.3" (3), "Vol. 1 - 15" (15).
+/// A bare volume ("Vol. 2") or text without digits yields `None`.
+pub fn parse_chapter_number(text: &str) -> Option<f64> {
+    let lower = text.to_lowercase();
+    // An explicit chapter marker wins over any other number in the text
+    if let Some(n) = marked_chapter_number(&lower) {
+        return Some(n);
+    }
+    let rest = strip_volume_prefix(&lower);
+    let start = rest.find(|c: char| c.is_ascii_digit())?;
+    leading_number(&rest[start..])
+}
+
+/// Label for a parsed chapter number: integers without decimals ("5"), fractions with as
+/// few as needed ("5.5"), at most three, so f32 noise from plugins ("5.099999…") disappears.
+pub fn format_chapter_number(n: f64) -> String {
+    let fixed = format!("{:.3}", n);
+    let trimmed = fixed.trim_end_matches('0').trim_end_matches('.');
+    match trimmed {
+        "-0" => "0".to_string(),
+        t => t.to_string(),
+    }
+}
+
+/// Stricter `parse_chapter_number` for titles, where a stray number is usually part of the
+/// name ("The 3 Brothers"): only a chapter marker ("Chapter 45: The End") or a number opening
+/// the title ("45 - The End", "Vol. 2 - 12") counts.
+pub fn parse_title_chapter_number(title: &str) -> Option<f64> {
+    let lower = title.to_lowercase();
+    marked_chapter_number(&lower).or_else(|| leading_number(strip_volume_prefix(&lower)))
+}
+
+fn marked_chapter_number(lower: &str) -> Option<f64> {
+    ["chapter", "chap", "ch", "#"].iter().find_map(|marker| {
+        lower.rmatch_indices(marker).find_map(|(pos, _)| {
+            let word_start = !lower[..pos]
+                .chars()
+                .next_back()
+                .is_some_and(|c| c.is_alphabetic());
+            word_start.then(|| leading_number(&lower[pos + marker.len()..]))?
+        })
+    })
+}
+
+// Number at the start of `s` after separators; "," is accepted as a decimal point.
+fn leading_number(s: &str) -> Option<f64> {
+    let s = s.trim_start_matches([' ', '.', ':', '-', '_']);
+    let int_len = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    if int_len == 0 {
+        return None;
+    }
+    let mut number = s[..int_len].to_string();
+    let rest = &s[int_len..];
+    if let Some(frac) = rest.strip_prefix(['.', ',']) {
+        let frac_len = frac
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(frac.len());
+        if frac_len > 0 {
+            number.push('.');
+            number.push_str(&frac[..frac_len]);
+        }
+    }
+    number.parse().ok()
+}
+
+// Drop "vol. 2", "volume 2" or "v2" so the volume isn't mistaken for the chapter.
+fn strip_volume_prefix(s: &str) -> &str {
+    let trimmed = s.trim_start();
+    for prefix in ["volume", "vol", "v"] {
+        if let Some(rest) = trimmed.strip_prefix(prefix) {
+            let rest = rest.trim_start_matches(['.', ' ']);
+            let digits = rest
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rest.len());
+            if digits > 0 {
+                return &rest[digits..];
+            }
+        }
+    }
+    s
+}
+
+pub fn episode_insert_from_unit(
+    id: String,
+    series_id: String,
+    source_id: String,
+    u: &Unit,
+) -> EpisodeInsert {
+    EpisodeInsert {
         id,
         series_id,
         source_id,
@@ -68,7 +189,339 @@ pub fn chapter_insert_from_unit(
         number_num: u.number.map(|n| n as f64),
         title: Some(u.title.clone()).filter(|s| !s.is_empty()),
         lang: u.lang.clone(),
-        volume: u.group.clone(),
+        season: u.group.clone(),
         published_at: u.published_at.clone(),
     }
 }
+
+pub fn section_insert_from_unit(
+    id: String,
+    series_id: String,
+    source_id: String,
+    position: i64,
+    u: &Unit,
+) -> SectionInsert {
+    SectionInsert {
+        id,
+        series_id,
+        source_id,
+        external_id: u.id.clone(),
+        number_text: u.number_text.clone(),
+        number_num: u.number.map(|n| n as f64),
+        title: Some(u.title.clone()).filter(|s| !s.is_empty()),
+        lang: u.lang.clone(),
+        position,
+    }
+}
+
+/// Split a unit list into sections and their children. A child belongs to the section whose
+/// id, title or number label matches its `group`; sources that don't label groups that way
+/// are grouped positionally (units follow the section they belong to).
+pub fn group_units_by_section(units: &[Unit]) -> Vec<(&Unit, Vec<&Unit>)> {
+    let sections: Vec<&Unit> = units
+        .iter()
+        .filter(|u| matches!(u.kind, UnitKind::Section))
+        .collect();
+    let matches_group = |section: &Unit, group: &str| {
+        let group = group.trim();
+        !group.is_empty()
+            && (section.id == group
+                || section.title.trim().eq_ignore_ascii_case(group)
+                || section
+                    .number_text
+                    .as_deref()
+                    .is_some_and(|n| n.trim().eq_ignore_ascii_case(group)))
+    };
+    let by_label = units.iter().any(|u| {
+        !matches!(u.kind, UnitKind::Section)
+            && u.group
+                .as_deref()
+                .is_some_and(|g| sections.iter().any(|s| matches_group(s, g)))
+    });
+
+    let mut out: Vec<(&Unit, Vec<&Unit>)> = sections.iter().map(|s| (*s, Vec::new())).collect();
+    let mut current: Option<usize> = None;
+    for u in units {
+        if matches!(u.kind, UnitKind::Section) {
+            current = sections.iter().position(|s| std::ptr::eq(*s, u));
+            continue;
+        }
+        let idx = if by_label {
+            u.group
+                .as_deref()
+                .and_then(|g| sections.iter().position(|s| matches_group(s, g)))
+        } else {
+            current
+        };
+        if let Some(i) = idx {
+            out[i].1.push(u);
+        }
+    }
+    out
+}
+
+// Resolutions recognised in stream URLs when the plugin doesn't report a height
+const QUALITY_TOKENS: &[&str] = &[
+    "2160p", "1440p", "1080p", "720p", "576p", "540p", "480p", "360p", "240p",
+];
+
+/// Quality label for a stream asset, e.g. "720p": from the reported height, else a
+/// resolution token in the URL (common for HLS variants and CDN paths).
+///
+/// The WIT `asset` record has no quality field, so this is inferred rather than reported by
+/// the plugin; adding one would break every compiled plugin. Assets with neither a height nor
+/// a recognisable token get `None`.
+pub fn stream_quality(asset: &Asset) -> Option<String> {
+    if let Some(h) = asset.height.filter(|h| *h > 0) {
+        return Some(format!("{}p", h));
+    }
+    let url = asset.url.to_ascii_lowercase();
+    QUALITY_TOKENS.iter().find_map(|t| {
+        url.match_indices(t)
+            .any(|(i, _)| {
+                let before = url[..i].chars().next_back();
+                before.is_none_or(|c| !c.is_ascii_digit())
+            })
+            .then(|| t.to_string())
+    })
+}
+
+const DAY_SECS: i64 = 86_400;
+
+/// Unix seconds for a plugin's free-form `published_at`: ISO 8601 / RFC 3339 timestamps
+/// (`Z` or an offset; none means UTC), plain `YYYY-MM-DD`, a bare year, unix timestamps
+/// (seconds or milliseconds) and English relative forms ("3 days ago", "an hour ago", "2h ago",
+/// "yesterday"), which resolve against `now`.
+pub fn parse_published_epoch(raw: &str, now: i64) -> Option<i64> {
+    let s = raw.trim();
+    if !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit()) {
+        let n: i64 = s.parse().ok()?;
+        return match s.len() {
+            4 => Some(days_from_civil(n, 1, 1) * DAY_SECS),
+            9..=11 => Some(n),
+            12..=14 => Some(n / 1000),
+            _ => None,
+        };
+    }
+    parse_iso_datetime(s).or_else(|| parse_relative_date(&s.to_ascii_lowercase(), now))
+}
+
+fn parse_iso_datetime(s: &str) -> Option<i64> {
+    let date = s.get(..10)?;
+    let sep = date.as_bytes()[4];
+    if !matches!(sep, b'-' | b'/') || date.as_bytes()[7] != sep {
+        return None;
+    }
+    let field = |range: std::ops::Range<usize>| -> Option<i64> {
+        let part = &date[range];
+        part.bytes()
+            .all(|b| b.is_ascii_digit())
+            .then(|| part.parse().ok())?
+    };
+    let (year, month, day) = (field(0..4)?, field(5..7)?, field(8..10)?);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    let mut secs = days_from_civil(year, month, day) * DAY_SECS;
+
+    let rest = &s[10..];
+    let Some(time) = rest.strip_prefix(['T', 't', ' ']) else {
+        return rest.is_empty().then_some(secs);
+    };
+    // HH:MM[:SS[.fraction]] followed by an optional zone
+    let zone_at = time.find(['Z', 'z', '+', '-']).unwrap_or(time.len());
+    let (clock, zone) = time.split_at(zone_at);
+    let mut parts = clock.split(':');
+    let hour: i64 = parts.next()?.parse().ok()?;
+    let minute: i64 = parts.next()?.parse().ok()?;
+    let second: i64 = match parts.next() {
+        Some(sec) => sec.split('.').next()?.parse().ok()?,
+        None => 0,
+    };
+    if parts.next().is_some() || hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+    secs += hour * 3600 + minute * 60 + second;
+
+    match zone {
+        "" | "Z" | "z" => Some(secs),
+        _ => {
+            let sign = if zone.starts_with('-') { -1 } else { 1 };
+            let digits: String = zone[1..].chars().filter(|c| *c != ':').collect();
+            if digits.len() != 4 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+                return None;
+            }
+            let offset =
+                digits[..2].parse::<i64>().ok()? * 3600 + digits[2..].parse::<i64>().ok()? * 60;
+            Some(secs - sign * offset)
+        }
+    }
+}
+
+// "3 days ago", "a week ago", "5h ago"; months and years are approximated as 30 / 365 days
+fn parse_relative_date(lower: &str, now: i64) -> Option<i64> {
+    match lower {
+        "just now" | "now" | "today" => return Some(now),
+        "yesterday" => return Some(now - DAY_SECS),
+        _ => {}
+    }
+    let rest = lower.strip_suffix("ago")?.trim();
+    let digits_end = rest
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(rest.len());
+    let (count, unit) = if digits_end == 0 {
+        let (word, unit) = rest.split_once(' ')?;
+        match word {
+            "a" | "an" | "one" => (1, unit),
+            _ => return None,
+        }
+    } else {
+        (rest[..digits_end].parse::<i64>().ok()?, &rest[digits_end..])
+    };
+    let unit = unit.trim();
+    if unit.is_empty() {
+        return None;
+    }
+    let unit_secs = match unit.trim_end_matches('s') {
+        "" | "sec" | "second" => 1,
+        "m" | "min" | "minute" => 60,
+        "h" | "hr" | "hour" => 3600,
+        "d" | "day" => DAY_SECS,
+        "w" | "wk" | "week" => 7 * DAY_SECS,
+        "mo" | "month" => 30 * DAY_SECS,
+        "y" | "yr" | "year" => 365 * DAY_SECS,
+        _ => return None,
+    };
+    now.checked_sub(count.checked_mul(unit_secs)?)
+}
+
+// Days since 1970-01-01 for a proleptic Gregorian date (Howard Hinnant's algorithm)
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = if y >= 0 { y } else { y - 399 } / 400;
+    let yoe = y - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unit(id: &str, kind: UnitKind, group: Option<&str>) -> Unit {
+        Unit {
+            id: id.to_string(),
+            title: format!("T {}", id),
+            number_text: None,
+            number: None,
+            lang: None,
+            group: group.map(str::to_string),
+            url: None,
+            published_at: None,
+            kind,
+        }
+    }
+
+    fn ids(groups: &[(&Unit, Vec<&Unit>)]) -> Vec<(String, Vec<String>)> {
+        groups
+            .iter()
+            .map(|(s, c)| (s.id.clone(), c.iter().map(|u| u.id.clone()).collect()))
+            .collect()
+    }
+
+    #[test]
+    fn series_kind_uses_other_label() {
+        assert_eq!(series_kind(&MediaType::Manga), "manga");
+        assert_eq!(series_kind(&MediaType::Other(" Novel ".into())), "novel");
+        assert_eq!(series_kind(&MediaType::Other(String::new())), "other");
+    }
+
+    #[test]
+    fn parses_chapter_numbers_from_text() {
+        assert_eq!(parse_chapter_number("12.5"), Some(12.5));
+        assert_eq!(parse_chapter_number("Chapter 7"), Some(7.0));
+        assert_eq!(parse_chapter_number("Vol.2 Ch.3"), Some(3.0));
+        assert_eq!(parse_chapter_number("Vol. 1 - 15,5"), Some(15.5));
+        assert_eq!(parse_chapter_number("Ch. 10: Reach 3"), Some(10.0));
+        assert_eq!(parse_chapter_number("#42"), Some(42.0));
+        assert_eq!(parse_chapter_number("Vol. 2"), None);
+        assert_eq!(parse_chapter_number("Oneshot"), None);
+
+        assert_eq!(
+            parse_title_chapter_number("Chapter 45: The End"),
+            Some(45.0)
+        );
+        assert_eq!(parse_title_chapter_number("12.5 - Extra"), Some(12.5));
+        assert_eq!(parse_title_chapter_number("The 3 Brothers"), None);
+
+        assert_eq!(format_chapter_number(5.0), "5");
+        assert_eq!(format_chapter_number(5.5), "5.5");
+        assert_eq!(format_chapter_number(5.1f32 as f64), "5.1");
+        assert_eq!(format_chapter_number(100.0), "100");
+    }
+
+    #[test]
+    fn parses_published_dates() {
+        let now = 1_700_000_000;
+        assert_eq!(
+            parse_published_epoch("2024-03-01", now),
+            Some(1_709_251_200)
+        );
+        assert_eq!(
+            parse_published_epoch("2024-03-01T12:30:00Z", now),
+            Some(1_709_296_200)
+        );
+        assert_eq!(
+            parse_published_epoch("2024-03-01T14:30:00.250+02:00", now),
+            Some(1_709_296_200)
+        );
+        assert_eq!(
+            parse_published_epoch("1709296200", now),
+            Some(1_709_296_200)
+        );
+        assert_eq!(
+            parse_published_epoch("2 days ago", now),
+            Some(now - 2 * 86_400)
+        );
+        assert_eq!(parse_published_epoch("an hour ago", now), Some(now - 3600));
+        assert_eq!(parse_published_epoch("5h ago", now), Some(now - 5 * 3600));
+        assert_eq!(parse_published_epoch("Yesterday", now), Some(now - 86_400));
+        assert_eq!(parse_published_epoch("2024-13-01", now), None);
+        assert_eq!(parse_published_epoch("soon", now), None);
+    }
+
+    #[test]
+    fn groups_children_by_label_or_position() {
+        let labelled = vec![
+            unit("arc1", UnitKind::Section, None),
+            unit("arc2", UnitKind::Section, None),
+            unit("c1", UnitKind::Chapter, Some("T arc1")),
+            unit("c2", UnitKind::Chapter, Some("arc2")),
+            unit("c3", UnitKind::Chapter, None),
+        ];
+        assert_eq!(
+            ids(&group_units_by_section(&labelled)),
+            vec![
+                ("arc1".into(), vec!["c1".into()]),
+                ("arc2".into(), vec!["c2".into()]),
+            ]
+        );
+
+        let positional = vec![
+            unit("c0", UnitKind::Chapter, None),
+            unit("arc1", UnitKind::Section, None),
+            unit("c1", UnitKind::Chapter, Some("Vol. 1")),
+            unit("arc2", UnitKind::Section, None),
+            unit("c2", UnitKind::Chapter, None),
+        ];
+        assert_eq!(
+            ids(&group_units_by_section(&positional)),
+            vec![
+                ("arc1".into(), vec!["c1".into()]),
+                ("arc2".into(), vec!["c2".into()]),
+            ]
+        );
+    }
+}
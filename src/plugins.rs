@@ -1,27 +1,35 @@
 use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::sync::{
     atomic::{AtomicBool, AtomicU64, Ordering},
     Arc,
 };
-use std::time::Duration;
-use tokio::sync::{mpsc, oneshot, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::{mpsc, oneshot, watch, Mutex};
 use tokio::task;
-use tracing::{debug, error, warn};
+use tracing::{debug, error, info, warn};
 use wasmtime::{Config, Engine};
 
+use crate::rate_limit::HostRateLimiter;
+
 // Generate WIT bindings from shared plugin-interface (generic library world)
 wasmtime::component::bindgen!({
     world: "library",
     path: "wit/",
 });
 
+mod allow_list;
 mod config;
 mod host;
+#[cfg(test)]
+pub(crate) mod mock;
 mod plugin;
+mod sanitize;
 
-use plugin::Plugin;
+pub use config::PluginConfig;
+use plugin::{ComponentSource, Plugin};
 
 // Commands routed to a dedicated worker thread per plugin
 enum PluginCmd {
@@ -42,9 +50,26 @@ enum PluginCmd {
         refresh: bool,
         reply: oneshot::Sender<anyhow::Result<ProviderCapabilities>>,
     },
+    // Forget cached capabilities so the next `GetCapabilities` queries the plugin
+    ResetCaps,
     GetAllowedHosts {
         reply: oneshot::Sender<anyhow::Result<Vec<String>>>,
     },
+    SetHostAllowed {
+        host: String,
+        allowed: bool,
+        reply: oneshot::Sender<anyhow::Result<Vec<String>>>,
+    },
+    UrlAllowed {
+        url: String,
+        reply: oneshot::Sender<anyhow::Result<bool>>,
+    },
+    #[cfg(feature = "debug-raw")]
+    CallRaw {
+        op: RawOp,
+        arg: String,
+        reply: oneshot::Sender<anyhow::Result<String>>,
+    },
 }
 
 #[derive(Clone)]
@@ -58,13 +83,341 @@ struct PluginArtifacts {
     fallback: Option<PathBuf>,
 }
 
+// Component bytes and config handed over by the app instead of files
+#[derive(Clone)]
+struct EmbeddedPlugin {
+    bytes: Arc<[u8]>,
+    config: PluginConfig,
+}
+
+// Operations a worker thread serves; implemented by the wasm `Plugin` and by in-process backends.
+trait PluginBackend: Send + 'static {
+    fn call_timeout(&self) -> Duration;
+    fn fetch_media_list(&mut self, kind: MediaType, query: &str) -> Result<Vec<Media>>;
+    fn fetch_units(&mut self, media_id: &str) -> Result<Vec<Unit>>;
+    fn fetch_assets(&mut self, unit_id: &str) -> Result<Vec<Asset>>;
+    fn get_capabilities(&mut self, refresh: bool) -> Result<ProviderCapabilities>;
+    /// Drop cached capabilities; backends that don't cache have nothing to do.
+    fn reset_capabilities(&mut self) {}
+    fn allowed_hosts(&self) -> Vec<String>;
+    /// Add or remove an allowed host; errors if that would empty the allow-list.
+    fn set_host_allowed(&mut self, host: &str, allowed: bool) -> Result<Vec<String>>;
+    fn url_allowed(&self, url: &str) -> bool;
+    /// The backend can no longer serve calls (a wasm call trapped); its worker retires it.
+    fn poisoned(&self) -> bool {
+        false
+    }
+    /// Unfiltered export result as JSON; see `PluginManager::plugin_call_raw`.
+    #[cfg(feature = "debug-raw")]
+    fn call_raw(&mut self, _op: RawOp, _arg: &str) -> Result<String> {
+        Err(anyhow!("raw calls are not supported by this source"))
+    }
+}
+
+impl PluginBackend for Plugin {
+    fn call_timeout(&self) -> Duration {
+        self.call_timeout
+    }
+    fn fetch_media_list(&mut self, kind: MediaType, query: &str) -> Result<Vec<Media>> {
+        Plugin::fetch_media_list(self, kind, query)
+    }
+    fn fetch_units(&mut self, media_id: &str) -> Result<Vec<Unit>> {
+        Plugin::fetch_units(self, media_id)
+    }
+    fn fetch_assets(&mut self, unit_id: &str) -> Result<Vec<Asset>> {
+        Plugin::fetch_assets(self, unit_id)
+    }
+    fn get_capabilities(&mut self, refresh: bool) -> Result<ProviderCapabilities> {
+        if refresh {
+            self.get_capabilities_refresh()
+        } else {
+            self.get_capabilities_cached()
+        }
+    }
+    fn reset_capabilities(&mut self) {
+        self.caps = None;
+    }
+    fn allowed_hosts(&self) -> Vec<String> {
+        self.allowed_hosts.clone().unwrap_or_default()
+    }
+    fn set_host_allowed(&mut self, host: &str, allowed: bool) -> Result<Vec<String>> {
+        Plugin::set_host_allowed(self, host, allowed)
+    }
+    fn url_allowed(&self, url: &str) -> bool {
+        Plugin::url_allowed(self, url)
+    }
+    fn poisoned(&self) -> bool {
+        self.poisoned
+    }
+    #[cfg(feature = "debug-raw")]
+    fn call_raw(&mut self, op: RawOp, arg: &str) -> Result<String> {
+        use serde_json::Value;
+        self.throttle();
+        let values: Vec<Value> = match op {
+            RawOp::MediaList => {
+                let (kind, query) = parse_raw_search_arg(arg);
+                let list = self.call_fetchmedialist(&kind, query)?;
+                list.iter().map(raw::media_json).collect()
+            }
+            RawOp::Units => {
+                let units = self.call_fetchunits(arg)?;
+                units.iter().map(raw::unit_json).collect()
+            }
+            RawOp::Assets => {
+                let assets = self.call_fetchassets(arg)?;
+                assets.iter().map(raw::asset_json).collect()
+            }
+        };
+        Ok(serde_json::to_string_pretty(&values)?)
+    }
+}
+
+/// Export named in `PluginManager::plugin_call_raw`.
+#[cfg(feature = "debug-raw")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RawOp {
+    MediaList,
+    Units,
+    Assets,
+}
+
+#[cfg(feature = "debug-raw")]
+impl std::str::FromStr for RawOp {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "fetchmedialist" => Ok(RawOp::MediaList),
+            "fetchunits" => Ok(RawOp::Units),
+            "fetchassets" => Ok(RawOp::Assets),
+            other => Err(anyhow!(
+                "unknown plugin op '{}' (expected fetchmedialist, fetchunits or fetchassets)",
+                other
+            )),
+        }
+    }
+}
+
+// `manga:<query>`, `anime:<query>` or `other:<kind>:<query>`; no recognized prefix means a
+// manga search for the whole string, so queries like "Re:Zero" pass through unchanged.
+#[cfg(feature = "debug-raw")]
+fn parse_raw_search_arg(arg: &str) -> (MediaType, &str) {
+    if let Some(query) = arg.strip_prefix("manga:") {
+        (MediaType::Manga, query)
+    } else if let Some(query) = arg.strip_prefix("anime:") {
+        (MediaType::Anime, query)
+    } else if let Some((kind, query)) = arg
+        .strip_prefix("other:")
+        .and_then(|rest| rest.split_once(':'))
+    {
+        (MediaType::Other(kind.to_string()), query)
+    } else {
+        (MediaType::Manga, arg)
+    }
+}
+
+// JSON shapes mirroring the WIT records field for field (kebab-case names, variants as
+// lowercase strings with `other:` payloads).
+#[cfg(feature = "debug-raw")]
+mod raw {
+    use super::{Asset, AssetKind, Media, MediaType, Unit, UnitKind};
+    use serde_json::{json, Value};
+
+    fn media_type(kind: &MediaType) -> String {
+        match kind {
+            MediaType::Manga => "manga".to_string(),
+            MediaType::Anime => "anime".to_string(),
+            MediaType::Other(s) => format!("other:{}", s),
+        }
+    }
+
+    fn unit_kind(kind: &UnitKind) -> String {
+        match kind {
+            UnitKind::Chapter => "chapter".to_string(),
+            UnitKind::Episode => "episode".to_string(),
+            UnitKind::Section => "section".to_string(),
+            UnitKind::Other(s) => format!("other:{}", s),
+        }
+    }
+
+    fn asset_kind(kind: &AssetKind) -> String {
+        match kind {
+            AssetKind::Page => "page".to_string(),
+            AssetKind::Image => "image".to_string(),
+            AssetKind::Audio => "audio".to_string(),
+            AssetKind::Video => "video".to_string(),
+            AssetKind::Subtitle => "subtitle".to_string(),
+            AssetKind::File => "file".to_string(),
+            AssetKind::Other(s) => format!("other:{}", s),
+        }
+    }
+
+    pub(super) fn media_json(m: &Media) -> Value {
+        json!({
+            "id": m.id,
+            "mediatype": media_type(&m.mediatype),
+            "title": m.title,
+            "description": m.description,
+            "url": m.url,
+            "cover-url": m.cover_url,
+        })
+    }
+
+    pub(super) fn unit_json(u: &Unit) -> Value {
+        json!({
+            "id": u.id,
+            "title": u.title,
+            "number-text": u.number_text,
+            "number": u.number,
+            "lang": u.lang,
+            "group": u.group,
+            "url": u.url,
+            "published-at": u.published_at,
+            "kind": unit_kind(&u.kind),
+        })
+    }
+
+    pub(super) fn asset_json(a: &Asset) -> Value {
+        json!({
+            "url": a.url,
+            "mime": a.mime,
+            "width": a.width,
+            "height": a.height,
+            "kind": asset_kind(&a.kind),
+        })
+    }
+}
+
+/// Health of one plugin for a source-status view. Times are unix seconds; history is kept
+/// in memory only and survives worker restarts but not plugin reloads.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PluginStatus {
+    pub name: String,
+    /// Instantiated with a live worker (plugins load lazily on first use)
+    pub loaded: bool,
+    /// Last fetch (search, units or assets) that returned `Ok`
+    pub last_success: Option<i64>,
+    /// Last fetch or instantiation error
+    pub last_error: Option<String>,
+    pub last_error_at: Option<i64>,
+}
+
+#[derive(Default)]
+struct PluginHealth {
+    last_success: Option<i64>,
+    last_error: Option<(i64, String)>,
+}
+
+impl PluginHealth {
+    fn record<T>(&mut self, result: &Result<T>) {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0);
+        match result {
+            Ok(_) => self.last_success = Some(now),
+            Err(e) => self.last_error = Some((now, format!("{e:#}"))),
+        }
+    }
+}
+
+type SharedHealth = Arc<std::sync::Mutex<PluginHealth>>;
+
+type BackendFactory = Arc<dyn Fn() -> Result<Box<dyn PluginBackend>> + Send + Sync>;
+
+// Where a slot gets its backend from when (re)instantiated
+enum SlotOrigin {
+    Artifacts(PluginArtifacts),
+    Embedded(EmbeddedPlugin),
+    #[cfg_attr(not(test), allow(dead_code))] // only registered by tests so far
+    Backend(BackendFactory),
+}
+
+// Run `backend` on a dedicated thread, serving commands until every sender is dropped.
+// If the backend panics or becomes poisoned the thread ends, the receiver closes, and the slot
+// replaces it on next use. Fetch outcomes are recorded in `health`.
+fn spawn_worker(mut backend: Box<dyn PluginBackend>, health: SharedHealth) -> PluginWorker {
+    let call_timeout = backend.call_timeout();
+    let (tx, mut rx) = mpsc::channel::<PluginCmd>(64);
+    std::thread::spawn(move || {
+        while let Some(cmd) = rx.blocking_recv() {
+            match cmd {
+                PluginCmd::FetchMediaList { kind, query, reply } => {
+                    let result = backend.fetch_media_list(kind, &query);
+                    health.lock().unwrap().record(&result);
+                    retire_if_poisoned(backend.as_ref(), &mut rx);
+                    let _ = reply.send(result);
+                }
+                PluginCmd::FetchUnits { media_id, reply } => {
+                    let result = backend.fetch_units(&media_id);
+                    health.lock().unwrap().record(&result);
+                    retire_if_poisoned(backend.as_ref(), &mut rx);
+                    let _ = reply.send(result);
+                }
+                PluginCmd::FetchAssets { unit_id, reply } => {
+                    let result = backend.fetch_assets(&unit_id);
+                    health.lock().unwrap().record(&result);
+                    retire_if_poisoned(backend.as_ref(), &mut rx);
+                    let _ = reply.send(result);
+                }
+                PluginCmd::GetCapabilities { refresh, reply } => {
+                    let result = backend.get_capabilities(refresh);
+                    retire_if_poisoned(backend.as_ref(), &mut rx);
+                    let _ = reply.send(result);
+                }
+                PluginCmd::ResetCaps => backend.reset_capabilities(),
+                PluginCmd::GetAllowedHosts { reply } => {
+                    let _ = reply.send(Ok(backend.allowed_hosts()));
+                }
+                PluginCmd::SetHostAllowed {
+                    host,
+                    allowed,
+                    reply,
+                } => {
+                    let _ = reply.send(backend.set_host_allowed(&host, allowed));
+                }
+                PluginCmd::UrlAllowed { url, reply } => {
+                    let _ = reply.send(Ok(backend.url_allowed(&url)));
+                }
+                #[cfg(feature = "debug-raw")]
+                PluginCmd::CallRaw { op, arg, reply } => {
+                    let result = backend.call_raw(op, &arg);
+                    retire_if_poisoned(backend.as_ref(), &mut rx);
+                    let _ = reply.send(result);
+                }
+            }
+            if backend.poisoned() {
+                // Commands still queued are dropped; their callers see the sender go away
+                break;
+            }
+        }
+    });
+    PluginWorker { tx, call_timeout }
+}
+
+// Close the channel before replying, so a caller that retries straight away already sees the
+// worker as dead and gets a fresh instance.
+fn retire_if_poisoned(backend: &dyn PluginBackend, rx: &mut mpsc::Receiver<PluginCmd>) {
+    if backend.poisoned() {
+        warn!("plugin call trapped; retiring instance");
+        rx.close();
+    }
+}
+
 struct PluginSlot {
     name: String,
-    artifacts: PluginArtifacts,
+    origin: SlotOrigin,
     engine: Arc<Engine>,
     epoch_ticks: Arc<AtomicU64>,
     epoch_interval: Duration,
+    host_limiter: Arc<HostRateLimiter>,
+    cancel: watch::Receiver<u64>,
     state: Mutex<Option<PluginWorker>>,
+    // Runtime allowed-host overrides (host, allowed), re-applied whenever the plugin is instantiated
+    host_overrides: std::sync::Mutex<Vec<(String, bool)>>,
+    health: SharedHealth,
+    // `languages` from the plugin TOML, lowercased
+    languages: Vec<String>,
 }
 
 #[derive(Default)]
@@ -100,40 +453,120 @@ impl ArtifactSet {
 impl PluginSlot {
     fn new(
         name: String,
-        artifacts: PluginArtifacts,
+        origin: SlotOrigin,
         engine: Arc<Engine>,
         epoch_ticks: Arc<AtomicU64>,
         epoch_interval: Duration,
+        host_limiter: Arc<HostRateLimiter>,
+        cancel: watch::Receiver<u64>,
     ) -> Self {
         Self {
             name,
-            artifacts,
+            origin,
             engine,
             epoch_ticks,
             epoch_interval,
+            host_limiter,
+            cancel,
             state: Mutex::new(None),
+            host_overrides: std::sync::Mutex::new(Vec::new()),
+            health: SharedHealth::default(),
+            languages: Vec::new(),
+        }
+    }
+
+    // A declared "pt" serves "pt-BR" requests and vice versa; otherwise tags must match
+    fn serves_language(&self, lang: &str) -> bool {
+        let primary = |tag: &str| {
+            tag.split(['-', '_'])
+                .next()
+                .unwrap_or(tag)
+                .to_ascii_lowercase()
+        };
+        let wanted = lang.trim().to_ascii_lowercase();
+        self.languages.iter().any(|declared| {
+            *declared == wanted
+                || (primary(declared) == primary(&wanted)
+                    && (!declared.contains(['-', '_']) || !wanted.contains(['-', '_'])))
+        })
+    }
+
+    async fn status(&self) -> PluginStatus {
+        let loaded = self
+            .state
+            .lock()
+            .await
+            .as_ref()
+            .is_some_and(|worker| !worker.tx.is_closed());
+        let health = self.health.lock().unwrap();
+        PluginStatus {
+            name: self.name.clone(),
+            loaded,
+            last_success: health.last_success,
+            last_error: health.last_error.as_ref().map(|(_, e)| e.clone()),
+            last_error_at: health.last_error.as_ref().map(|(at, _)| *at),
         }
     }
 
+    fn record_host_override(&self, host: &str, allowed: bool) {
+        let host = host.trim().to_ascii_lowercase();
+        let mut overrides = self.host_overrides.lock().unwrap();
+        overrides.retain(|(h, _)| h != &host);
+        overrides.push((host, allowed));
+    }
+
     fn name(&self) -> &str {
         &self.name
     }
 
     async fn worker(&self) -> Result<PluginWorker> {
+        let result = self.worker_inner().await;
+        if result.is_err() {
+            self.health.lock().unwrap().record(&result);
+        }
+        result
+    }
+
+    async fn worker_inner(&self) -> Result<PluginWorker> {
         let mut guard = self.state.lock().await;
         if let Some(worker) = guard.as_ref() {
-            return Ok(worker.clone());
+            if !worker.tx.is_closed() {
+                return Ok(worker.clone());
+            }
+            // The worker thread is gone (it panicked or retired a trapped instance); drop it and
+            // start a fresh instance
+            warn!(plugin=%self.name, "plugin worker died; re-instantiating");
+            *guard = None;
         }
 
-        let primary_path = self.artifacts.primary.clone();
+        let artifacts = match &self.origin {
+            SlotOrigin::Artifacts(artifacts) => artifacts,
+            SlotOrigin::Embedded(embedded) => {
+                let source = ComponentSource::Bytes {
+                    name: self.name.clone(),
+                    bytes: embedded.bytes.clone(),
+                    config: Box::new(embedded.config.clone()),
+                };
+                let worker = self.instantiate_source(source).await?;
+                info!(plugin=%self.name, "loaded embedded plugin");
+                *guard = Some(worker.clone());
+                return Ok(worker);
+            }
+            SlotOrigin::Backend(factory) => {
+                let worker = self.start(factory()?);
+                *guard = Some(worker.clone());
+                return Ok(worker);
+            }
+        };
+        let primary_path = artifacts.primary.clone();
         match self.instantiate(&primary_path).await {
             Ok(worker) => {
                 *guard = Some(worker.clone());
-                return Ok(worker);
+                Ok(worker)
             }
             Err(mut err) => {
                 warn!(plugin=%self.name, path=%primary_path.display(), error=?err, "failed to load plugin artifact");
-                if let Some(fallback_path) = &self.artifacts.fallback {
+                if let Some(fallback_path) = &artifacts.fallback {
                     warn!(plugin=%self.name, path=%fallback_path.display(), error=?err, "attempting fallback artifact");
                     match self.instantiate(fallback_path).await {
                         Ok(worker) => {
@@ -161,11 +594,20 @@ impl PluginSlot {
             return Err(anyhow!("missing plugin config: {}", cfg_path.display()));
         }
 
+        let worker = self
+            .instantiate_source(ComponentSource::File(path_buf.clone()))
+            .await?;
+        println!("Loaded plugin: {}", path_buf.display());
+        Ok(worker)
+    }
+
+    async fn instantiate_source(&self, source: ComponentSource) -> Result<PluginWorker> {
         let slot_name = self.name.clone();
         let engine = self.engine.clone();
         let epoch_ticks = self.epoch_ticks.clone();
         let interval = self.epoch_interval;
-        let path_to_load = path_buf.clone();
+        let host_limiter = self.host_limiter.clone();
+        let cancel = self.cancel.clone();
 
         let plugin = task::spawn_blocking(move || -> Result<Plugin> {
             let worker_threads = if cfg!(target_os = "ios") || cfg!(target_os = "android") {
@@ -181,9 +623,11 @@ impl PluginSlot {
             );
             let fut = Plugin::new_async(
                 &engine,
-                &path_to_load,
+                source,
                 epoch_ticks,
                 interval,
+                host_limiter,
+                cancel,
                 rt_arc.clone(),
             );
             rt_arc.block_on(fut)
@@ -197,38 +641,55 @@ impl PluginSlot {
             )
         })??;
 
-        let call_timeout = plugin.call_timeout;
-        let (tx, mut rx) = mpsc::channel::<PluginCmd>(64);
-        std::thread::spawn(move || {
-            let mut plugin = plugin;
-            while let Some(cmd) = rx.blocking_recv() {
-                match cmd {
-                    PluginCmd::FetchMediaList { kind, query, reply } => {
-                        let _ = reply.send(plugin.fetch_media_list(kind, &query));
-                    }
-                    PluginCmd::FetchUnits { media_id, reply } => {
-                        let _ = reply.send(plugin.fetch_units(&media_id));
-                    }
-                    PluginCmd::FetchAssets { unit_id, reply } => {
-                        let _ = reply.send(plugin.fetch_assets(&unit_id));
-                    }
-                    PluginCmd::GetCapabilities { refresh, reply } => {
-                        let res = if refresh {
-                            plugin.get_capabilities_refresh()
-                        } else {
-                            plugin.get_capabilities_cached()
-                        };
-                        let _ = reply.send(res);
-                    }
-                    PluginCmd::GetAllowedHosts { reply } => {
-                        let hosts = plugin.allowed_hosts.clone().unwrap_or_default();
-                        let _ = reply.send(Ok(hosts));
-                    }
-                }
+        Ok(self.start(Box::new(plugin)))
+    }
+
+    // Apply recorded host overrides and hand the backend to a fresh worker thread.
+    fn start(&self, mut backend: Box<dyn PluginBackend>) -> PluginWorker {
+        for (host, allowed) in self.host_overrides.lock().unwrap().iter() {
+            if let Err(e) = backend.set_host_allowed(host, *allowed) {
+                warn!(plugin=%self.name, host=%host, error=%e, "host override not applied");
             }
-        });
-        println!("Loaded plugin: {}", path_buf.display());
-        Ok(PluginWorker { tx, call_timeout })
+        }
+        spawn_worker(backend, self.health.clone())
+    }
+}
+
+// Failures (instantiation, send, timeout) are logged and count as "not allowed".
+async fn url_allowed_on(slot: &PluginSlot, url: &str) -> bool {
+    let worker = match slot.worker().await {
+        Ok(worker) => worker,
+        Err(e) => {
+            warn!(plugin=%slot.name(), error=%e, "failed to initialize plugin");
+            return false;
+        }
+    };
+    let (reply_tx, reply_rx) = oneshot::channel();
+    if let Err(e) = worker
+        .tx
+        .send(PluginCmd::UrlAllowed {
+            url: url.to_string(),
+            reply: reply_tx,
+        })
+        .await
+    {
+        warn!(plugin=%slot.name(), error=%e, "send error url_allowed");
+        return false;
+    }
+    match tokio::time::timeout(worker.call_timeout, reply_rx).await {
+        Ok(Ok(Ok(allowed))) => allowed,
+        Ok(Ok(Err(e))) => {
+            warn!(plugin=%slot.name(), error=%e, "url_allowed failed");
+            false
+        }
+        Ok(Err(_)) => {
+            warn!(plugin=%slot.name(), "url_allowed sender dropped");
+            false
+        }
+        Err(_) => {
+            warn!(plugin=%slot.name(), "url_allowed timeout");
+            false
+        }
     }
 }
 
@@ -239,6 +700,13 @@ pub struct PluginManager {
     slots: Vec<Arc<PluginSlot>>,
     epoch_ticks: Arc<AtomicU64>,
     epoch_interval: Duration,
+    host_limiter: Arc<HostRateLimiter>,
+    // Generation bumped by `cancel_all_calls`; every plugin holds a receiver
+    cancel: watch::Sender<u64>,
+    // When set, a search where every source failed is an error rather than an empty result
+    fail_when_all_sources_error: AtomicBool,
+    // Load `.cwasm` before `.wasm` when both exist
+    prefer_precompiled: AtomicBool,
     _epoch_stop: Arc<AtomicBool>,
     _epoch_thread: Option<std::thread::JoinHandle<()>>,
 }
@@ -292,18 +760,33 @@ impl PluginManager {
             slots: Vec::new(),
             epoch_ticks,
             epoch_interval,
+            host_limiter: Arc::new(HostRateLimiter::from_env()),
+            cancel: watch::channel(0).0,
+            fail_when_all_sources_error: AtomicBool::new(true),
+            prefer_precompiled: AtomicBool::new(prefer_precompiled_from_env()),
             _epoch_stop: epoch_stop,
             _epoch_thread: Some(handle),
         })
     }
 
+    /// Abort every plugin call in progress: wasm execution traps at the next epoch tick and
+    /// pending HTTP requests fail, so the calls return `TouringError::Cancelled`. A trapped
+    /// plugin is re-instantiated on its next call, so later calls are unaffected; calls queued
+    /// behind the cancelled one on the same plugin fail.
+    pub fn cancel_all_calls(&self) {
+        self.cancel.send_modify(|generation| *generation += 1);
+    }
+
+    /// Register every plugin in `dir`, replacing previously loaded directory plugins.
+    /// Embedded plugins stay registered unless a directory plugin has the same name.
     pub async fn load_plugins_from_directory(&mut self, dir: &Path) -> Result<()> {
-        self.slots.clear();
+        self.slots
+            .retain(|s| matches!(s.origin, SlotOrigin::Embedded(_)));
         if !dir.exists() {
             println!("Plugin directory does not exist: {}", dir.display());
             return Ok(());
         }
-        let prefer_precompiled = !cfg!(target_os = "android");
+        let prefer_precompiled = self.prefer_precompiled();
         let mut artifacts_by_name: HashMap<String, ArtifactSet> = HashMap::new();
 
         for entry in std::fs::read_dir(dir)? {
@@ -333,14 +816,18 @@ impl PluginManager {
                 warn!(plugin=%name, config=%cfg_path.display(), "rejecting plugin: missing .toml config");
                 continue;
             }
-            let slot = PluginSlot::new(
+            let mut slot = PluginSlot::new(
                 name.clone(),
-                artifacts,
+                SlotOrigin::Artifacts(artifacts),
                 self.engine.clone(),
                 self.epoch_ticks.clone(),
                 self.epoch_interval,
+                self.host_limiter.clone(),
+                self.cancel.subscribe(),
             );
+            slot.languages = config::read_languages(&cfg_path);
             debug!(plugin=%name, "registered plugin for lazy loading");
+            self.slots.retain(|s| s.name() != name);
             self.slots.push(Arc::new(slot));
         }
 
@@ -348,6 +835,140 @@ impl PluginManager {
         Ok(())
     }
 
+    /// Register a plugin from in-memory component bytes (plain `.wasm` or precompiled `.cwasm`
+    /// for this engine) for platforms where plugins ship inside the app package. It is
+    /// instantiated lazily like directory plugins and replaces a plugin with the same name.
+    pub fn load_plugin_bytes(
+        &mut self,
+        name: &str,
+        wasm: &[u8],
+        config: PluginConfig,
+    ) -> Result<()> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(anyhow!("plugin name must not be empty"));
+        }
+        match Engine::detect_precompiled(wasm) {
+            Some(wasmtime::Precompiled::Component) => {}
+            Some(wasmtime::Precompiled::Module) => {
+                return Err(anyhow!(
+                    "plugin {name}: precompiled core module, expected a component"
+                ))
+            }
+            None if !wasm.starts_with(b"\0asm") => {
+                return Err(anyhow!("plugin {name}: bytes are not a wasm component"))
+            }
+            None => {}
+        }
+        self.register_embedded(
+            name,
+            EmbeddedPlugin {
+                bytes: Arc::from(wasm),
+                config,
+            },
+        );
+        Ok(())
+    }
+
+    /// Re-register `other`'s embedded plugins here (used when the runtime is rebuilt).
+    pub fn adopt_embedded_plugins(&mut self, other: &PluginManager) {
+        for slot in &other.slots {
+            if let SlotOrigin::Embedded(embedded) = &slot.origin {
+                self.register_embedded(slot.name(), embedded.clone());
+            }
+        }
+    }
+
+    fn register_embedded(&mut self, name: &str, embedded: EmbeddedPlugin) {
+        let languages = embedded.config.normalized_languages();
+        let mut slot = PluginSlot::new(
+            name.to_string(),
+            SlotOrigin::Embedded(embedded),
+            self.engine.clone(),
+            self.epoch_ticks.clone(),
+            self.epoch_interval,
+            self.host_limiter.clone(),
+            self.cancel.subscribe(),
+        );
+        slot.languages = languages;
+        debug!(plugin=%name, "registered embedded plugin for lazy loading");
+        self.slots.retain(|s| s.name() != name);
+        self.slots.push(Arc::new(slot));
+        self.slots.sort_by(|a, b| a.name().cmp(b.name()));
+    }
+
+    // Register an in-process backend under `name`; instantiated lazily like wasm plugins.
+    #[cfg(test)]
+    fn register_backend(&mut self, name: &str, factory: BackendFactory) {
+        let slot = PluginSlot::new(
+            name.to_string(),
+            SlotOrigin::Backend(factory),
+            self.engine.clone(),
+            self.epoch_ticks.clone(),
+            self.epoch_interval,
+            self.host_limiter.clone(),
+            self.cancel.subscribe(),
+        );
+        self.slots.retain(|s| s.name() != name);
+        self.slots.push(Arc::new(slot));
+        self.slots.sort_by(|a, b| a.name().cmp(b.name()));
+    }
+
+    /// Register canned test data as source `name` (replaces a plugin with the same name).
+    #[cfg(test)]
+    pub(crate) fn register_mock_source(&mut self, name: &str, source: mock::MockSource) {
+        self.register_backend(
+            name,
+            Arc::new(move || Ok(Box::new(source.clone()) as Box<dyn PluginBackend>)),
+        );
+    }
+
+    /// Instantiate every plugin concurrently (normally done lazily on first use).
+    /// Returns each plugin's name with the outcome.
+    pub async fn warm_all(&self) -> Vec<(String, Result<()>)> {
+        let futures = self.slots.iter().map(|slot| async move {
+            let res = slot.worker().await.map(|_| ());
+            if let Err(e) = &res {
+                warn!(plugin=%slot.name(), error=%e, "plugin warm-up failed");
+            }
+            (slot.name().to_string(), res)
+        });
+        futures::future::join_all(futures).await
+    }
+
+    pub fn prefer_precompiled(&self) -> bool {
+        self.prefer_precompiled.load(Ordering::Relaxed)
+    }
+
+    /// Choose `.cwasm` (true) or `.wasm` (false) as the primary artifact when both exist;
+    /// the other remains the fallback. Takes effect on the next directory load.
+    pub fn set_prefer_precompiled(&self, prefer: bool) {
+        self.prefer_precompiled.store(prefer, Ordering::Relaxed);
+    }
+
+    /// Per-host limiter shared by all plugin HTTP traffic.
+    pub fn host_rate_limiter(&self) -> Arc<HostRateLimiter> {
+        self.host_limiter.clone()
+    }
+
+    /// Share an existing limiter (e.g. to keep limiter state across a plugin reload).
+    /// Applies to plugins registered after this call.
+    pub fn set_host_rate_limiter(&mut self, limiter: Arc<HostRateLimiter>) {
+        self.host_limiter = limiter;
+    }
+
+    /// Whether multi-source searches return `Err` when every source failed (errored, timed out
+    /// or could not be initialized). Enabled by default; when disabled such a search yields an
+    /// empty list, indistinguishable from "no matches".
+    pub fn set_fail_when_all_sources_error(&self, enabled: bool) {
+        self.fail_when_all_sources_error
+            .store(enabled, Ordering::Relaxed);
+    }
+
+    pub fn fail_when_all_sources_error(&self) -> bool {
+        self.fail_when_all_sources_error.load(Ordering::Relaxed)
+    }
+
     pub fn list_plugins(&self) -> Vec<String> {
         self.slots
             .iter()
@@ -355,6 +976,33 @@ impl PluginManager {
             .collect()
     }
 
+    /// Languages plugin `name` declares in its TOML (`languages`); empty if none or unknown.
+    pub fn plugin_languages(&self, name: &str) -> Vec<String> {
+        self.slots
+            .iter()
+            .find(|slot| slot.name() == name)
+            .map(|slot| slot.languages.clone())
+            .unwrap_or_default()
+    }
+
+    /// Whether plugin `name` declares `lang`. A bare language and a regional variant match
+    /// each other ("pt" and "pt-BR"); two different regions don't.
+    pub fn plugin_serves_language(&self, name: &str, lang: &str) -> bool {
+        self.slots
+            .iter()
+            .find(|slot| slot.name() == name)
+            .is_some_and(|slot| slot.serves_language(lang))
+    }
+
+    /// Load state and last fetch success/error of every plugin, without instantiating any.
+    pub async fn get_plugin_status(&self) -> Vec<PluginStatus> {
+        let mut out = Vec::with_capacity(self.slots.len());
+        for slot in &self.slots {
+            out.push(slot.status().await);
+        }
+        out
+    }
+
     pub async fn get_capabilities(
         &self,
         refresh: bool,
@@ -393,6 +1041,58 @@ impl PluginManager {
         Ok(out)
     }
 
+    /// Capabilities of a single plugin; `Ok(None)` if no plugin with that name is loaded.
+    pub async fn get_capabilities_for(
+        &self,
+        name: &str,
+        refresh: bool,
+    ) -> Result<Option<ProviderCapabilities>> {
+        let Some(slot) = self.slots.iter().find(|slot| slot.name() == name).cloned() else {
+            return Ok(None);
+        };
+        let worker = slot
+            .worker()
+            .await
+            .map_err(|e| anyhow!("failed to initialize plugin {}: {}", name, e))?;
+        let (reply_tx, reply_rx) = oneshot::channel();
+        worker
+            .tx
+            .send(PluginCmd::GetCapabilities {
+                refresh,
+                reply: reply_tx,
+            })
+            .await
+            .map_err(|e| anyhow!("send error: {}", e))?;
+        match tokio::time::timeout(worker.call_timeout, reply_rx).await {
+            Ok(Ok(res)) => res.map(Some),
+            Ok(Err(_)) => Err(anyhow!("sender dropped")),
+            Err(_) => Err(anyhow!("timeout after {:?}", worker.call_timeout)),
+        }
+    }
+
+    /// Forget the cached capabilities of plugin `name` (all plugins for `None`) so the next
+    /// capabilities call re-queries it. Plugins that aren't instantiated have nothing cached.
+    pub async fn reset_capabilities_cache(&self, name: Option<&str>) -> Result<()> {
+        if let Some(name) = name {
+            if !self.slots.iter().any(|slot| slot.name() == name) {
+                return Err(anyhow!("plugin not loaded: {}", name));
+            }
+        }
+        for slot in &self.slots {
+            if name.is_some_and(|n| n != slot.name()) {
+                continue;
+            }
+            let Some(worker) = slot.state.lock().await.clone() else {
+                continue;
+            };
+            // A dead worker is replaced on next use, starting with empty caps anyway
+            if worker.tx.send(PluginCmd::ResetCaps).await.is_err() {
+                debug!(plugin=%slot.name(), "reset_capabilities: worker gone");
+            }
+        }
+        Ok(())
+    }
+
     pub async fn get_allowed_hosts(&self) -> Result<Vec<(String, Vec<String>)>> {
         let mut out = Vec::new();
         for slot_arc in &self.slots {
@@ -425,6 +1125,84 @@ impl PluginManager {
         Ok(out)
     }
 
+    /// Allowed hosts of one plugin, instantiating only that plugin. `None` if not loaded.
+    pub async fn get_plugin_allowed_hosts(&self, name: &str) -> Result<Option<Vec<String>>> {
+        let Some(slot) = self.slots.iter().find(|slot| slot.name() == name).cloned() else {
+            return Ok(None);
+        };
+        let worker = slot
+            .worker()
+            .await
+            .map_err(|e| anyhow!("failed to initialize plugin {}: {}", name, e))?;
+        let (reply_tx, reply_rx) = oneshot::channel();
+        worker
+            .tx
+            .send(PluginCmd::GetAllowedHosts { reply: reply_tx })
+            .await
+            .map_err(|e| anyhow!("send error: {}", e))?;
+        match tokio::time::timeout(worker.call_timeout, reply_rx).await {
+            Ok(Ok(Ok(hosts))) => Ok(Some(hosts)),
+            Ok(Ok(Err(e))) => Err(e),
+            Ok(Err(_)) => Err(anyhow!("sender dropped")),
+            Err(_) => Err(anyhow!("timeout after {:?}", worker.call_timeout)),
+        }
+    }
+
+    /// True if at least one loaded plugin's allow-list permits `url`.
+    /// Used to vet host-side requests (e.g. stream probing) against plugin policy.
+    pub async fn url_allowed_by_any(&self, url: &str) -> Result<bool> {
+        for slot in &self.slots {
+            if url_allowed_on(slot, url).await {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Whether plugin `name` permits `url`; false if no such plugin is loaded.
+    pub async fn url_allowed_by_plugin(&self, name: &str, url: &str) -> Result<bool> {
+        match self.slots.iter().find(|slot| slot.name() == name) {
+            Some(slot) => Ok(url_allowed_on(slot, url).await),
+            None => Ok(false),
+        }
+    }
+
+    /// Apply an allowed-host override to a plugin (instantiating it if needed, so an override
+    /// that would empty its allow-list is rejected up front) and record it for restarts.
+    pub async fn set_host_allowed(&self, plugin: &str, host: &str, allowed: bool) -> Result<()> {
+        let slot = self
+            .slots
+            .iter()
+            .find(|slot| slot.name() == plugin)
+            .ok_or_else(|| anyhow!("plugin not loaded: {}", plugin))?;
+        let worker = slot.worker().await?;
+        let (reply_tx, reply_rx) = oneshot::channel();
+        worker
+            .tx
+            .send(PluginCmd::SetHostAllowed {
+                host: host.to_string(),
+                allowed,
+                reply: reply_tx,
+            })
+            .await
+            .map_err(|e| anyhow!("send error: {}", e))?;
+        match tokio::time::timeout(worker.call_timeout, reply_rx).await {
+            Ok(Ok(res)) => res?,
+            Ok(Err(_)) => return Err(anyhow!("sender dropped")),
+            Err(_) => return Err(anyhow!("timeout after {:?}", worker.call_timeout)),
+        };
+        slot.record_host_override(host, allowed);
+        Ok(())
+    }
+
+    /// Record a persisted host override without instantiating the plugin; it is applied when
+    /// the worker starts (overrides the plugin rejects are logged and skipped).
+    pub(crate) fn restore_host_override(&self, plugin: &str, host: &str, allowed: bool) {
+        if let Some(slot) = self.slots.iter().find(|slot| slot.name() == plugin) {
+            slot.record_host_override(host, allowed);
+        }
+    }
+
     pub async fn search_manga_with_sources(&self, query: &str) -> Result<Vec<(String, Media)>> {
         self.search_with_sources(MediaType::Manga, query).await
     }
@@ -441,6 +1219,16 @@ impl PluginManager {
         self.search_for(MediaType::Anime, source, query).await
     }
 
+    /// Search one named plugin for any media type (including `MediaType::Other`).
+    pub async fn search_kind_for(
+        &self,
+        kind: MediaType,
+        source: &str,
+        query: &str,
+    ) -> Result<Vec<Media>> {
+        self.search_for(kind, source, query).await
+    }
+
     // Generic internal helpers ------------------------------------------------------
     async fn search_with_sources(
         &self,
@@ -492,12 +1280,13 @@ impl PluginManager {
                 }
             });
         }
+        let results = futures::future::join_all(futures).await;
+        let failed = results.iter().filter(|r| r.is_none()).count();
+        if failed > 0 && failed == results.len() && self.fail_when_all_sources_error() {
+            return Err(anyhow!("search failed: all {} sources errored", failed));
+        }
         let mut all = Vec::new();
-        for r in futures::future::join_all(futures)
-            .await
-            .into_iter()
-            .flatten()
-        {
+        for r in results.into_iter().flatten() {
             let (name, list) = r;
             debug!(plugin=%name, kind=?kind, query, count=list.len(), "search results");
             for m in list {
@@ -533,7 +1322,7 @@ impl PluginManager {
                     debug!(plugin=%source, kind=?kind, query, count=v.len(), "search_for results");
                     Ok(v)
                 }
-                Ok(Ok(Err(e))) => Err(anyhow!("{}", e)),
+                Ok(Ok(Err(e))) => Err(e),
                 Ok(Err(_)) => Err(anyhow!("sender dropped")),
                 Err(_) => Err(anyhow!("timeout after {:?}", call_timeout)),
             }
@@ -541,6 +1330,69 @@ impl PluginManager {
             Ok(Vec::new())
         }
     }
+    /// Fetch units for `media_id` from one named plugin (no fallback across sources).
+    pub async fn get_units_for(&self, source: &str, media_id: &str) -> Result<Vec<Unit>> {
+        let slot = self
+            .slots
+            .iter()
+            .find(|slot| slot.name() == source)
+            .cloned()
+            .ok_or_else(|| anyhow!("plugin not loaded: {}", source))?;
+        let worker = slot
+            .worker()
+            .await
+            .map_err(|e| anyhow!("failed to initialize plugin {}: {}", source, e))?;
+        let (reply_tx, reply_rx) = oneshot::channel();
+        worker
+            .tx
+            .send(PluginCmd::FetchUnits {
+                media_id: media_id.to_string(),
+                reply: reply_tx,
+            })
+            .await
+            .map_err(|e| anyhow!("send error: {}", e))?;
+        match tokio::time::timeout(worker.call_timeout, reply_rx).await {
+            Ok(Ok(res)) => res,
+            Ok(Err(_)) => Err(anyhow!("sender dropped")),
+            Err(_) => Err(anyhow!("timeout after {:?}", worker.call_timeout)),
+        }
+    }
+
+    /// Call one export of a named plugin and return exactly what the wasm produced as JSON:
+    /// no sentinel suppression, no allowed-hosts URL nulling, no cache, no persistence.
+    /// `op` is `fetchmedialist` (`arg` is `manga:<query>`, `anime:<query>`,
+    /// `other:<kind>:<query>` or a bare manga query), `fetchunits` (media id) or
+    /// `fetchassets` (unit id). Built only with the `debug-raw` feature.
+    #[cfg(feature = "debug-raw")]
+    pub async fn plugin_call_raw(&self, source: &str, op: &str, arg: &str) -> Result<String> {
+        let op: RawOp = op.parse()?;
+        let slot = self
+            .slots
+            .iter()
+            .find(|slot| slot.name() == source)
+            .cloned()
+            .ok_or_else(|| anyhow!("plugin not loaded: {}", source))?;
+        let worker = slot
+            .worker()
+            .await
+            .map_err(|e| anyhow!("failed to initialize plugin {}: {}", source, e))?;
+        let (reply_tx, reply_rx) = oneshot::channel();
+        worker
+            .tx
+            .send(PluginCmd::CallRaw {
+                op,
+                arg: arg.to_string(),
+                reply: reply_tx,
+            })
+            .await
+            .map_err(|e| anyhow!("send error: {}", e))?;
+        match tokio::time::timeout(worker.call_timeout, reply_rx).await {
+            Ok(Ok(res)) => res,
+            Ok(Err(_)) => Err(anyhow!("sender dropped")),
+            Err(_) => Err(anyhow!("timeout after {:?}", worker.call_timeout)),
+        }
+    }
+
     pub async fn get_manga_chapters_with_source(
         &self,
         manga_id: &str,
@@ -586,11 +1438,65 @@ impl PluginManager {
         Ok((None, Vec::new()))
     }
 
+    /// Units for `media_id` from the first plugin that reports any `Section`. Returns the
+    /// full unit list (sections and their children) so callers can group them.
+    pub async fn get_sections_with_source(
+        &self,
+        media_id: &str,
+    ) -> Result<(Option<String>, Vec<Unit>)> {
+        for slot_arc in &self.slots {
+            let slot = slot_arc.clone();
+            let worker = match slot.worker().await {
+                Ok(worker) => worker,
+                Err(e) => {
+                    warn!(plugin=%slot.name(), error=%e, "failed to initialize plugin");
+                    continue;
+                }
+            };
+            let name = slot.name().to_string();
+            let call_timeout = worker.call_timeout;
+            let tx = worker.tx.clone();
+            let (reply_tx, reply_rx) = oneshot::channel();
+            if let Err(e) = tx
+                .send(PluginCmd::FetchUnits {
+                    media_id: media_id.to_string(),
+                    reply: reply_tx,
+                })
+                .await
+            {
+                warn!(plugin=%name, error=%e, "send error get_sections_with_source");
+                continue;
+            }
+            match tokio::time::timeout(call_timeout, reply_rx).await {
+                Ok(Ok(Ok(units))) => {
+                    if units.iter().any(|u| matches!(u.kind, UnitKind::Section)) {
+                        return Ok((Some(name), units));
+                    }
+                }
+                Ok(Ok(Err(e))) => warn!(plugin=%name, error=%e, "fetchunits failed"),
+                Ok(Err(_)) => warn!(plugin=%name, "fetchunits sender dropped"),
+                Err(_) => warn!(plugin=%name, "fetchunits timeout"),
+            }
+        }
+        Ok((None, Vec::new()))
+    }
+
     pub async fn get_chapter_images_with_source(
         &self,
         chapter_id: &str,
     ) -> Result<(Option<String>, Vec<String>)> {
-        for slot_arc in &self.slots {
+        self.get_chapter_images_with_source_in_order(chapter_id, &[])
+            .await
+    }
+
+    /// Like `get_chapter_images_with_source`, trying the `preferred` plugins first (in that
+    /// order) and the remaining ones after them in the usual name order. Unknown names are ignored.
+    pub async fn get_chapter_images_with_source_in_order(
+        &self,
+        chapter_id: &str,
+        preferred: &[String],
+    ) -> Result<(Option<String>, Vec<String>)> {
+        for slot_arc in &self.slots_in_order(preferred) {
             let slot = slot_arc.clone();
             let worker = match slot.worker().await {
                 Ok(worker) => worker,
@@ -632,6 +1538,21 @@ impl PluginManager {
         Ok((None, Vec::new()))
     }
 
+    fn slots_in_order(&self, preferred: &[String]) -> Vec<Arc<PluginSlot>> {
+        let mut ordered: Vec<_> = preferred
+            .iter()
+            .filter_map(|name| self.slots.iter().find(|slot| slot.name() == name))
+            .cloned()
+            .collect();
+        ordered.extend(
+            self.slots
+                .iter()
+                .filter(|slot| !preferred.iter().any(|name| name == slot.name()))
+                .cloned(),
+        );
+        ordered
+    }
+
     pub async fn get_anime_episodes_with_source(
         &self,
         anime_id: &str,
@@ -732,3 +1653,215 @@ impl Drop for PluginManager {
         }
     }
 }
+
+// `TOURING_PREFER_PRECOMPILED=1|0|true|false`; defaults to precompiled everywhere but Android.
+fn prefer_precompiled_from_env() -> bool {
+    match std::env::var("TOURING_PREFER_PRECOMPILED")
+        .map(|v| v.trim().to_ascii_lowercase())
+        .as_deref()
+    {
+        Ok("1") | Ok("true") | Ok("yes") => true,
+        Ok("0") | Ok("false") | Ok("no") => false,
+        _ => !cfg!(target_os = "android"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::TouringError;
+    use std::sync::atomic::AtomicUsize;
+
+    // Panics on its first fetch_units call across all instances, then behaves.
+    struct FlakyBackend {
+        calls: Arc<AtomicUsize>,
+    }
+
+    impl PluginBackend for FlakyBackend {
+        fn call_timeout(&self) -> Duration {
+            Duration::from_secs(5)
+        }
+        fn fetch_media_list(&mut self, _kind: MediaType, _query: &str) -> Result<Vec<Media>> {
+            Ok(Vec::new())
+        }
+        fn fetch_units(&mut self, media_id: &str) -> Result<Vec<Unit>> {
+            if self.calls.fetch_add(1, Ordering::SeqCst) == 0 {
+                panic!("simulated plugin crash");
+            }
+            Ok(vec![Unit {
+                id: format!("{media_id}-1"),
+                title: String::new(),
+                number_text: None,
+                number: Some(1.0),
+                lang: None,
+                group: None,
+                url: None,
+                published_at: None,
+                kind: UnitKind::Episode,
+            }])
+        }
+        fn fetch_assets(&mut self, _unit_id: &str) -> Result<Vec<Asset>> {
+            Ok(Vec::new())
+        }
+        fn get_capabilities(&mut self, _refresh: bool) -> Result<ProviderCapabilities> {
+            Err(anyhow!("unsupported"))
+        }
+        fn allowed_hosts(&self) -> Vec<String> {
+            Vec::new()
+        }
+        fn set_host_allowed(&mut self, _host: &str, _allowed: bool) -> Result<Vec<String>> {
+            Ok(Vec::new())
+        }
+        fn url_allowed(&self, _url: &str) -> bool {
+            false
+        }
+    }
+
+    #[tokio::test]
+    async fn dead_worker_is_replaced_on_next_call() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let instances = Arc::new(AtomicUsize::new(0));
+        let mut pm = PluginManager::new().unwrap();
+        let (c, n) = (calls.clone(), instances.clone());
+        pm.register_backend(
+            "flaky",
+            Arc::new(move || {
+                n.fetch_add(1, Ordering::SeqCst);
+                Ok(Box::new(FlakyBackend { calls: c.clone() }) as Box<dyn PluginBackend>)
+            }),
+        );
+
+        assert!(pm.get_units_for("flaky", "m").await.is_err());
+        // Give the panicking thread a moment to unwind and drop its receiver
+        for _ in 0..50 {
+            let dead = pm.slots[0]
+                .state
+                .lock()
+                .await
+                .as_ref()
+                .is_some_and(|w| w.tx.is_closed());
+            if dead {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        let units = pm.get_units_for("flaky", "m").await.unwrap();
+        assert_eq!(units.len(), 1);
+        assert_eq!(instances.load(Ordering::SeqCst), 2);
+    }
+
+    // Mimics a wasm instance: the first call overall runs until cancelled, then the instance
+    // is poisoned and refuses further calls, like wasmtime after a trap.
+    struct TrappingBackend {
+        calls: Arc<AtomicUsize>,
+        cancel: watch::Receiver<u64>,
+        poisoned: bool,
+    }
+
+    impl PluginBackend for TrappingBackend {
+        fn call_timeout(&self) -> Duration {
+            Duration::from_secs(5)
+        }
+        fn fetch_media_list(&mut self, _kind: MediaType, _query: &str) -> Result<Vec<Media>> {
+            Ok(Vec::new())
+        }
+        fn fetch_units(&mut self, media_id: &str) -> Result<Vec<Unit>> {
+            if self.poisoned {
+                return Err(anyhow!("cannot enter component instance"));
+            }
+            if self.calls.fetch_add(1, Ordering::SeqCst) == 0 {
+                let generation = *self.cancel.borrow();
+                while *self.cancel.borrow() == generation {
+                    std::thread::sleep(Duration::from_millis(5));
+                }
+                self.poisoned = true;
+                return Err(TouringError::Cancelled {
+                    operation: "fetchunits".to_string(),
+                }
+                .into());
+            }
+            Ok(vec![Unit {
+                id: format!("{media_id}-1"),
+                title: String::new(),
+                number_text: None,
+                number: Some(1.0),
+                lang: None,
+                group: None,
+                url: None,
+                published_at: None,
+                kind: UnitKind::Episode,
+            }])
+        }
+        fn fetch_assets(&mut self, _unit_id: &str) -> Result<Vec<Asset>> {
+            Ok(Vec::new())
+        }
+        fn get_capabilities(&mut self, _refresh: bool) -> Result<ProviderCapabilities> {
+            Err(anyhow!("unsupported"))
+        }
+        fn allowed_hosts(&self) -> Vec<String> {
+            Vec::new()
+        }
+        fn set_host_allowed(&mut self, _host: &str, _allowed: bool) -> Result<Vec<String>> {
+            Ok(Vec::new())
+        }
+        fn url_allowed(&self, _url: &str) -> bool {
+            false
+        }
+        fn poisoned(&self) -> bool {
+            self.poisoned
+        }
+    }
+
+    #[tokio::test]
+    async fn calls_succeed_after_cancel_all_calls() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let instances = Arc::new(AtomicUsize::new(0));
+        let mut pm = PluginManager::new().unwrap();
+        let (c, n, cancel) = (calls.clone(), instances.clone(), pm.cancel.subscribe());
+        pm.register_backend(
+            "trapping",
+            Arc::new(move || {
+                n.fetch_add(1, Ordering::SeqCst);
+                Ok(Box::new(TrappingBackend {
+                    calls: c.clone(),
+                    cancel: cancel.clone(),
+                    poisoned: false,
+                }) as Box<dyn PluginBackend>)
+            }),
+        );
+        let pm = Arc::new(pm);
+
+        let inflight = tokio::spawn({
+            let pm = pm.clone();
+            async move { pm.get_units_for("trapping", "m").await }
+        });
+        while calls.load(Ordering::SeqCst) == 0 {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        pm.cancel_all_calls();
+        assert!(inflight.await.unwrap().is_err());
+
+        let units = pm.get_units_for("trapping", "m").await.unwrap();
+        assert_eq!(units.len(), 1);
+        assert_eq!(instances.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn embedded_plugins_survive_directory_loads() {
+        let mut pm = PluginManager::new().unwrap();
+        let config = PluginConfig::from_toml("languages = [\"EN\"]").unwrap();
+        assert!(pm
+            .load_plugin_bytes("bundled", b"not wasm", config.clone())
+            .is_err());
+        // Component preamble only: registration is lazy, so compilation fails on first use
+        pm.load_plugin_bytes("bundled", b"\0asm\x0d\x00\x01\x00", config)
+            .unwrap();
+        pm.load_plugins_from_directory(Path::new("/nonexistent-plugin-dir"))
+            .await
+            .unwrap();
+        assert_eq!(pm.list_plugins(), vec!["bundled".to_string()]);
+        assert_eq!(pm.plugin_languages("bundled"), vec!["en".to_string()]);
+        assert!(pm.get_units_for("bundled", "m").await.is_err());
+    }
+}
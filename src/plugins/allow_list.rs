@@ -0,0 +1,132 @@
+//! Allowed-host entries and matching. Entries are compared in canonical form: lowercase ASCII
+//! (IDN hosts as punycode, like parsed URLs), no scheme, path or trailing dot. `*.cdn.com`
+//! matches `cdn.com` and its subdomains. An entry without a port allows any port; `host:port`
+//! allows only that port (default ports count, so `example.com:443` matches `https://example.com`).
+
+use anyhow::{anyhow, Result};
+use url::{Host, Url};
+
+/// Canonical form of an allow-list entry; `None` if it doesn't name a host.
+pub(crate) fn normalize_host_entry(entry: &str) -> Option<String> {
+    let mut s = entry.trim();
+    if let Some((_, rest)) = s.split_once("://") {
+        s = rest;
+    }
+    let s = s.split(['/', '?', '#']).next().unwrap_or_default();
+    let (wildcard, s) = match s.strip_prefix("*.") {
+        Some(rest) => ("*.", rest),
+        None => ("", s),
+    };
+    let (host, port) = split_port(s)?;
+    let host = match Host::parse(host.trim_end_matches('.')).ok()? {
+        Host::Domain(domain) => domain,
+        Host::Ipv4(ip) => ip.to_string(),
+        Host::Ipv6(ip) => format!("[{ip}]"),
+    };
+    if host.is_empty() {
+        return None;
+    }
+    Some(match port {
+        Some(port) => format!("{wildcard}{host}:{port}"),
+        None => format!("{wildcard}{host}"),
+    })
+}
+
+/// Whether an http(s) `url` is covered by one of the (normalized) `entries`.
+pub(crate) fn url_matches(entries: &[String], url: &str) -> bool {
+    let Ok(parsed) = Url::parse(url) else {
+        return false;
+    };
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return false;
+    }
+    let Some(host) = parsed.host_str() else {
+        return false;
+    };
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    let port = parsed.port_or_known_default();
+    entries.iter().any(|entry| {
+        let Some((pattern, wanted_port)) = split_port(entry) else {
+            return false;
+        };
+        if wanted_port.is_some() && wanted_port != port {
+            return false;
+        }
+        match pattern.strip_prefix("*.") {
+            Some(suffix) => host == suffix || host.ends_with(&format!(".{suffix}")),
+            None => host == pattern,
+        }
+    })
+}
+
+/// Add or remove `host` in a live allow-list; `None` (all hosts allowed) is left untouched.
+/// Removing the last entry is rejected, since an empty list denies every host.
+pub(crate) fn set_entry(list: &mut Option<Vec<String>>, host: &str, allowed: bool) -> Result<()> {
+    let host = normalize_host_entry(host).ok_or_else(|| anyhow!("invalid host: {}", host))?;
+    let Some(list) = list else {
+        return Ok(());
+    };
+    if !allowed && !list.is_empty() && list.iter().all(|h| h == &host) {
+        return Err(anyhow!(
+            "refusing to remove {}: it is the last allowed host",
+            host
+        ));
+    }
+    list.retain(|h| h != &host);
+    if allowed {
+        list.push(host);
+    }
+    Ok(())
+}
+
+// `host[:port]`, with IPv6 literals in brackets; `None` for an unparseable port
+fn split_port(s: &str) -> Option<(&str, Option<u16>)> {
+    let (host, port) = if s.starts_with('[') {
+        let end = s.find(']')?;
+        (&s[..=end], s[end + 1..].strip_prefix(':'))
+    } else if s.matches(':').count() == 1 {
+        let (host, port) = s.split_once(':')?;
+        (host, Some(port))
+    } else {
+        (s, None)
+    };
+    let port = port.map(str::parse::<u16>).transpose().ok()?;
+    Some((host, port))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_ports_wildcards_and_idn_hosts() {
+        let entries: Vec<String> = [
+            "*.cdn.com",
+            "HTTPS://Example.org/",
+            "bücher.de",
+            "api.example.net:8443",
+        ]
+        .iter()
+        .filter_map(|e| normalize_host_entry(e))
+        .collect();
+        assert_eq!(
+            entries,
+            [
+                "*.cdn.com",
+                "example.org",
+                "xn--bcher-kva.de",
+                "api.example.net:8443"
+            ]
+        );
+
+        assert!(url_matches(&entries, "https://img.cdn.com:443/a.jpg"));
+        assert!(url_matches(&entries, "http://cdn.com:8080/"));
+        assert!(url_matches(&entries, "https://example.org./x"));
+        assert!(url_matches(&entries, "https://BÜCHER.de/cover.png"));
+        assert!(url_matches(&entries, "https://api.example.net:8443/v1"));
+        assert!(!url_matches(&entries, "https://api.example.net/v1"));
+        assert!(!url_matches(&entries, "https://notcdn.com/"));
+        assert!(!url_matches(&entries, "ftp://img.cdn.com/"));
+        assert_eq!(normalize_host_entry("host:notaport"), None);
+    }
+}
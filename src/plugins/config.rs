@@ -1,11 +1,68 @@
 use serde::Deserialize;
+use std::collections::HashMap;
 
+/// A plugin's settings, normally read from the `.toml` next to its artifact. Embedded plugins
+/// (`load_plugin_bytes`) pass it directly, built in code or with `from_toml`.
 #[derive(Debug, Deserialize, Clone, Default)]
-pub(crate) struct PluginConfig {
+pub struct PluginConfig {
     #[serde(default)]
-    pub(crate) allowed_hosts: Option<Vec<String>>,
+    pub allowed_hosts: Option<Vec<String>>,
     #[serde(default)]
-    pub(crate) rate_limit_ms: Option<u64>,
+    pub rate_limit_ms: Option<u64>,
     #[serde(default)]
-    pub(crate) call_timeout_ms: Option<u64>,
+    pub call_timeout_ms: Option<u64>,
+    /// Extra attempts after a failed plugin call (default 1; 0 fails fast). All attempts
+    /// together must fit within `call_timeout_ms` or the host gives up waiting first.
+    #[serde(default)]
+    pub retry_attempts: Option<u32>,
+    /// Pause between attempts (default 200).
+    #[serde(default)]
+    pub retry_delay_ms: Option<u64>,
+    /// Headers added to the plugin's outbound requests (e.g. a host-obtained cookie or
+    /// token). Headers the plugin sets itself take precedence.
+    #[serde(default)]
+    pub default_headers: Option<HashMap<String, String>>,
+    /// Hosts (allow-list syntax) whose requests get `default_headers`, so credentials don't
+    /// reach CDNs or trackers. Defaults to `allowed_hosts`; with neither set they are not sent.
+    #[serde(default)]
+    pub default_headers_hosts: Option<Vec<String>>,
+    /// Patterns marking error placeholders in search results: `id:<value>` matches a media id
+    /// exactly, anything else is a title prefix. Defaults to `["id:error", "HTTP Error:"]`.
+    #[serde(default)]
+    pub error_sentinels: Option<Vec<String>>,
+    /// Languages the source serves ("en", "pt-BR"); used to rank search results for a
+    /// preferred language. Read without instantiating the plugin.
+    #[serde(default)]
+    pub languages: Option<Vec<String>>,
+}
+
+impl PluginConfig {
+    /// Parse the contents of a plugin `.toml`.
+    pub fn from_toml(s: &str) -> anyhow::Result<Self> {
+        Ok(toml::from_str(s)?)
+    }
+
+    /// `languages`, lowercased.
+    pub(crate) fn normalized_languages(&self) -> Vec<String> {
+        self.languages
+            .iter()
+            .flatten()
+            .map(|l| l.trim().to_ascii_lowercase())
+            .filter(|l| !l.is_empty())
+            .collect()
+    }
+}
+
+/// `languages` from a plugin's TOML, lowercased; empty if unset or unreadable.
+pub(crate) fn read_languages(cfg_path: &std::path::Path) -> Vec<String> {
+    std::fs::read_to_string(cfg_path)
+        .ok()
+        .and_then(|s| toml::from_str::<PluginConfig>(&s).ok())
+        .map(|cfg| cfg.normalized_languages())
+        .unwrap_or_default()
+}
+
+/// Sentinels used when the TOML doesn't set `error_sentinels`.
+pub(crate) fn default_error_sentinels() -> Vec<String> {
+    vec!["id:error".to_string(), "HTTP Error:".to_string()]
 }
@@ -1,11 +1,33 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio::sync::watch;
 use wasmtime_wasi::{WasiCtx, WasiCtxView, WasiView};
-use wasmtime_wasi_http::{WasiHttpCtx, WasiHttpView};
+use wasmtime_wasi_http::bindings::http::types::ErrorCode;
+use wasmtime_wasi_http::body::HyperOutgoingBody;
+use wasmtime_wasi_http::types::{
+    default_send_request_handler, HostFutureIncomingResponse, OutgoingRequestConfig,
+};
+use wasmtime_wasi_http::{HttpResult, WasiHttpCtx, WasiHttpView};
+
+use crate::plugins::allow_list::url_matches;
+use crate::rate_limit::HostRateLimiter;
 
 // Host context with WASI and HTTP support
 pub(crate) struct Host {
     pub(crate) wasi: WasiCtx,
     pub(crate) table: wasmtime_wasi::ResourceTable,
     pub(crate) http: WasiHttpCtx,
+    // Shared across all plugins so requests to the same host are spaced out globally
+    pub(crate) host_limiter: Arc<HostRateLimiter>,
+    // From the plugin TOML `default_headers`; only filled in when the request lacks them
+    pub(crate) default_headers: http::HeaderMap,
+    // Normalized allow-list entries for the hosts that receive `default_headers`
+    pub(crate) default_header_hosts: Vec<String>,
+    // Plugin cancel signal and the generation of the call in progress (see `Plugin`); pending
+    // requests fail as soon as the generation moves on
+    pub(crate) cancel: watch::Receiver<u64>,
+    pub(crate) call_generation: Arc<AtomicU64>,
 }
 
 impl WasiView for Host {
@@ -24,6 +46,126 @@ impl WasiHttpView for Host {
     fn table(&mut self) -> &mut wasmtime_wasi::ResourceTable {
         &mut self.table
     }
+
+    // Outbound hook: add default headers, wait for the shared per-host slot, then send as the
+    // default handler would.
+    fn send_request(
+        &mut self,
+        mut request: http::Request<HyperOutgoingBody>,
+        config: OutgoingRequestConfig,
+    ) -> HttpResult<HostFutureIncomingResponse> {
+        add_default_headers(
+            &self.default_headers,
+            &self.default_header_hosts,
+            &mut request,
+        );
+        let limiter = self.host_limiter.clone();
+        let host = request.uri().host().map(|h| h.to_string());
+        let mut cancel = self.cancel.clone();
+        let generation = self.call_generation.load(Ordering::Relaxed);
+        let handle = wasmtime_wasi::runtime::spawn(async move {
+            let send = async {
+                if let Some(host) = host {
+                    limiter.acquire(&host).await;
+                }
+                default_send_request_handler(request, config).await
+            };
+            tokio::select! {
+                res = send => Ok(res),
+                Ok(_) = cancel.wait_for(|g| *g != generation) => {
+                    Ok(Err(ErrorCode::InternalError(Some("cancelled".to_string()))))
+                }
+            }
+        });
+        Ok(HostFutureIncomingResponse::pending(handle))
+    }
+}
+
+// Only requests to one of `hosts` get the headers; anything else (CDNs, trackers) goes out
+// without the source's credentials.
+fn add_default_headers<B>(
+    headers: &http::HeaderMap,
+    hosts: &[String],
+    request: &mut http::Request<B>,
+) {
+    if headers.is_empty() || !url_matches(hosts, &request.uri().to_string()) {
+        return;
+    }
+    for (name, value) in headers {
+        if !request.headers().contains_key(name) {
+            request.headers_mut().insert(name.clone(), value.clone());
+        }
+    }
+}
+
+/// Build a header map from the TOML table, skipping (and logging) invalid names/values.
+pub(crate) fn header_map(
+    plugin: &str,
+    headers: Option<&HashMap<String, String>>,
+) -> http::HeaderMap {
+    let mut map = http::HeaderMap::new();
+    for (name, value) in headers.into_iter().flatten() {
+        match (
+            http::HeaderName::from_bytes(name.trim().as_bytes()),
+            http::HeaderValue::from_str(value.trim()),
+        ) {
+            (Ok(n), Ok(mut v)) => {
+                // Keep tokens/cookies out of debug output
+                v.set_sensitive(true);
+                map.insert(n, v);
+            }
+            _ => tracing::warn!(plugin, header = %name, "invalid default header; skipped"),
+        }
+    }
+    map
 }
 
 // (No explicit sockets context; wasi-http handles networking internally in this preview.)
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::config::PluginConfig;
+
+    #[test]
+    fn default_headers_from_toml_skip_invalid() {
+        let cfg: PluginConfig = toml::from_str(
+            "allowed_hosts = [\"example.org\"]\n[default_headers]\nCookie = \"sid=abc\"\n\"Bad Name\" = \"x\"\n",
+        )
+        .unwrap();
+        let map = header_map("test", cfg.default_headers.as_ref());
+        assert_eq!(map.len(), 1);
+        assert_eq!(map.get("cookie").unwrap(), "sid=abc");
+        assert!(map.get("cookie").unwrap().is_sensitive());
+    }
+
+    #[test]
+    fn default_headers_only_go_to_listed_hosts() {
+        let mut headers = http::HeaderMap::new();
+        headers.insert("cookie", http::HeaderValue::from_static("sid=abc"));
+        let hosts = vec!["example.org".to_string()];
+        let request = |uri: &str| {
+            let mut req = http::Request::builder().uri(uri).body(()).unwrap();
+            add_default_headers(&headers, &hosts, &mut req);
+            req
+        };
+
+        let own = request("https://example.org/api");
+        assert_eq!(own.headers().get("cookie").unwrap(), "sid=abc");
+        assert!(request("https://cdn.example.net/img.jpg")
+            .headers()
+            .get("cookie")
+            .is_none());
+        assert!(request("https://tracker.example.org.evil.com/")
+            .headers()
+            .get("cookie")
+            .is_none());
+
+        let mut unlisted = http::Request::builder()
+            .uri("https://example.org/")
+            .body(())
+            .unwrap();
+        add_default_headers(&headers, &[], &mut unlisted);
+        assert!(unlisted.headers().get("cookie").is_none());
+    }
+}
@@ -0,0 +1,147 @@
+//! In-process source with canned data, so aggregator caching/persistence/fallback logic can be
+//! tested without wasmtime or plugin artifacts. Register it with
+//! `PluginManager::register_mock_source` (or the `Aggregator` wrapper).
+
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::{
+    Asset, AssetKind, Media, MediaType, PluginBackend, ProviderCapabilities, Unit, UnitKind,
+};
+
+#[derive(Clone, Default)]
+pub(crate) struct MockSource {
+    media: Vec<Media>,
+    units: HashMap<String, Vec<Unit>>,
+    assets: HashMap<String, Vec<Asset>>,
+    failing: bool,
+    allowed_hosts: Option<Vec<String>>,
+    // Shared across instances so tests can assert whether the cache was used
+    calls: Arc<AtomicUsize>,
+}
+
+impl MockSource {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returned by searches whose query is contained in the title (case-insensitive).
+    pub(crate) fn with_media(mut self, media: Media) -> Self {
+        self.media.push(media);
+        self
+    }
+
+    pub(crate) fn with_units(mut self, media_id: &str, units: Vec<Unit>) -> Self {
+        self.units.insert(media_id.to_string(), units);
+        self
+    }
+
+    pub(crate) fn with_assets(mut self, unit_id: &str, assets: Vec<Asset>) -> Self {
+        self.assets.insert(unit_id.to_string(), assets);
+        self
+    }
+
+    /// Every fetch returns `Err`, like a source that is down.
+    pub(crate) fn failing(mut self) -> Self {
+        self.failing = true;
+        self
+    }
+
+    /// Restrict the source to an allow-list (by default every host is allowed).
+    pub(crate) fn with_allowed_hosts(mut self, hosts: &[&str]) -> Self {
+        self.allowed_hosts = Some(hosts.iter().map(|h| h.to_string()).collect());
+        self
+    }
+
+    /// Fetch calls served so far (search, units and assets).
+    pub(crate) fn calls(&self) -> usize {
+        self.calls.load(Ordering::SeqCst)
+    }
+
+    fn begin_call(&self) -> Result<()> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        if self.failing {
+            return Err(anyhow!("mock source failure"));
+        }
+        Ok(())
+    }
+}
+
+impl PluginBackend for MockSource {
+    fn call_timeout(&self) -> Duration {
+        Duration::from_secs(5)
+    }
+    fn fetch_media_list(&mut self, _kind: MediaType, query: &str) -> Result<Vec<Media>> {
+        self.begin_call()?;
+        let query = query.to_lowercase();
+        Ok(self
+            .media
+            .iter()
+            .filter(|m| m.title.to_lowercase().contains(&query))
+            .cloned()
+            .collect())
+    }
+    fn fetch_units(&mut self, media_id: &str) -> Result<Vec<Unit>> {
+        self.begin_call()?;
+        Ok(self.units.get(media_id).cloned().unwrap_or_default())
+    }
+    fn fetch_assets(&mut self, unit_id: &str) -> Result<Vec<Asset>> {
+        self.begin_call()?;
+        Ok(self.assets.get(unit_id).cloned().unwrap_or_default())
+    }
+    fn get_capabilities(&mut self, _refresh: bool) -> Result<ProviderCapabilities> {
+        Ok(ProviderCapabilities {
+            media_types: vec![MediaType::Manga, MediaType::Anime],
+            unit_kinds: vec![UnitKind::Chapter, UnitKind::Episode],
+            asset_kinds: vec![AssetKind::Page, AssetKind::Video],
+        })
+    }
+    fn allowed_hosts(&self) -> Vec<String> {
+        self.allowed_hosts.clone().unwrap_or_default()
+    }
+    fn set_host_allowed(&mut self, host: &str, allowed: bool) -> Result<Vec<String>> {
+        super::allow_list::set_entry(&mut self.allowed_hosts, host, allowed)?;
+        Ok(self.allowed_hosts())
+    }
+    fn url_allowed(&self, url: &str) -> bool {
+        url.starts_with("mock://")
+    }
+}
+
+pub(crate) fn media(id: &str, title: &str, kind: MediaType) -> Media {
+    Media {
+        id: id.to_string(),
+        mediatype: kind,
+        title: title.to_string(),
+        description: None,
+        url: None,
+        cover_url: None,
+    }
+}
+
+pub(crate) fn unit(id: &str, number: f32, kind: UnitKind) -> Unit {
+    Unit {
+        id: id.to_string(),
+        title: String::new(),
+        number_text: Some(number.to_string()),
+        number: Some(number),
+        lang: Some("en".to_string()),
+        group: None,
+        url: None,
+        published_at: None,
+        kind,
+    }
+}
+
+pub(crate) fn page(url: &str) -> Asset {
+    Asset {
+        url: url.to_string(),
+        mime: None,
+        width: None,
+        height: None,
+        kind: AssetKind::Page,
+    }
+}
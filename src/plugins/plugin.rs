@@ -1,21 +1,26 @@
 use anyhow::{anyhow, Result};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::{
     atomic::{AtomicU64, Ordering},
     Arc,
 };
 use std::time::{Duration, Instant};
 use tracing::{debug, error, warn};
-use url::Url;
-use wasmtime::{component::*, Engine, Store};
+use wasmtime::{component::*, Engine, Store, UpdateDeadline};
 use wasmtime_wasi::WasiCtxBuilder;
 use wasmtime_wasi_http;
 
-use crate::plugins::config::PluginConfig;
-use crate::plugins::host::Host;
+use crate::plugins::allow_list::{self, normalize_host_entry, url_matches};
+use crate::plugins::config::{default_error_sentinels, PluginConfig};
+use crate::plugins::host::{header_map, Host};
+use crate::plugins::sanitize::{sanitize_asset, sanitize_media, sanitize_unit};
 use crate::plugins::*; // bindgen types (Media, Unit, Asset, MediaType, UnitKind, AssetKind, ProviderCapabilities)
+use crate::rate_limit::HostRateLimiter;
 use std::sync::Arc as StdArc;
 use tokio::runtime::Runtime;
+use tokio::sync::watch;
+
+use crate::error::TouringError;
 
 #[allow(dead_code)] // Some fields retained for future lifecycle / metrics usage
 pub(crate) struct Plugin {
@@ -27,52 +32,121 @@ pub(crate) struct Plugin {
     pub(crate) rate_limit: Duration,
     pub(crate) slow_warn: Duration,
     pub(crate) call_timeout: Duration,
+    pub(crate) retry_attempts: u32,
+    pub(crate) retry_delay: Duration,
     pub(crate) last_call: Option<Instant>,
     pub(crate) epoch_ticks: Arc<AtomicU64>,
     pub(crate) epoch_interval: Duration,
+    // Bumped by `PluginManager::cancel_all_calls`; a call started under an older value traps
+    cancel: watch::Receiver<u64>,
+    // Cancel generation and epoch tick limit of the call in progress, read by the epoch
+    // callback and (generation only) by outgoing HTTP requests
+    call_generation: Arc<AtomicU64>,
+    call_deadline: Arc<AtomicU64>,
+    // Set once a call traps (timeout, cancellation, plugin panic): wasmtime leaves the
+    // instance unenterable, so the worker retires it and the slot instantiates a new one
+    pub(crate) poisoned: bool,
+    // `default_headers_hosts` was configured; otherwise header scope follows `allowed_hosts`
+    explicit_header_hosts: bool,
     pub(crate) allowed_hosts: Option<Vec<String>>,
+    pub(crate) error_sentinels: Vec<String>,
     pub(crate) _instance: wasmtime::component::Instance,
     pub(crate) _component: Component,
     rt: StdArc<Runtime>,
 }
 
+/// Where a plugin's component and configuration come from.
+pub(crate) enum ComponentSource {
+    /// `.wasm` or `.cwasm` on disk; the config is the sibling `.toml`.
+    File(PathBuf),
+    /// Component bytes bundled with the app (see `PluginManager::load_plugin_bytes`).
+    Bytes {
+        name: String,
+        bytes: Arc<[u8]>,
+        config: Box<PluginConfig>,
+    },
+}
+
+fn component_from_file(engine: &Engine, plugin_path: &Path) -> Result<Component> {
+    if plugin_path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.eq_ignore_ascii_case("cwasm"))
+        .unwrap_or(false)
+    {
+        return unsafe { Component::deserialize_file(engine, plugin_path) };
+    }
+    #[cfg(target_os = "ios")]
+    {
+        Err(anyhow!(
+            "Pulley host requires precompiled .cwasm artifact; found {}",
+            plugin_path.display()
+        ))
+    }
+    #[cfg(not(target_os = "ios"))]
+    {
+        Component::from_file(engine, plugin_path)
+    }
+}
+
+// Precompiled (`.cwasm`) bytes are recognised by their header; anything else is compiled
+fn component_from_bytes(engine: &Engine, bytes: &[u8]) -> Result<Component> {
+    if Engine::detect_precompiled(bytes).is_some() {
+        // Same trust model as a `.cwasm` file: the bytes ship with the app
+        return unsafe { Component::deserialize(engine, bytes) };
+    }
+    #[cfg(target_os = "ios")]
+    {
+        Err(anyhow!(
+            "Pulley host requires a precompiled component; embedded bytes are plain wasm"
+        ))
+    }
+    #[cfg(not(target_os = "ios"))]
+    {
+        Component::from_binary(engine, bytes)
+    }
+}
+
 impl Plugin {
     pub async fn new_async(
         engine: &Engine,
-        plugin_path: &Path,
+        source: ComponentSource,
         epoch_ticks: Arc<AtomicU64>,
         epoch_interval: Duration,
+        host_limiter: Arc<HostRateLimiter>,
+        cancel: watch::Receiver<u64>,
         rt: StdArc<Runtime>,
     ) -> Result<Self> {
-        let component = if plugin_path
-            .extension()
-            .and_then(|ext| ext.to_str())
-            .map(|ext| ext.eq_ignore_ascii_case("cwasm"))
-            .unwrap_or(false)
-        {
-            unsafe { Component::deserialize_file(engine, plugin_path)? }
-        } else {
-            #[cfg(target_os = "ios")]
-            {
-                return Err(anyhow!(
-                    "Pulley host requires precompiled .cwasm artifact; found {}",
-                    plugin_path.display()
-                ));
-            }
-            #[cfg(not(target_os = "ios"))]
-            {
-                Component::from_file(engine, plugin_path)?
+        let (plugin_name, component, cfg) = match source {
+            ComponentSource::File(plugin_path) => {
+                let component = component_from_file(engine, &plugin_path)?;
+                let cfg_path = plugin_path.with_extension("toml");
+                let cfg: PluginConfig = std::fs::read_to_string(&cfg_path)
+                    .ok()
+                    .and_then(|s| toml::from_str(&s).ok())
+                    .unwrap_or_default();
+                let name = plugin_path
+                    .file_stem()
+                    .and_then(|s| s.to_str())
+                    .unwrap_or("unknown")
+                    .to_string();
+                (name, component, cfg)
             }
+            ComponentSource::Bytes {
+                name,
+                bytes,
+                config,
+            } => (name, component_from_bytes(engine, &bytes)?, *config),
         };
-        let cfg_path = plugin_path.with_extension("toml");
-        let cfg: PluginConfig = std::fs::read_to_string(&cfg_path)
-            .ok()
-            .and_then(|s| toml::from_str(&s).ok())
-            .unwrap_or_default();
         let allowed_hosts: Option<Vec<String>> = cfg.allowed_hosts.as_ref().map(|v| {
             v.iter()
-                .map(|h| h.trim().to_ascii_lowercase())
-                .filter(|h| !h.is_empty())
+                .filter_map(|h| {
+                    let normalized = normalize_host_entry(h);
+                    if normalized.is_none() && !h.trim().is_empty() {
+                        warn!(plugin=%plugin_name, entry=%h, "ignoring invalid allowed_hosts entry");
+                    }
+                    normalized
+                })
                 .collect()
         });
         let mut builder = WasiCtxBuilder::new();
@@ -80,17 +154,54 @@ impl Plugin {
         if let Some(list) = &allowed_hosts {
             builder.env("TOURING_ALLOWED_HOSTS", list.join(","));
         }
+        let explicit_header_hosts = cfg.default_headers_hosts.is_some();
+        let default_header_hosts: Vec<String> = match &cfg.default_headers_hosts {
+            Some(hosts) => hosts
+                .iter()
+                .filter_map(|h| normalize_host_entry(h))
+                .collect(),
+            None => allowed_hosts.clone().unwrap_or_default(),
+        };
+        if cfg.default_headers.as_ref().is_some_and(|h| !h.is_empty())
+            && default_header_hosts.is_empty()
+        {
+            warn!(plugin=%plugin_name, "default_headers set without allowed_hosts or default_headers_hosts; they will not be sent");
+        }
         let wasi = builder.build();
         let http = wasmtime_wasi_http::WasiHttpCtx::new();
+        let call_generation = Arc::new(AtomicU64::new(*cancel.borrow()));
+        let call_deadline = Arc::new(AtomicU64::new(u64::MAX));
         let host = Host {
             wasi,
             table: wasmtime_wasi::ResourceTable::new(),
             http,
+            host_limiter,
+            default_headers: header_map(&plugin_name, cfg.default_headers.as_ref()),
+            default_header_hosts,
+            cancel: cancel.clone(),
+            call_generation: call_generation.clone(),
         };
         let mut store = Store::new(engine, host);
         let now = epoch_ticks.load(Ordering::Relaxed);
         let far = now.saturating_add(1_000_000_000);
         store.set_epoch_deadline(far);
+        // During a call the deadline is one tick away, so this runs every tick: trap on
+        // cancellation or once the call's own deadline has passed, otherwise keep going
+        {
+            let cancel = cancel.clone();
+            let generation = call_generation.clone();
+            let deadline = call_deadline.clone();
+            let ticks = epoch_ticks.clone();
+            store.epoch_deadline_callback(move |_| {
+                if *cancel.borrow() != generation.load(Ordering::Relaxed) {
+                    return Err(anyhow!("cancelled"));
+                }
+                if ticks.load(Ordering::Relaxed) >= deadline.load(Ordering::Relaxed) {
+                    return Ok(UpdateDeadline::Interrupt);
+                }
+                Ok(UpdateDeadline::Continue(1))
+            });
+        }
         let mut linker = Linker::<Host>::new(engine);
         wasmtime_wasi::p2::add_to_linker_async(&mut linker)?;
         wasmtime_wasi_http::add_only_http_to_linker_async(&mut linker)?;
@@ -101,21 +212,28 @@ impl Plugin {
         let caps = None;
         // Use a multi-thread runtime so async HTTP tasks can execute even after moving the Plugin to a different thread.
         Ok(Self {
-            name: plugin_path
-                .file_stem()
-                .and_then(|s| s.to_str())
-                .unwrap_or("unknown")
-                .to_string(),
+            name: plugin_name,
             store,
             _bindings: bindings,
             caps,
             rate_limit: Duration::from_millis(cfg.rate_limit_ms.unwrap_or(150)),
             slow_warn: Duration::from_secs(5),
             call_timeout: Duration::from_millis(cfg.call_timeout_ms.unwrap_or(15_000)),
+            retry_attempts: cfg.retry_attempts.unwrap_or(1),
+            retry_delay: Duration::from_millis(cfg.retry_delay_ms.unwrap_or(200)),
             last_call: None,
             epoch_ticks,
             epoch_interval,
+            cancel,
+            call_generation,
+            call_deadline,
+            poisoned: false,
+            explicit_header_hosts,
             allowed_hosts,
+            error_sentinels: cfg
+                .error_sentinels
+                .clone()
+                .unwrap_or_else(default_error_sentinels),
             _instance: instance,
             _component: component,
             rt,
@@ -125,16 +243,29 @@ impl Plugin {
 
     pub(crate) fn set_deadline(&mut self) {
         let now = self.epoch_ticks.load(Ordering::Relaxed);
-        let per_tick_ms = self.epoch_interval.as_millis().max(1) as u128;
-        let need = ((self.call_timeout.as_millis() + per_tick_ms - 1) / per_tick_ms) as u64;
-        let deadline = now.saturating_add(need);
-        self.store.set_epoch_deadline(deadline);
+        let per_tick_ms = self.epoch_interval.as_millis().max(1);
+        let need = self.call_timeout.as_millis().div_ceil(per_tick_ms) as u64;
+        self.call_deadline
+            .store(now.saturating_add(need), Ordering::Relaxed);
+        self.call_generation
+            .store(self.cancel_generation(), Ordering::Relaxed);
+        // Checked by the epoch callback every tick from here on
+        self.store.set_epoch_deadline(1);
     }
 
     pub(crate) fn clear_deadline(&mut self) {
-        let now = self.epoch_ticks.load(Ordering::Relaxed);
-        let far = now.saturating_add(1_000_000_000);
-        self.store.set_epoch_deadline(far);
+        self.call_deadline.store(u64::MAX, Ordering::Relaxed);
+        self.store.set_epoch_deadline(1_000_000_000);
+    }
+
+    // A failed call into the instance means it trapped; see `poisoned`
+    fn trapped(&mut self, op: &str, e: anyhow::Error) -> anyhow::Error {
+        self.poisoned = true;
+        anyhow!("Failed to call {} async: {}", op, e)
+    }
+
+    fn cancel_generation(&self) -> u64 {
+        *self.cancel.borrow()
     }
 
     pub(crate) fn throttle(&mut self) {
@@ -154,76 +285,133 @@ impl Plugin {
         }
     }
 
-    pub(crate) fn retry_once<T, F>(&mut self, mut f: F, op: &str) -> Result<T>
+    // Run `f` up to `1 + retries` times, pausing `retry_delay` between attempts
+    pub(crate) fn retry<T, F>(&mut self, mut f: F, op: &str, retries: u32) -> Result<T>
     where
         F: FnMut(&mut Self) -> Result<T>,
     {
-        for attempt in 0..2 {
+        let generation = self.cancel_generation();
+        let cancelled = || -> anyhow::Error {
+            TouringError::Cancelled {
+                operation: op.to_string(),
+            }
+            .into()
+        };
+        let mut attempt = 0;
+        loop {
             self.set_deadline();
             let res = f(self);
             self.clear_deadline();
             match res {
                 Ok(v) => return Ok(v),
-                Err(e1) if attempt == 0 => {
-                    warn!(plugin=%self.name, op, error=%e1, "plugin op failed - retrying");
-                    std::thread::sleep(Duration::from_millis(200));
-                    continue;
+                Err(_) if self.cancel_generation() != generation => return Err(cancelled()),
+                // Another attempt could only fail with "cannot enter component instance"
+                Err(e) if self.poisoned => return Err(anyhow!("{}: {}", op, e)),
+                Err(e) if attempt < retries => {
+                    attempt += 1;
+                    warn!(plugin=%self.name, op, attempt, retries, error=%e, "plugin op failed - retrying");
+                    std::thread::sleep(self.retry_delay);
+                    if self.cancel_generation() != generation {
+                        return Err(cancelled());
+                    }
                 }
-                Err(e) => return Err(anyhow!("{} after retry: {}", op, e)),
+                Err(e) if retries == 0 => return Err(anyhow!("{}: {}", op, e)),
+                Err(e) => return Err(anyhow!("{} after {} retries: {}", op, retries, e)),
             }
         }
-        unreachable!();
     }
 
     pub(crate) fn url_allowed(&self, url: &str) -> bool {
         match &self.allowed_hosts {
             None => true,
-            Some(list) => {
-                if list.is_empty() {
-                    return false;
-                }
-                let Ok(parsed) = Url::parse(url) else {
-                    return false;
-                };
-                match parsed.scheme() {
-                    "http" | "https" => {}
-                    _ => return false,
-                }
-                let Some(host) = parsed.host_str() else {
-                    return false;
-                };
-                let host = host.to_ascii_lowercase();
-                list.iter().any(|allowed| {
-                    let a = allowed.as_str();
-                    if let Some(stripped) = a.strip_prefix("*.") {
-                        host == stripped || host.ends_with(&format!(".{}", stripped))
-                    } else {
-                        host == a
-                    }
-                })
-            }
+            Some(list) => url_matches(list, url),
         }
     }
 
-    pub(crate) fn fetch_media_list(&mut self, kind: MediaType, query: &str) -> Result<Vec<Media>> {
-        if matches!(&self.allowed_hosts, Some(v) if v.is_empty()) {
-            return Ok(Vec::new());
+    /// Add or remove a host from the live allow-list. A plugin without an allow-list
+    /// (all hosts allowed) is left untouched; removing the last entry is an error. Returns
+    /// the resulting list.
+    pub(crate) fn set_host_allowed(&mut self, host: &str, allowed: bool) -> Result<Vec<String>> {
+        if self.allowed_hosts.is_none() {
+            debug!(plugin=%self.name, host=%host, allowed, "no allow-list configured; override ignored");
         }
-        self.throttle();
-        self.set_deadline();
-        let start = Instant::now();
-        debug!(plugin=%self.name, ?kind, query, "fetch_media_list start");
-        let res = self.retry_once(|this| {
+        allow_list::set_entry(&mut self.allowed_hosts, host, allowed)?;
+        if !self.explicit_header_hosts {
+            self.store.data_mut().default_header_hosts =
+                self.allowed_hosts.clone().unwrap_or_default();
+        }
+        Ok(self.allowed_hosts.clone().unwrap_or_default())
+    }
+
+    fn is_error_sentinel(&self, m: &Media) -> bool {
+        self.error_sentinels
+            .iter()
+            .any(|pat| match pat.strip_prefix("id:") {
+                Some(id) => m.id == id,
+                None => !pat.is_empty() && m.title.starts_with(pat.as_str()),
+            })
+    }
+
+    // Export calls with retries but none of the sentinel or allowed-hosts filtering below
+    pub(crate) fn call_fetchmedialist(
+        &mut self,
+        kind: &MediaType,
+        query: &str,
+    ) -> Result<Vec<Media>> {
+        self.retry(|this| {
             // Try plain export name first, then prefixed variant
             let func = this._instance.get_func(&mut this.store, "fetchmedialist")
                 .or_else(|| this._instance.get_func(&mut this.store, "library#fetchmedialist"))
                 .ok_or_else(|| anyhow!("missing export fetchmedialist (tried 'fetchmedialist' and 'library#fetchmedialist')"))?;
             let typed = func.typed::<(MediaType, String), (Vec<Media>,)>(&this.store)?;
             let (result_vec,) = this.rt.block_on(typed.call_async(&mut this.store, (kind.clone(), query.to_string())))
-                .map_err(|e| anyhow!("Failed to call fetchmedialist async: {}", e))?;
-            this.rt.block_on(typed.post_return_async(&mut this.store))?;
+                .map_err(|e| this.trapped("fetchmedialist", e))?;
+            this.rt
+                .block_on(typed.post_return_async(&mut this.store))
+                .map_err(|e| this.trapped("fetchmedialist", e))?;
+            Ok(result_vec)
+        }, "fetchmedialist", self.retry_attempts)
+    }
+
+    pub(crate) fn call_fetchunits(&mut self, media_id: &str) -> Result<Vec<Unit>> {
+        self.retry(|this| {
+            let func = this._instance.get_func(&mut this.store, "fetchunits")
+                .or_else(|| this._instance.get_func(&mut this.store, "library#fetchunits"))
+                .ok_or_else(|| anyhow!("missing export fetchunits (tried 'fetchunits' and 'library#fetchunits')"))?;
+            let typed = func.typed::<(String,), (Vec<Unit>,)>(&this.store)?;
+            let (result_vec,) = this.rt.block_on(typed.call_async(&mut this.store, (media_id.to_string(),)))
+                .map_err(|e| this.trapped("fetchunits", e))?;
+            this.rt
+                .block_on(typed.post_return_async(&mut this.store))
+                .map_err(|e| this.trapped("fetchunits", e))?;
             Ok(result_vec)
-        }, "fetchmedialist");
+        }, "fetchunits", self.retry_attempts)
+    }
+
+    pub(crate) fn call_fetchassets(&mut self, unit_id: &str) -> Result<Vec<Asset>> {
+        self.retry(|this| {
+            let func = this._instance.get_func(&mut this.store, "fetchassets")
+                .or_else(|| this._instance.get_func(&mut this.store, "library#fetchassets"))
+                .ok_or_else(|| anyhow!("missing export fetchassets (tried 'fetchassets' and 'library#fetchassets')"))?;
+            let typed = func.typed::<(String,), (Vec<Asset>,)>(&this.store)?;
+            let (result_vec,) = this.rt.block_on(typed.call_async(&mut this.store, (unit_id.to_string(),)))
+                .map_err(|e| this.trapped("fetchassets", e))?;
+            this.rt
+                .block_on(typed.post_return_async(&mut this.store))
+                .map_err(|e| this.trapped("fetchassets", e))?;
+            Ok(result_vec)
+        }, "fetchassets", self.retry_attempts)
+    }
+
+    pub(crate) fn fetch_media_list(&mut self, kind: MediaType, query: &str) -> Result<Vec<Media>> {
+        if matches!(&self.allowed_hosts, Some(v) if v.is_empty()) {
+            return Ok(Vec::new());
+        }
+        self.throttle();
+        self.set_deadline();
+        let start = Instant::now();
+        debug!(plugin=%self.name, ?kind, query, "fetch_media_list start");
+        let res = self.call_fetchmedialist(&kind, query);
         self.clear_deadline();
         self.warn_if_slow(start, "fetchmedialist");
         let mut list = match res {
@@ -231,18 +419,36 @@ impl Plugin {
                 // Inspect and log sentinel error entries before filtering them out
                 let mut filtered: Vec<Media> = Vec::with_capacity(v.len());
                 let mut suppressed = 0usize;
-                for m in v.into_iter() {
-                    if m.id == "error" || m.title.starts_with("HTTP Error:") {
+                let mut malformed = 0usize;
+                for mut m in v.into_iter() {
+                    if self.is_error_sentinel(&m) {
                         suppressed += 1;
                         continue;
                     }
+                    if !sanitize_media(&mut m) {
+                        malformed += 1;
+                        continue;
+                    }
                     filtered.push(m);
                 }
+                if malformed > 0 {
+                    warn!(plugin=%self.name, query, malformed, "dropped malformed search results");
+                }
+                if suppressed > 0 && filtered.is_empty() {
+                    // Nothing but error placeholders: report a failed source, not "0 results"
+                    return Err(anyhow!(
+                        "{} returned only error entries ({} suppressed)",
+                        self.name,
+                        suppressed
+                    ));
+                }
                 if suppressed > 0 {
                     debug!(plugin=%self.name, query, suppressed, "suppressed sentinel error entries");
                 }
                 filtered
             }
+            // Not "no results": callers must not cache a cancelled fetch
+            Err(e) if is_cancelled(&e) => return Err(e),
             Err(e) => {
                 error!(plugin=%self.name, error=%e, "fetchmedialist failed");
                 Vec::new()
@@ -271,25 +477,23 @@ impl Plugin {
         self.throttle();
         self.set_deadline();
         let start = Instant::now();
-        let res = self.retry_once(|this| {
-            let func = this._instance.get_func(&mut this.store, "fetchunits")
-                .or_else(|| this._instance.get_func(&mut this.store, "library#fetchunits"))
-                .ok_or_else(|| anyhow!("missing export fetchunits (tried 'fetchunits' and 'library#fetchunits')"))?;
-            let typed = func.typed::<(String,), (Vec<Unit>,)>(&this.store)?;
-            let (result_vec,) = this.rt.block_on(typed.call_async(&mut this.store, (media_id.to_string(),)))
-                .map_err(|e| anyhow!("Failed to call fetchunits async: {}", e))?;
-            this.rt.block_on(typed.post_return_async(&mut this.store))?;
-            Ok(result_vec)
-        }, "fetchunits");
+        let res = self.call_fetchunits(media_id);
         self.clear_deadline();
         self.warn_if_slow(start, "fetchunits");
         let mut units = match res {
             Ok(v) => v,
+            // Not "no results": callers must not cache a cancelled fetch
+            Err(e) if is_cancelled(&e) => return Err(e),
             Err(e) => {
                 error!(plugin=%self.name, error=%e, "fetchunits failed");
                 Vec::new()
             }
         };
+        let before = units.len();
+        units.retain_mut(sanitize_unit);
+        if units.len() < before {
+            warn!(plugin=%self.name, media_id, dropped = before - units.len(), "dropped malformed units");
+        }
         for u in &mut units {
             if let Some(uurl) = &u.url {
                 if !self.url_allowed(uurl) {
@@ -307,20 +511,13 @@ impl Plugin {
         self.throttle();
         self.set_deadline();
         let start = Instant::now();
-        let res = self.retry_once(|this| {
-            let func = this._instance.get_func(&mut this.store, "fetchassets")
-                .or_else(|| this._instance.get_func(&mut this.store, "library#fetchassets"))
-                .ok_or_else(|| anyhow!("missing export fetchassets (tried 'fetchassets' and 'library#fetchassets')"))?;
-            let typed = func.typed::<(String,), (Vec<Asset>,)>(&this.store)?;
-            let (result_vec,) = this.rt.block_on(typed.call_async(&mut this.store, (unit_id.to_string(),)))
-                .map_err(|e| anyhow!("Failed to call fetchassets async: {}", e))?;
-            this.rt.block_on(typed.post_return_async(&mut this.store))?;
-            Ok(result_vec)
-        }, "fetchassets");
+        let res = self.call_fetchassets(unit_id);
         self.clear_deadline();
         self.warn_if_slow(start, "fetchassets");
         let assets = match res {
             Ok(v) => v,
+            // Not "no results": callers must not cache a cancelled fetch
+            Err(e) if is_cancelled(&e) => return Err(e),
             Err(e) => {
                 error!(plugin=%self.name, error=%e, "fetchassets failed");
                 Vec::new()
@@ -328,6 +525,7 @@ impl Plugin {
         };
         let filtered: Vec<Asset> = assets
             .into_iter()
+            .filter_map(|mut a| sanitize_asset(&mut a).then_some(a))
             .filter(|a| self.url_allowed(&a.url))
             .collect();
         Ok(filtered)
@@ -337,16 +535,18 @@ impl Plugin {
         self.throttle();
         self.set_deadline();
         let start = Instant::now();
-        let res = self.retry_once(|this| {
+        let res = self.retry(|this| {
             let func = this._instance.get_func(&mut this.store, "getcapabilities")
                 .or_else(|| this._instance.get_func(&mut this.store, "library#getcapabilities"))
                 .ok_or_else(|| anyhow!("missing export getcapabilities (tried 'getcapabilities' and 'library#getcapabilities')"))?;
             let typed = func.typed::<(), (ProviderCapabilities,)>(&this.store)?;
             let (caps,) = this.rt.block_on(typed.call_async(&mut this.store, ()))
-                .map_err(|e| anyhow!("Failed to call getcapabilities async: {}", e))?;
-            this.rt.block_on(typed.post_return_async(&mut this.store))?;
+                .map_err(|e| this.trapped("getcapabilities", e))?;
+            this.rt
+                .block_on(typed.post_return_async(&mut this.store))
+                .map_err(|e| this.trapped("getcapabilities", e))?;
             Ok(caps)
-        }, "getcapabilities");
+        }, "getcapabilities", self.retry_attempts);
         self.clear_deadline();
         self.warn_if_slow(start, "getcapabilities");
         if let Ok(c) = &res {
@@ -362,3 +562,10 @@ impl Plugin {
         self.get_capabilities_refresh()
     }
 }
+
+fn is_cancelled(err: &anyhow::Error) -> bool {
+    matches!(
+        TouringError::classify(err),
+        Some(TouringError::Cancelled { .. })
+    )
+}
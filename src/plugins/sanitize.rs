@@ -0,0 +1,153 @@
+//! Normalization of plugin output before the host uses it. Plugins are untrusted: strings are
+//! trimmed, stripped of control characters and capped, URLs must be absolute http(s), and
+//! entries without a usable id (or an asset without a usable URL) are dropped.
+
+use url::Url;
+
+use super::{Asset, Media, Unit};
+
+const MAX_ID_LEN: usize = 1024;
+const MAX_URL_LEN: usize = 4096;
+const MAX_TITLE_LEN: usize = 512;
+const MAX_DESCRIPTION_LEN: usize = 16 * 1024;
+// number text, language, group, timestamps, MIME types
+const MAX_LABEL_LEN: usize = 128;
+
+/// Returns false when the entry should be discarded.
+pub(crate) fn sanitize_media(m: &mut Media) -> bool {
+    if !valid_id(&mut m.id) {
+        return false;
+    }
+    m.title = single_line(&m.title, MAX_TITLE_LEN);
+    m.description = m
+        .description
+        .as_deref()
+        .map(|d| multi_line(d, MAX_DESCRIPTION_LEN))
+        .filter(|d| !d.is_empty());
+    m.url = m.url.as_deref().and_then(http_url);
+    m.cover_url = m.cover_url.as_deref().and_then(http_url);
+    true
+}
+
+/// Returns false when the entry should be discarded.
+pub(crate) fn sanitize_unit(u: &mut Unit) -> bool {
+    if !valid_id(&mut u.id) {
+        return false;
+    }
+    u.title = single_line(&u.title, MAX_TITLE_LEN);
+    for field in [
+        &mut u.number_text,
+        &mut u.lang,
+        &mut u.group,
+        &mut u.published_at,
+    ] {
+        *field = optional_label(field.as_deref());
+    }
+    u.number = u.number.filter(|n| n.is_finite());
+    u.url = u.url.as_deref().and_then(http_url);
+    true
+}
+
+/// Returns false when the asset has no usable URL.
+pub(crate) fn sanitize_asset(a: &mut Asset) -> bool {
+    let Some(url) = http_url(&a.url) else {
+        return false;
+    };
+    a.url = url;
+    a.mime = optional_label(a.mime.as_deref());
+    true
+}
+
+// Ids are opaque keys: trimmed but never truncated, so an oversized one disqualifies the entry
+fn valid_id(id: &mut String) -> bool {
+    let trimmed = id.trim();
+    if trimmed.is_empty() || trimmed.len() > MAX_ID_LEN || trimmed.chars().any(char::is_control) {
+        return false;
+    }
+    if trimmed.len() != id.len() {
+        *id = trimmed.to_string();
+    }
+    true
+}
+
+fn http_url(raw: &str) -> Option<String> {
+    let raw = raw.trim();
+    if raw.len() > MAX_URL_LEN || raw.chars().any(char::is_control) {
+        return None;
+    }
+    let url = Url::parse(raw).ok()?;
+    matches!(url.scheme(), "http" | "https").then(|| raw.to_string())
+}
+
+fn optional_label(value: Option<&str>) -> Option<String> {
+    value
+        .map(|v| single_line(v, MAX_LABEL_LEN))
+        .filter(|v| !v.is_empty())
+}
+
+// Control characters (newlines included) become spaces, runs of whitespace collapse
+fn single_line(s: &str, max_chars: usize) -> String {
+    let cleaned: String = s
+        .chars()
+        .map(|c| if c.is_control() { ' ' } else { c })
+        .collect();
+    let collapsed = cleaned.split_whitespace().collect::<Vec<_>>().join(" ");
+    truncate_chars(collapsed, max_chars)
+}
+
+// Keeps line breaks and tabs, drops other control characters
+fn multi_line(s: &str, max_chars: usize) -> String {
+    let cleaned: String = s
+        .trim()
+        .chars()
+        .filter(|c| !c.is_control() || matches!(c, '\n' | '\t'))
+        .collect();
+    truncate_chars(cleaned, max_chars)
+}
+
+fn truncate_chars(s: String, max_chars: usize) -> String {
+    match s.char_indices().nth(max_chars) {
+        Some((idx, _)) => s[..idx].trim_end().to_string(),
+        None => s,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::{AssetKind, MediaType};
+
+    #[test]
+    fn cleans_media_and_rejects_unusable_entries() {
+        let mut m = Media {
+            id: "  42 ".into(),
+            mediatype: MediaType::Manga,
+            title: "Blue\u{0}\n  Period".into(),
+            description: Some(" line one\nline two\u{7} ".into()),
+            url: Some(" https://example.org/m/42 ".into()),
+            cover_url: Some("javascript:alert(1)".into()),
+        };
+        assert!(sanitize_media(&mut m));
+        assert_eq!(m.id, "42");
+        assert_eq!(m.title, "Blue Period");
+        assert_eq!(m.description.as_deref(), Some("line one\nline two"));
+        assert_eq!(m.url.as_deref(), Some("https://example.org/m/42"));
+        assert_eq!(m.cover_url, None);
+
+        m.id = " ".into();
+        assert!(!sanitize_media(&mut m));
+        assert_eq!(
+            single_line(&"é".repeat(600), MAX_TITLE_LEN).chars().count(),
+            512
+        );
+
+        let mut page = Asset {
+            url: "data:image/png;base64,AAAA".into(),
+            mime: None,
+            width: None,
+            height: None,
+            kind: AssetKind::Page,
+        };
+        assert!(!sanitize_asset(&mut page));
+    }
+}
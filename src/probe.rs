@@ -0,0 +1,274 @@
+use anyhow::{anyhow, Result};
+use reqwest::header::{ACCEPT_RANGES, CONTENT_LENGTH, CONTENT_TYPE, RANGE};
+use serde::{Deserialize, Serialize};
+
+/// Metadata about a direct stream URL, gathered without downloading the media.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StreamInfo {
+    pub url: String,
+    pub content_type: Option<String>,
+    pub content_length: Option<u64>,
+    pub duration_secs: Option<f64>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub video_codec: Option<String>,
+    pub audio_codec: Option<String>,
+}
+
+// Refuse to pull absurdly large moov boxes; real-world ones are well under this.
+const MAX_MOOV_BYTES: u64 = 16 * 1024 * 1024;
+// Upper bound on top-level boxes walked before giving up.
+const MAX_TOP_LEVEL_BOXES: usize = 64;
+
+/// HEAD the URL for length/type and, for MP4s served with range support,
+/// walk the top-level boxes to read duration/resolution/codecs from `moov`.
+pub async fn probe_stream(client: &reqwest::Client, url: &str) -> Result<StreamInfo> {
+    let resp = client.head(url).send().await?;
+    if !resp.status().is_success() {
+        return Err(anyhow!("HEAD {} returned {}", url, resp.status()));
+    }
+    let headers = resp.headers();
+    let content_type = headers
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(|s| s.split(';').next().unwrap_or(s).trim().to_ascii_lowercase());
+    let content_length = headers
+        .get(CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|s| s.parse::<u64>().ok());
+    let ranges = headers
+        .get(ACCEPT_RANGES)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.eq_ignore_ascii_case("bytes"));
+
+    let mut info = StreamInfo {
+        url: url.to_string(),
+        content_type,
+        content_length,
+        ..Default::default()
+    };
+
+    if ranges && looks_like_mp4(url, info.content_type.as_deref()) {
+        match find_moov(client, url, info.content_length).await {
+            Ok(Some(moov)) => parse_moov(&moov, &mut info),
+            Ok(None) => tracing::debug!(url, "no moov box found"),
+            Err(e) => tracing::debug!(url, error=%e, "mp4 probe failed"),
+        }
+    }
+    Ok(info)
+}
+
+fn looks_like_mp4(url: &str, content_type: Option<&str>) -> bool {
+    if let Some(ct) = content_type {
+        if ct == "video/mp4" || ct == "video/quicktime" || ct == "audio/mp4" {
+            return true;
+        }
+    }
+    let path = url
+        .split(['?', '#'])
+        .next()
+        .unwrap_or(url)
+        .to_ascii_lowercase();
+    path.ends_with(".mp4") || path.ends_with(".m4v") || path.ends_with(".mov")
+}
+
+async fn read_range(client: &reqwest::Client, url: &str, start: u64, len: u64) -> Result<Vec<u8>> {
+    let resp = client
+        .get(url)
+        .header(RANGE, format!("bytes={}-{}", start, start + len - 1))
+        .send()
+        .await?;
+    if resp.status() != reqwest::StatusCode::PARTIAL_CONTENT {
+        return Err(anyhow!("range request returned {}", resp.status()));
+    }
+    Ok(resp.bytes().await?.to_vec())
+}
+
+// Walk top-level boxes via small range reads, skipping mdat, until moov is found.
+async fn find_moov(
+    client: &reqwest::Client,
+    url: &str,
+    total: Option<u64>,
+) -> Result<Option<Vec<u8>>> {
+    let mut offset = 0u64;
+    for _ in 0..MAX_TOP_LEVEL_BOXES {
+        if total.is_some_and(|t| offset.saturating_add(8) > t) {
+            break;
+        }
+        let head = read_range(client, url, offset, 16).await?;
+        let Some((size, kind, header_len)) = box_header(&head) else {
+            break;
+        };
+        let size = match size {
+            0 => total.map(|t| t - offset).unwrap_or(0),
+            s => s,
+        };
+        if size < header_len {
+            break;
+        }
+        if &kind == b"moov" {
+            if size > MAX_MOOV_BYTES {
+                return Err(anyhow!("moov box too large ({} bytes)", size));
+            }
+            let data = read_range(client, url, offset, size).await?;
+            return Ok(data.get(header_len as usize..).map(<[u8]>::to_vec));
+        }
+        // Box sizes come from the remote file; a corrupt one must not wrap the offset
+        let Some(next) = offset.checked_add(size) else {
+            break;
+        };
+        offset = next;
+    }
+    Ok(None)
+}
+
+// (box size, type, header length); size 0 means "to end of file".
+fn box_header(buf: &[u8]) -> Option<(u64, [u8; 4], u64)> {
+    if buf.len() < 8 {
+        return None;
+    }
+    let size = u32::from_be_bytes(buf[0..4].try_into().ok()?) as u64;
+    let kind: [u8; 4] = buf[4..8].try_into().ok()?;
+    if size == 1 {
+        let large = u64::from_be_bytes(buf.get(8..16)?.try_into().ok()?);
+        return Some((large, kind, 16));
+    }
+    Some((size, kind, 8))
+}
+
+// Iterate the child boxes of a container payload as (type, payload).
+fn children(buf: &[u8]) -> impl Iterator<Item = ([u8; 4], &[u8])> {
+    let mut pos = 0usize;
+    std::iter::from_fn(move || {
+        let (size, kind, header_len) = box_header(&buf[pos..])?;
+        let size = if size == 0 {
+            (buf.len() - pos) as u64
+        } else {
+            size
+        };
+        let end = pos.checked_add(size as usize)?;
+        if size < header_len || end > buf.len() {
+            return None;
+        }
+        let payload = &buf[pos + header_len as usize..end];
+        pos = end;
+        Some((kind, payload))
+    })
+}
+
+fn child<'a>(buf: &'a [u8], kind: &[u8; 4]) -> Option<&'a [u8]> {
+    children(buf).find(|(k, _)| k == kind).map(|(_, p)| p)
+}
+
+fn be_u32(buf: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(buf.get(at..at + 4)?.try_into().ok()?))
+}
+
+fn be_u64(buf: &[u8], at: usize) -> Option<u64> {
+    Some(u64::from_be_bytes(buf.get(at..at + 8)?.try_into().ok()?))
+}
+
+fn parse_moov(moov: &[u8], info: &mut StreamInfo) {
+    if let Some(mvhd) = child(moov, b"mvhd") {
+        // version(1) flags(3), then v0: ctime(4) mtime(4) timescale(4) duration(4)
+        //                            v1: ctime(8) mtime(8) timescale(4) duration(8)
+        let parsed = match mvhd.first() {
+            Some(1) => be_u32(mvhd, 20).zip(be_u64(mvhd, 24)),
+            Some(_) => be_u32(mvhd, 12).zip(be_u32(mvhd, 16).map(u64::from)),
+            None => None,
+        };
+        if let Some((timescale, duration)) = parsed {
+            if timescale > 0 && duration != u64::from(u32::MAX) {
+                info.duration_secs = Some(duration as f64 / timescale as f64);
+            }
+        }
+    }
+
+    for (kind, trak) in children(moov) {
+        if &kind != b"trak" {
+            continue;
+        }
+        let Some(mdia) = child(trak, b"mdia") else {
+            continue;
+        };
+        // hdlr: version/flags(4) pre_defined(4) handler_type(4)
+        let handler = child(mdia, b"hdlr").and_then(|h| h.get(8..12));
+        let codec = child(mdia, b"minf")
+            .and_then(|m| child(m, b"stbl"))
+            .and_then(|s| child(s, b"stsd"))
+            // stsd: version/flags(4) entry_count(4), then the first sample entry box
+            .and_then(|s| s.get(8..))
+            .and_then(|entries| children(entries).next())
+            .map(|(k, _)| String::from_utf8_lossy(&k).trim().to_string());
+        match handler {
+            Some(b"vide") => {
+                if info.video_codec.is_none() {
+                    info.video_codec = codec;
+                }
+                // tkhd ends with width/height as 16.16 fixed point
+                if let Some(tkhd) = child(trak, b"tkhd") {
+                    if tkhd.len() >= 8 {
+                        let w = be_u32(tkhd, tkhd.len() - 8).unwrap_or(0) >> 16;
+                        let h = be_u32(tkhd, tkhd.len() - 4).unwrap_or(0) >> 16;
+                        if w > 0 && h > 0 && info.width.is_none() {
+                            info.width = Some(w);
+                            info.height = Some(h);
+                        }
+                    }
+                }
+            }
+            Some(b"soun") if info.audio_codec.is_none() => info.audio_codec = codec,
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mp4_box(kind: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+        let mut out = ((payload.len() + 8) as u32).to_be_bytes().to_vec();
+        out.extend_from_slice(kind);
+        out.extend_from_slice(payload);
+        out
+    }
+
+    #[test]
+    fn parses_duration_resolution_and_codecs() {
+        let mut mvhd = vec![0u8; 12];
+        mvhd.extend_from_slice(&1000u32.to_be_bytes());
+        mvhd.extend_from_slice(&90_500u32.to_be_bytes());
+        mvhd.extend_from_slice(&[0u8; 80]);
+
+        let mut tkhd = vec![0u8; 76];
+        tkhd.extend_from_slice(&(1920u32 << 16).to_be_bytes());
+        tkhd.extend_from_slice(&(1080u32 << 16).to_be_bytes());
+
+        let track = |handler: &[u8; 4], entry: &[u8; 4], tkhd: Option<&[u8]>| {
+            let mut hdlr = vec![0u8; 8];
+            hdlr.extend_from_slice(handler);
+            hdlr.extend_from_slice(&[0u8; 12]);
+            let mut stsd = vec![0, 0, 0, 0, 0, 0, 0, 1];
+            stsd.extend(mp4_box(entry, &[0u8; 16]));
+            let stbl = mp4_box(b"stbl", &mp4_box(b"stsd", &stsd));
+            let minf = mp4_box(b"minf", &stbl);
+            let mut mdia = mp4_box(b"hdlr", &hdlr);
+            mdia.extend(minf);
+            let mut trak = tkhd.map(|t| mp4_box(b"tkhd", t)).unwrap_or_default();
+            trak.extend(mp4_box(b"mdia", &mdia));
+            mp4_box(b"trak", &trak)
+        };
+
+        let mut moov = mp4_box(b"mvhd", &mvhd);
+        moov.extend(track(b"vide", b"avc1", Some(&tkhd)));
+        moov.extend(track(b"soun", b"mp4a", None));
+
+        let mut info = StreamInfo::default();
+        parse_moov(&moov, &mut info);
+        assert_eq!(info.duration_secs, Some(90.5));
+        assert_eq!((info.width, info.height), (Some(1920), Some(1080)));
+        assert_eq!(info.video_codec.as_deref(), Some("avc1"));
+        assert_eq!(info.audio_codec.as_deref(), Some("mp4a"));
+    }
+}
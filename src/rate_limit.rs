@@ -0,0 +1,92 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Spaces out requests to the same host, shared by every plugin (and host-side downloads).
+/// An interval of zero disables limiting.
+pub struct HostRateLimiter {
+    interval_ms: AtomicU64,
+    // host -> earliest instant the next request may start
+    next_slot: Mutex<HashMap<String, Instant>>,
+}
+
+impl HostRateLimiter {
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval_ms: AtomicU64::new(interval.as_millis() as u64),
+            next_slot: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Build from `TOURING_HOST_RATE_LIMIT_MS` (unset/0 = disabled).
+    pub fn from_env() -> Self {
+        let ms = std::env::var("TOURING_HOST_RATE_LIMIT_MS")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(0);
+        Self::new(Duration::from_millis(ms))
+    }
+
+    pub fn interval(&self) -> Duration {
+        Duration::from_millis(self.interval_ms.load(Ordering::Relaxed))
+    }
+
+    pub fn set_interval(&self, interval: Duration) {
+        self.interval_ms
+            .store(interval.as_millis() as u64, Ordering::Relaxed);
+        if interval.is_zero() {
+            self.next_slot.lock().unwrap().clear();
+        }
+    }
+
+    /// Reserve the next request slot for `host`; returns how long the caller must wait.
+    pub fn reserve(&self, host: &str) -> Duration {
+        let interval = self.interval();
+        if interval.is_zero() {
+            return Duration::ZERO;
+        }
+        let host = host.to_ascii_lowercase();
+        let now = Instant::now();
+        let mut slots = self.next_slot.lock().unwrap();
+        let slot = slots
+            .get(&host)
+            .copied()
+            .filter(|t| *t > now)
+            .unwrap_or(now);
+        slots.insert(host, slot + interval);
+        slot - now
+    }
+
+    /// Wait until a request to `host` may be sent.
+    pub async fn acquire(&self, host: &str) {
+        let wait = self.reserve(host);
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disabled_limiter_never_waits() {
+        let limiter = HostRateLimiter::new(Duration::ZERO);
+        assert_eq!(limiter.reserve("a.com"), Duration::ZERO);
+        assert_eq!(limiter.reserve("a.com"), Duration::ZERO);
+    }
+
+    #[test]
+    fn same_host_requests_are_spaced() {
+        let limiter = HostRateLimiter::new(Duration::from_millis(500));
+        assert_eq!(limiter.reserve("cdn.example.com"), Duration::ZERO);
+        let second = limiter.reserve("CDN.example.com");
+        assert!(second > Duration::from_millis(400));
+        let third = limiter.reserve("cdn.example.com");
+        assert!(third > Duration::from_millis(900));
+        // Other hosts are independent
+        assert_eq!(limiter.reserve("other.example.com"), Duration::ZERO);
+    }
+}
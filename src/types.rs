@@ -1,5 +1,59 @@
 use crate::plugins::{Media, MediaType};
-use serde::{Deserialize, Serialize};
+use anyhow::Result;
+use base64::Engine as _;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+/// Encoding used for new cache payloads. Reads accept either codec (and legacy
+/// unprefixed JSON), so switching codecs never invalidates existing rows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum CacheCodec {
+    #[default]
+    Json,
+    /// MessagePack, base64-encoded so it fits the TEXT payload column.
+    MsgPack,
+}
+
+// Single-byte payload prefixes recording the codec
+const JSON_PREFIX: char = 'J';
+const MSGPACK_PREFIX: char = 'M';
+
+impl CacheCodec {
+    /// `TOURING_CACHE_CODEC=json|msgpack` (default json).
+    pub fn from_env() -> Self {
+        match std::env::var("TOURING_CACHE_CODEC")
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase()
+            .as_str()
+        {
+            "msgpack" | "messagepack" | "mp" => CacheCodec::MsgPack,
+            _ => CacheCodec::Json,
+        }
+    }
+
+    pub(crate) fn encode<T: Serialize>(&self, value: &T) -> Result<String> {
+        Ok(match self {
+            CacheCodec::Json => format!("{}{}", JSON_PREFIX, serde_json::to_string(value)?),
+            CacheCodec::MsgPack => {
+                let bytes = rmp_serde::to_vec(value)?;
+                let b64 = base64::engine::general_purpose::STANDARD_NO_PAD.encode(bytes);
+                format!("{}{}", MSGPACK_PREFIX, b64)
+            }
+        })
+    }
+}
+
+/// Decode a cache payload written by any codec; unprefixed payloads are legacy JSON.
+pub(crate) fn decode_cache<T: DeserializeOwned>(payload: &str) -> Option<T> {
+    if let Some(rest) = payload.strip_prefix(MSGPACK_PREFIX) {
+        let bytes = base64::engine::general_purpose::STANDARD_NO_PAD
+            .decode(rest)
+            .ok()?;
+        return rmp_serde::from_slice(&bytes).ok();
+    }
+    let json = payload.strip_prefix(JSON_PREFIX).unwrap_or(payload);
+    serde_json::from_str(json).ok()
+}
 
 #[derive(Serialize, Deserialize, Clone)]
 pub(crate) struct MediaCache {
@@ -49,3 +103,19 @@ pub(crate) fn media_from_cache(mc: MediaCache) -> Media {
         cover_url: mc.cover_url,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_every_codec_and_legacy_json() {
+        let urls = vec!["https://a/1.jpg".to_string(), "https://a/2.jpg".to_string()];
+        for codec in [CacheCodec::Json, CacheCodec::MsgPack] {
+            let payload = codec.encode(&urls).unwrap();
+            assert_eq!(decode_cache::<Vec<String>>(&payload), Some(urls.clone()));
+        }
+        let legacy = serde_json::to_string(&urls).unwrap();
+        assert_eq!(decode_cache::<Vec<String>>(&legacy), Some(urls));
+    }
+}